@@ -0,0 +1,309 @@
+//! Headless runner for saved requests and workspace folders, meant for CI.
+//!
+//! Exit codes: 0 when every request passed, 1 when any request failed or
+//! errored, 2 for usage and setup problems. A request without assertions
+//! fails on a 4xx or 5xx status.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use prism_lib::engine::pool::ClientPool;
+use prism_lib::env::{build_variable_map, EnvStore, EnvVar};
+use prism_lib::runner::{self, report, RunOptions, RunReport};
+use prism_lib::settings::{HttpSettings, SettingsStore};
+use prism_lib::workspace::{folder, load_request_from_file, postman};
+use prism_lib::workspace::models::SavedRequest;
+
+/// Must match `identifier` in tauri.conf.json, which names the app data folder
+const APP_IDENTIFIER: &str = "com.prism.dev";
+
+const USAGE: &str = "Usage: prism-cli run <PATH> [options]
+
+Runs a saved request file, or every request in a workspace folder.
+
+Options:
+  -e, --env <NAME|FILE>     Environment name from the desktop app, or an
+                            environment JSON file (Postman or {\"KEY\": \"value\"})
+  -v, --var <KEY=VALUE>     Set or override a variable (repeatable)
+  -o, --output <FORMAT>     Report format: text (default), json or junit
+      --output-file <FILE>  Write the report to a file instead of stdout
+      --stop-on-failure     Skip remaining requests after the first failure
+      --delay <MS>          Wait between requests, in milliseconds
+      --data-dir <DIR>      App data folder holding env.db and settings.db
+  -h, --help                Show this help";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Text,
+    Json,
+    Junit,
+}
+
+struct Args {
+    path: PathBuf,
+    env: Option<String>,
+    vars: Vec<(String, String)>,
+    output: OutputFormat,
+    output_file: Option<PathBuf>,
+    options: RunOptions,
+    data_dir: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1).collect()) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("Error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(args).await {
+        Ok(report) if report.success() => ExitCode::SUCCESS,
+        Ok(_) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+async fn run(args: Args) -> Result<RunReport, String> {
+    let items = load_items(&args.path)?;
+    if items.is_empty() {
+        return Err(format!("No requests found in {}", args.path.display()));
+    }
+
+    let mut variables = load_environment(args.env.as_deref(), args.data_dir.as_deref())?;
+    variables.extend(args.vars);
+    let settings = load_http_settings(args.data_dir.as_deref())?;
+    for entry in settings.tls.iter().filter(|t| t.skip_verification) {
+        eprintln!("Warning: TLS certificates are not verified for '{}'", entry.host);
+    }
+
+    let report = runner::run_requests(items, variables, &settings, &ClientPool::new(), &args.options, |_| {}).await;
+
+    let suite_name = args
+        .path
+        .file_stem()
+        .and_then(|n| n.to_str())
+        .unwrap_or("prism");
+    let rendered = match args.output {
+        OutputFormat::Text => report::to_text(&report),
+        OutputFormat::Json => serde_json::to_string_pretty(&report)
+            .map_err(|e| format!("Failed to serialize report: {}", e))?,
+        OutputFormat::Junit => report::to_junit(&report, suite_name),
+    };
+
+    match &args.output_file {
+        Some(file) => {
+            std::fs::write(file, rendered)
+                .map_err(|e| format!("Failed to write report: {}", e))?;
+            // Keep a short summary on the console when the report goes to a file
+            eprintln!(
+                "{} requests: {} passed, {} failed, {} errors, {} skipped",
+                report.total, report.passed, report.failed, report.errors, report.skipped
+            );
+        }
+        None => println!("{}", rendered.trim_end()),
+    }
+
+    Ok(report)
+}
+
+/// A folder runs every request below it; a file runs just that request
+fn load_items(path: &Path) -> Result<Vec<SavedRequest>, String> {
+    if path.is_dir() {
+        return Ok(folder::load_workspace(path)?.flatten_requests());
+    }
+
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| "Invalid file path".to_string())?;
+    let request = load_request_from_file(path_str)?;
+    let name = path
+        .file_stem()
+        .and_then(|n| n.to_str())
+        .unwrap_or(path_str)
+        .to_string();
+    Ok(vec![SavedRequest { name, request }])
+}
+
+/// Variables from an environment file, or a named environment (plus globals)
+/// from the desktop app's database
+fn load_environment(env: Option<&str>, data_dir: Option<&Path>) -> Result<HashMap<String, String>, String> {
+    let Some(env) = env else {
+        return Ok(HashMap::new());
+    };
+
+    let env_path = Path::new(env);
+    if env_path.is_file() {
+        let content = std::fs::read_to_string(env_path)
+            .map_err(|e| format!("Failed to read environment file: {}", e))?;
+        return Ok(build_variable_map(Vec::new(), parse_environment_file(&content)?));
+    }
+
+    let db_path = app_data_dir(data_dir)?.join("env.db");
+    if !db_path.is_file() {
+        return Err(format!(
+            "'{}' is not an environment file and no app database was found at {}",
+            env,
+            db_path.display()
+        ));
+    }
+
+    let db_str = db_path
+        .to_str()
+        .ok_or_else(|| "Invalid env database path".to_string())?;
+    let store = EnvStore::new(db_str)
+        .map_err(|e| format!("Failed to open env store: {}", e))?;
+    let environments = store
+        .list_environments()
+        .map_err(|e| format!("Failed to list environments: {}", e))?;
+    let environment = environments
+        .iter()
+        .find(|e| e.name == env)
+        .ok_or_else(|| {
+            let names: Vec<&str> = environments.iter().map(|e| e.name.as_str()).collect();
+            format!("Unknown environment '{}' (available: {})", env, names.join(", "))
+        })?;
+
+    let globals = store
+        .get_globals()
+        .map_err(|e| format!("Failed to load global vars: {}", e))?;
+    let vars = store
+        .get_vars(environment.id)
+        .map_err(|e| format!("Failed to load env vars: {}", e))?;
+    Ok(build_variable_map(globals, vars))
+}
+
+/// Client settings saved in the desktop app, or the defaults when there are none
+fn load_http_settings(data_dir: Option<&Path>) -> Result<HttpSettings, String> {
+    let db_path = app_data_dir(data_dir)?.join("settings.db");
+    if !db_path.is_file() {
+        return Ok(HttpSettings::default());
+    }
+    let db_str = db_path
+        .to_str()
+        .ok_or_else(|| "Invalid settings database path".to_string())?;
+    SettingsStore::new(db_str)?.get_http_settings()
+}
+
+fn app_data_dir(data_dir: Option<&Path>) -> Result<PathBuf, String> {
+    match data_dir {
+        Some(dir) => Ok(dir.to_path_buf()),
+        None => Ok(dirs::data_dir()
+            .ok_or_else(|| "Could not determine the app data directory, use --data-dir".to_string())?
+            .join(APP_IDENTIFIER)),
+    }
+}
+
+/// Accepts a Postman environment export or a flat JSON object
+fn parse_environment_file(content: &str) -> Result<Vec<EnvVar>, String> {
+    let root: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| format!("Failed to parse environment file: {}", e))?;
+
+    if root.get("values").is_some() {
+        let (_, vars, report) = postman::import_environment(content)?;
+        for warning in report.warnings {
+            eprintln!("Warning: {}", warning);
+        }
+        return Ok(vars);
+    }
+
+    let object = root
+        .as_object()
+        .ok_or_else(|| "Environment file must be a JSON object".to_string())?;
+    Ok(object
+        .iter()
+        .map(|(key, value)| EnvVar {
+            key: key.clone(),
+            value: match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            },
+            enabled: true,
+        })
+        .collect())
+}
+
+/// Returns None when help was requested
+fn parse_args(raw: Vec<String>) -> Result<Option<Args>, String> {
+    let mut iter = raw.into_iter();
+    match iter.next().as_deref() {
+        Some("run") => {}
+        Some("-h") | Some("--help") | None => return Ok(None),
+        Some(other) => return Err(format!("Unknown command '{}'", other)),
+    }
+
+    let mut path = None;
+    let mut env = None;
+    let mut vars = Vec::new();
+    let mut output = OutputFormat::Text;
+    let mut output_file = None;
+    let mut options = RunOptions::default();
+    let mut data_dir = None;
+
+    while let Some(arg) = iter.next() {
+        // Support both `--flag value` and `--flag=value`
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = |name: &str| {
+            inline
+                .clone()
+                .or_else(|| iter.next())
+                .ok_or_else(|| format!("{} requires a value", name))
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-e" | "--env" => env = Some(value("--env")?),
+            "-v" | "--var" => {
+                let pair = value("--var")?;
+                let (key, val) = pair
+                    .split_once('=')
+                    .ok_or_else(|| format!("Invalid --var '{}', expected KEY=VALUE", pair))?;
+                vars.push((key.trim().to_string(), val.to_string()));
+            }
+            "-o" | "--output" => {
+                output = match value("--output")?.to_lowercase().as_str() {
+                    "text" => OutputFormat::Text,
+                    "json" => OutputFormat::Json,
+                    "junit" => OutputFormat::Junit,
+                    other => return Err(format!("Unknown output format '{}'", other)),
+                }
+            }
+            "--output-file" => output_file = Some(PathBuf::from(value("--output-file")?)),
+            "--stop-on-failure" => options.stop_on_failure = true,
+            "--delay" => {
+                let delay = value("--delay")?;
+                options.delay_ms = delay
+                    .parse()
+                    .map_err(|_| format!("Invalid --delay '{}'", delay))?;
+            }
+            "--data-dir" => data_dir = Some(PathBuf::from(value("--data-dir")?)),
+            _ if flag.starts_with('-') => return Err(format!("Unknown option '{}'", flag)),
+            _ if path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Unexpected argument '{}'", arg)),
+        }
+    }
+
+    let path = path.ok_or_else(|| "Missing <PATH> to run".to_string())?;
+    Ok(Some(Args {
+        path,
+        env,
+        vars,
+        output,
+        output_file,
+        options,
+        data_dir,
+    }))
+}
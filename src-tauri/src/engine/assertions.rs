@@ -0,0 +1,273 @@
+use crate::models::{ApiResponse, Assertion, AssertionCheck, AssertionResult};
+use regex::Regex;
+use serde_json::Value;
+
+/// Runs every enabled assertion against a response
+pub fn evaluate(assertions: &[Assertion], response: &ApiResponse) -> Vec<AssertionResult> {
+    assertions
+        .iter()
+        .filter(|a| a.enabled)
+        .map(|assertion| {
+            let (passed, message) = check(&assertion.check, response);
+            AssertionResult {
+                assertion: assertion.clone(),
+                passed,
+                message,
+            }
+        })
+        .collect()
+}
+
+fn check(check: &AssertionCheck, response: &ApiResponse) -> (bool, String) {
+    match check {
+        AssertionCheck::StatusEquals { value } => (
+            response.status == *value,
+            format!("Expected status {}, got {}", value, response.status),
+        ),
+        AssertionCheck::StatusInRange { min, max } => (
+            (*min..=*max).contains(&response.status),
+            format!("Expected status in {}..={}, got {}", min, max, response.status),
+        ),
+        AssertionCheck::HeaderPresent { name } => match find_header(response, name) {
+            Some(value) => (true, format!("Header '{}' is '{}'", name, value)),
+            None => (false, format!("Header '{}' is missing", name)),
+        },
+        AssertionCheck::HeaderMatches { name, pattern } => match (find_header(response, name), Regex::new(pattern)) {
+            (_, Err(e)) => (false, format!("Invalid pattern '{}': {}", pattern, e)),
+            (None, _) => (false, format!("Header '{}' is missing", name)),
+            (Some(value), Ok(re)) => (
+                re.is_match(value),
+                format!("Header '{}' is '{}', expected to match /{}/", name, value, pattern),
+            ),
+        },
+        AssertionCheck::JsonPathEquals { path, value } => match response.body.json().and_then(|root| json_path(root, path)) {
+            Some(actual) => (
+                json_equals(actual, value),
+                format!("{} is {}, expected {}", path, actual, value),
+            ),
+            None => (false, format!("{} does not exist", path)),
+        },
+        AssertionCheck::JsonPathExists { path } => match response.body.json().and_then(|root| json_path(root, path)) {
+            Some(actual) => (true, format!("{} is {}", path, actual)),
+            None => (false, format!("{} does not exist", path)),
+        },
+        AssertionCheck::JsonPathType { path, expected } => match response.body.json().and_then(|root| json_path(root, path)) {
+            Some(actual) => {
+                let actual_type = json_type(actual);
+                (
+                    actual_type == expected.to_lowercase(),
+                    format!("{} is {}, expected {}", path, actual_type, expected),
+                )
+            }
+            None => (false, format!("{} does not exist", path)),
+        },
+        AssertionCheck::BodyContains { value } => {
+            if body_text(response).contains(value.as_str()) {
+                (true, format!("Body contains '{}'", value))
+            } else {
+                (false, format!("Body does not contain '{}'", value))
+            }
+        }
+        AssertionCheck::BodyMatches { pattern } => match Regex::new(pattern) {
+            Ok(re) if re.is_match(&body_text(response)) => (true, format!("Body matches /{}/", pattern)),
+            Ok(_) => (false, format!("Body does not match /{}/", pattern)),
+            Err(e) => (false, format!("Invalid pattern '{}': {}", pattern, e)),
+        },
+        AssertionCheck::ResponseTimeBelow { ms } => (
+            response.time < *ms,
+            format!("Response time {} ms, expected below {} ms", response.time, ms),
+        ),
+        AssertionCheck::SizeBelow { bytes } => (
+            response.size_bytes < *bytes,
+            format!("Size {} bytes, expected below {} bytes", response.size_bytes, bytes),
+        ),
+    }
+}
+
+fn find_header<'a>(response: &'a ApiResponse, name: &str) -> Option<&'a str> {
+    response
+        .headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Binary bodies have no text to match against
+fn body_text(response: &ApiResponse) -> std::borrow::Cow<'_, str> {
+    response.body.text().unwrap_or_default()
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Numbers compare by value so 1 equals 1.0
+fn json_equals(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        // Allow a string expectation to match a scalar, e.g. "42" vs 42
+        (Value::Number(a), Value::String(s)) => s.trim().parse::<f64>().ok() == a.as_f64(),
+        (Value::Bool(a), Value::String(s)) => s.trim().parse::<bool>().ok() == Some(*a),
+        _ => actual == expected,
+    }
+}
+
+/// Minimal JSONPath: `$.data.items[0].name`, `$['key with space']`, `items[2]`
+pub fn json_path<'a>(root: &'a Value, path: &str) -> Option<&'a Value> {
+    let path = path.trim();
+    let path = path.strip_prefix('$').unwrap_or(path);
+
+    let mut current = root;
+    let mut chars = path.chars().peekable();
+    while let Some(&ch) = chars.peek() {
+        match ch {
+            '.' => {
+                chars.next();
+            }
+            '[' => {
+                chars.next();
+                let mut segment = String::new();
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    segment.push(c);
+                }
+                let segment = segment.trim();
+                let quoted = segment
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| segment.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+                current = match quoted {
+                    Some(key) => current.get(key)?,
+                    None => {
+                        let index: i64 = segment.parse().ok()?;
+                        let array = current.as_array()?;
+                        // Negative indexes count from the end
+                        let index = if index < 0 { array.len() as i64 + index } else { index };
+                        array.get(usize::try_from(index).ok()?)?
+                    }
+                };
+            }
+            _ => {
+                let mut key = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c == '.' || c == '[' {
+                        break;
+                    }
+                    key.push(c);
+                    chars.next();
+                }
+                current = current.get(key.as_str())?;
+            }
+        }
+    }
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ResponseBody;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn response(body: Value) -> ApiResponse {
+        let mut headers = HashMap::new();
+        headers.insert("content-type".to_string(), "application/json; charset=utf-8".to_string());
+        ApiResponse {
+            status: 201,
+            status_text: "Created".to_string(),
+            time: 120,
+            size: "0.05".to_string(),
+            size_bytes: 52,
+            headers,
+            body: match body {
+                Value::String(text) => ResponseBody::Text { text, charset: "utf-8".to_string() },
+                value => ResponseBody::Json { value },
+            },
+            ..Default::default()
+        }
+    }
+
+    fn assertion(check: AssertionCheck) -> Assertion {
+        Assertion { enabled: true, check }
+    }
+
+    #[test]
+    fn test_json_path() {
+        let body = json!({"data": {"items": [{"name": "a"}, {"name": "b"}], "odd key": 1}});
+        assert_eq!(json_path(&body, "$.data.items[1].name"), Some(&json!("b")));
+        assert_eq!(json_path(&body, "data.items[-1].name"), Some(&json!("b")));
+        assert_eq!(json_path(&body, "$.data['odd key']"), Some(&json!(1)));
+        assert_eq!(json_path(&body, "$"), Some(&body));
+        assert_eq!(json_path(&body, "$.data.items[5]"), None);
+        assert_eq!(json_path(&body, "$.missing"), None);
+    }
+
+    #[test]
+    fn test_status_and_headers() {
+        let res = response(json!({}));
+        let results = evaluate(
+            &[
+                assertion(AssertionCheck::StatusEquals { value: 201 }),
+                assertion(AssertionCheck::StatusInRange { min: 200, max: 299 }),
+                assertion(AssertionCheck::HeaderPresent { name: "Content-Type".to_string() }),
+                assertion(AssertionCheck::HeaderMatches { name: "content-type".to_string(), pattern: "^text/".to_string() }),
+            ],
+            &res,
+        );
+        let passed: Vec<bool> = results.iter().map(|r| r.passed).collect();
+        assert_eq!(passed, vec![true, true, true, false]);
+    }
+
+    #[test]
+    fn test_body_checks() {
+        let res = response(json!({"id": 42, "tags": ["x"], "name": "prism"}));
+        let results = evaluate(
+            &[
+                assertion(AssertionCheck::JsonPathEquals { path: "$.id".to_string(), value: json!(42.0) }),
+                assertion(AssertionCheck::JsonPathEquals { path: "$.id".to_string(), value: json!("42") }),
+                assertion(AssertionCheck::JsonPathExists { path: "$.tags[0]".to_string() }),
+                assertion(AssertionCheck::JsonPathType { path: "$.tags".to_string(), expected: "array".to_string() }),
+                assertion(AssertionCheck::BodyContains { value: "prism".to_string() }),
+                assertion(AssertionCheck::BodyMatches { pattern: r#""id":\d+"#.to_string() }),
+                assertion(AssertionCheck::ResponseTimeBelow { ms: 500 }),
+                assertion(AssertionCheck::SizeBelow { bytes: 10 }),
+            ],
+            &res,
+        );
+        let passed: Vec<bool> = results.iter().map(|r| r.passed).collect();
+        assert_eq!(passed, vec![true, true, true, true, true, true, true, false]);
+        assert_eq!(results[7].message, "Size 52 bytes, expected below 10 bytes");
+    }
+
+    #[test]
+    fn test_disabled_and_invalid() {
+        let res = response(json!("plain text"));
+        let results = evaluate(
+            &[
+                Assertion { enabled: false, check: AssertionCheck::StatusEquals { value: 500 } },
+                assertion(AssertionCheck::BodyMatches { pattern: "(".to_string() }),
+            ],
+            &res,
+        );
+        assert_eq!(results.len(), 1);
+        assert!(!results[0].passed);
+        assert!(results[0].message.starts_with("Invalid pattern"));
+    }
+
+    #[test]
+    fn test_assertion_serde_shape() {
+        let parsed: Assertion = serde_json::from_str(r#"{"type": "jsonPathEquals", "path": "$.ok", "value": true}"#).unwrap();
+        assert!(parsed.enabled);
+        assert!(matches!(parsed.check, AssertionCheck::JsonPathEquals { .. }));
+    }
+}
@@ -0,0 +1,314 @@
+use crate::models::AuthPayload;
+use md5::{Digest, Md5};
+use rand::Rng;
+use reqwest::header::HeaderMap;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Md5,
+    Md5Sess,
+    Sha256,
+    Sha256Sess,
+}
+
+impl Algorithm {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_uppercase().as_str() {
+            "MD5" => Some(Algorithm::Md5),
+            "MD5-SESS" => Some(Algorithm::Md5Sess),
+            "SHA-256" => Some(Algorithm::Sha256),
+            "SHA-256-SESS" => Some(Algorithm::Sha256Sess),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Algorithm::Md5 => "MD5",
+            Algorithm::Md5Sess => "MD5-sess",
+            Algorithm::Sha256 => "SHA-256",
+            Algorithm::Sha256Sess => "SHA-256-sess",
+        }
+    }
+
+    fn hash(self, data: &str) -> String {
+        let digest = match self {
+            Algorithm::Md5 | Algorithm::Md5Sess => Md5::digest(data.as_bytes()).to_vec(),
+            Algorithm::Sha256 | Algorithm::Sha256Sess => {
+                ring::digest::digest(&ring::digest::SHA256, data.as_bytes()).as_ref().to_vec()
+            }
+        };
+        digest.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+/// A `WWW-Authenticate: Digest ...` challenge
+#[derive(Debug, Clone, PartialEq)]
+pub struct Challenge {
+    pub realm: String,
+    pub nonce: String,
+    pub opaque: Option<String>,
+    pub algorithm: Algorithm,
+    /// Whether the server sent an algorithm parameter (echoed back if so)
+    pub explicit_algorithm: bool,
+    /// Whether the server offered qop=auth; without it the RFC 2069 form is used
+    pub qop_auth: bool,
+    pub stale: bool,
+}
+
+impl Challenge {
+    /// The strongest supported Digest challenge among the response's
+    /// WWW-Authenticate headers, if any
+    pub fn from_headers(headers: &HeaderMap) -> Option<Challenge> {
+        headers
+            .get_all(reqwest::header::WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(parse_schemes)
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("digest"))
+            .filter_map(|(_, params)| Challenge::from_params(&params))
+            .max_by_key(|challenge| matches!(challenge.algorithm, Algorithm::Sha256 | Algorithm::Sha256Sess))
+    }
+
+    fn from_params(params: &HashMap<String, String>) -> Option<Challenge> {
+        let algorithm = match params.get("algorithm") {
+            Some(name) => Algorithm::parse(name)?,
+            None => Algorithm::Md5,
+        };
+        // Only qop=auth is supported; a server insisting on auth-int can't be answered
+        let qop_auth = match params.get("qop") {
+            Some(qop) => qop.split(',').any(|q| q.trim().eq_ignore_ascii_case("auth")).then_some(true)?,
+            None => false,
+        };
+        Some(Challenge {
+            realm: params.get("realm").cloned().unwrap_or_default(),
+            nonce: params.get("nonce")?.clone(),
+            opaque: params.get("opaque").cloned(),
+            algorithm,
+            explicit_algorithm: params.contains_key("algorithm"),
+            qop_auth,
+            stale: params.get("stale").is_some_and(|s| s.eq_ignore_ascii_case("true")),
+        })
+    }
+
+    /// The Authorization header answering this challenge for `method` on `uri`
+    /// (the request target: path and query), as the `nc`-th use of the nonce
+    pub fn authorization(&self, username: &str, password: &str, method: &str, uri: &str, nc: u32, cnonce: &str) -> String {
+        let algorithm = self.algorithm;
+        let mut ha1 = algorithm.hash(&format!("{}:{}:{}", username, self.realm, password));
+        if matches!(algorithm, Algorithm::Md5Sess | Algorithm::Sha256Sess) {
+            ha1 = algorithm.hash(&format!("{}:{}:{}", ha1, self.nonce, cnonce));
+        }
+        let ha2 = algorithm.hash(&format!("{}:{}", method.to_uppercase(), uri));
+        let nc = format!("{:08x}", nc);
+        let response = if self.qop_auth {
+            algorithm.hash(&format!("{}:{}:{}:{}:auth:{}", ha1, self.nonce, nc, cnonce, ha2))
+        } else {
+            algorithm.hash(&format!("{}:{}:{}", ha1, self.nonce, ha2))
+        };
+
+        let mut header = format!(
+            "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\"",
+            quote(username),
+            quote(&self.realm),
+            quote(&self.nonce),
+            quote(uri)
+        );
+        if self.explicit_algorithm {
+            header.push_str(&format!(", algorithm={}", algorithm.name()));
+        }
+        header.push_str(&format!(", response=\"{}\"", response));
+        if self.qop_auth {
+            header.push_str(&format!(", qop=auth, nc={}, cnonce=\"{}\"", nc, quote(cnonce)));
+        }
+        if let Some(opaque) = &self.opaque {
+            header.push_str(&format!(", opaque=\"{}\"", quote(opaque)));
+        }
+        header
+    }
+}
+
+fn quote(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Splits a WWW-Authenticate value into its challenges, e.g.
+/// `Basic realm="a", Digest realm="b", nonce="c"` into two schemes with their params
+fn parse_schemes(value: &str) -> Vec<(String, HashMap<String, String>)> {
+    let mut schemes: Vec<(String, HashMap<String, String>)> = Vec::new();
+    let mut chars = value.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+        let mut token = String::new();
+        while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != ',' && *c != '=') {
+            token.push(c);
+        }
+        if token.is_empty() {
+            if chars.next().is_none() {
+                break;
+            }
+            continue;
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        if chars.next_if_eq(&'=').is_none() {
+            schemes.push((token, HashMap::new()));
+            continue;
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let mut value = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' => value.extend(chars.next()),
+                    c => value.push(c),
+                }
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| *c != ',') {
+                value.push(c);
+            }
+        }
+        if let Some((_, params)) = schemes.last_mut() {
+            params.insert(token.to_ascii_lowercase(), value.trim_end().to_string());
+        }
+    }
+    schemes
+}
+
+/// The last challenge answered per origin and user, with its nonce count,
+/// so later requests can authenticate up-front instead of taking a 401 first
+struct Session {
+    challenge: Challenge,
+    nc: u32,
+}
+
+fn sessions() -> &'static Mutex<HashMap<String, Session>> {
+    static SESSIONS: OnceLock<Mutex<HashMap<String, Session>>> = OnceLock::new();
+    SESSIONS.get_or_init(Default::default)
+}
+
+fn credentials(auth: &AuthPayload) -> (&str, &str) {
+    (auth.username.as_deref().unwrap_or(""), auth.password.as_deref().unwrap_or(""))
+}
+
+fn session_key(auth: &AuthPayload, url: &url::Url) -> String {
+    format!("{}|{}", url.origin().ascii_serialization(), credentials(auth).0)
+}
+
+fn request_target(url: &url::Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
+}
+
+fn cnonce() -> String {
+    format!("{:016x}", rand::thread_rng().gen::<u64>())
+}
+
+/// Authorization for a request to an origin that already challenged us,
+/// reusing its nonce with the next count
+pub fn preemptive(auth: &AuthPayload, method: &str, url: &url::Url) -> Option<String> {
+    let mut sessions = sessions().lock().unwrap();
+    let session = sessions.get_mut(&session_key(auth, url))?;
+    session.nc += 1;
+    let (username, password) = credentials(auth);
+    Some(session.challenge.authorization(username, password, method, &request_target(url), session.nc, &cnonce()))
+}
+
+/// Authorization answering a 401's Digest challenge, remembered for later
+/// requests. None if the response carries no usable challenge.
+pub fn respond(auth: &AuthPayload, method: &str, url: &url::Url, headers: &HeaderMap) -> Option<String> {
+    let challenge = Challenge::from_headers(headers)?;
+    let (username, password) = credentials(auth);
+    let authorization = challenge.authorization(username, password, method, &request_target(url), 1, &cnonce());
+    sessions().lock().unwrap().insert(session_key(auth, url), Session { challenge, nc: 1 });
+    Some(authorization)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(values: &[&'static str]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for value in values {
+            map.append(reqwest::header::WWW_AUTHENTICATE, HeaderValue::from_static(value));
+        }
+        map
+    }
+
+    #[test]
+    fn test_rfc2617_md5() {
+        let challenge = Challenge::from_headers(&headers(&[
+            r#"Digest realm="testrealm@host.com", qop="auth,auth-int", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093", opaque="5ccc069c403ebaf9f0171e9517f40e41""#,
+        ]))
+        .unwrap();
+        let header = challenge.authorization("Mufasa", "Circle Of Life", "GET", "/dir/index.html", 1, "0a4f113b");
+        assert_eq!(
+            header,
+            r#"Digest username="Mufasa", realm="testrealm@host.com", nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093", uri="/dir/index.html", response="6629fae49393a05397450978507c4ef1", qop=auth, nc=00000001, cnonce="0a4f113b", opaque="5ccc069c403ebaf9f0171e9517f40e41""#
+        );
+    }
+
+    #[test]
+    fn test_rfc7616_prefers_sha256() {
+        let challenge = Challenge::from_headers(&headers(&[
+            r#"Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=MD5, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#,
+            r#"Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=SHA-256, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#,
+        ]))
+        .unwrap();
+        assert_eq!(challenge.algorithm, Algorithm::Sha256);
+        let cnonce = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+        let header = challenge.authorization("Mufasa", "Circle of Life", "GET", "/dir/index.html", 1, cnonce);
+        assert!(header.contains("algorithm=SHA-256, response=\"753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1\""));
+
+        let md5 = Challenge { algorithm: Algorithm::Md5, ..challenge };
+        let header = md5.authorization("Mufasa", "Circle of Life", "GET", "/dir/index.html", 1, cnonce);
+        assert!(header.contains("response=\"8ca523f5e9506fed4657c9700eebdbec\""));
+    }
+
+    #[test]
+    fn test_parse_mixed_schemes() {
+        let schemes = parse_schemes(r#"Basic realm="basic", Digest realm="a \"quoted\" realm", nonce=abc, stale=TRUE"#);
+        assert_eq!(schemes.len(), 2);
+        assert_eq!(schemes[0].0, "Basic");
+        assert_eq!(schemes[1].1["realm"], r#"a "quoted" realm"#);
+
+        let challenge = Challenge::from_params(&schemes[1].1).unwrap();
+        assert!(challenge.stale && !challenge.qop_auth && !challenge.explicit_algorithm);
+        // RFC 2069 form: no qop, nc or cnonce
+        let header = challenge.authorization("u", "p", "GET", "/", 1, "c");
+        assert!(!header.contains("qop=") && !header.contains("nc="));
+        assert!(header.contains(r#"realm="a \"quoted\" realm""#));
+    }
+
+    #[test]
+    fn test_unsupported_challenges() {
+        assert!(Challenge::from_headers(&headers(&[r#"Basic realm="x""#])).is_none());
+        assert!(Challenge::from_headers(&headers(&[r#"Digest realm="x", nonce="n", algorithm=SHA-512-256"#])).is_none());
+        assert!(Challenge::from_headers(&headers(&[r#"Digest realm="x", nonce="n", qop="auth-int""#])).is_none());
+    }
+
+    #[test]
+    fn test_nonce_count_increments() {
+        let auth = AuthPayload {
+            auth_type: "digest".to_string(),
+            username: Some("counter".to_string()),
+            password: Some("pw".to_string()),
+            ..Default::default()
+        };
+        let url = url::Url::parse("http://device.local/status?verbose=1").unwrap();
+        assert!(preemptive(&auth, "GET", &url).is_none());
+
+        let first = respond(&auth, "GET", &url, &headers(&[r#"Digest realm="r", nonce="n", qop="auth""#])).unwrap();
+        assert!(first.contains("uri=\"/status?verbose=1\"") && first.contains("nc=00000001"));
+        let next = preemptive(&auth, "POST", &url.join("/other").unwrap()).unwrap();
+        assert!(next.contains("uri=\"/other\"") && next.contains("nc=00000002"));
+    }
+}
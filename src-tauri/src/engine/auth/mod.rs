@@ -3,6 +3,10 @@ use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use std::str::FromStr;
 use base64::Engine;
 
+pub mod awsv4;
+pub mod digest;
+pub mod oauth2;
+
 pub fn apply_auth(
     auth: &AuthPayload,
     headers: &mut HeaderMap,
@@ -60,3 +64,19 @@ pub fn apply_auth(
     }
     Ok(())
 }
+
+/// Signs the final request for auth types that cover the whole request
+/// ("awsv4"); runs after every header and the body are in place.
+pub fn sign_request(
+    auth: &AuthPayload,
+    method: &str,
+    url: &url::Url,
+    headers: &mut HeaderMap,
+    body: &[u8],
+) -> Result<(), String> {
+    if auth.auth_type == "awsv4" {
+        let config = auth.awsv4.as_ref().ok_or("AWS Signature v4 is selected but not configured")?;
+        awsv4::sign(config, method, url, headers, body, chrono::Utc::now())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,238 @@
+use crate::models::ResponseBody;
+use base64::Engine;
+use encoding_rs::{Encoding, UTF_8};
+
+/// Decodes a response body according to its Content-Type header.
+/// Text is decoded with the declared charset (or a BOM); bodies that aren't
+/// text, or don't decode cleanly, are kept byte for byte as base64.
+pub fn decode(bytes: &[u8], content_type: Option<&str>) -> ResponseBody {
+    let (mime, charset) = parse_content_type(content_type.unwrap_or(""));
+
+    let textual = match mime.as_str() {
+        "" => !bytes.contains(&0) && std::str::from_utf8(bytes).is_ok(),
+        mime => charset.is_some() || is_text_mime(mime),
+    };
+    if textual {
+        if let Some((text, charset)) = decode_text(bytes, charset.as_deref()) {
+            // JSON types, and bodies with no Content-Type, are parsed as JSON when they can be
+            if (mime.is_empty() || is_json_mime(&mime)) && !text.trim().is_empty() {
+                if let Ok(value) = serde_json::from_str(&text) {
+                    return ResponseBody::Json { value };
+                }
+            }
+            return ResponseBody::Text { text, charset };
+        }
+    }
+
+    ResponseBody::Binary {
+        base64: base64::engine::general_purpose::STANDARD.encode(bytes),
+        mime: if mime.is_empty() { "application/octet-stream".to_string() } else { mime },
+    }
+}
+
+/// True for bodies decoded as text: a textual mime type, a declared charset,
+/// or no Content-Type at all
+pub fn is_text_content_type(content_type: &str) -> bool {
+    let (mime, charset) = parse_content_type(content_type);
+    mime.is_empty() || charset.is_some() || is_text_mime(&mime)
+}
+
+/// Lowercased mime type and charset parameter
+fn parse_content_type(content_type: &str) -> (String, Option<String>) {
+    let mut parts = content_type.split(';');
+    let mime = parts.next().unwrap_or("").trim().to_ascii_lowercase();
+    let charset = parts.find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"').to_ascii_lowercase())
+    });
+    (mime, charset)
+}
+
+fn is_json_mime(mime: &str) -> bool {
+    mime.split_once('/')
+        .is_some_and(|(_, subtype)| subtype == "json" || subtype.ends_with("+json"))
+}
+
+fn is_text_mime(mime: &str) -> bool {
+    let Some((kind, subtype)) = mime.split_once('/') else {
+        return false;
+    };
+    kind == "text"
+        || subtype.ends_with("+json")
+        || subtype.ends_with("+xml")
+        || matches!(
+            subtype,
+            "json" | "xml" | "javascript" | "ecmascript" | "x-www-form-urlencoded" | "graphql" | "yaml" | "x-yaml" | "x-ndjson"
+        )
+}
+
+/// Decodes text, returning it with the name of the charset used.
+/// A byte order mark wins over the declared charset; unknown charsets are
+/// tried as UTF-8. None when the bytes aren't valid in that charset.
+fn decode_text(bytes: &[u8], declared: Option<&str>) -> Option<(String, String)> {
+    let (encoding, text) = match Encoding::for_bom(bytes) {
+        Some((encoding, bom_length)) => (encoding, &bytes[bom_length..]),
+        None => (declared.and_then(|label| Encoding::for_label(label.as_bytes())).unwrap_or(UTF_8), bytes),
+    };
+    let text = encoding.decode_without_bom_handling_and_without_replacement(text)?;
+    Some((text.into_owned(), encoding.name().to_ascii_lowercase()))
+}
+
+/// Mime types and their usual file extension, for saving and uploading files
+const KNOWN_TYPES: &[(&str, &str)] = &[
+    ("application/json", "json"),
+    ("application/xml", "xml"),
+    ("text/xml", "xml"),
+    ("text/html", "html"),
+    ("text/plain", "txt"),
+    ("text/csv", "csv"),
+    ("text/css", "css"),
+    ("application/javascript", "js"),
+    ("text/javascript", "js"),
+    ("application/yaml", "yaml"),
+    ("application/pdf", "pdf"),
+    ("application/zip", "zip"),
+    ("application/gzip", "gz"),
+    ("application/x-protobuf", "pb"),
+    ("application/protobuf", "pb"),
+    ("image/png", "png"),
+    ("image/jpeg", "jpg"),
+    ("image/gif", "gif"),
+    ("image/webp", "webp"),
+    ("image/svg+xml", "svg"),
+    ("audio/mpeg", "mp3"),
+    ("video/mp4", "mp4"),
+];
+
+/// File extension suggested when saving a body of this mime type
+pub fn file_extension(mime: &str) -> &'static str {
+    let (mime, _) = parse_content_type(mime);
+    if let Some((_, extension)) = KNOWN_TYPES.iter().find(|(known, _)| *known == mime) {
+        return extension;
+    }
+    match mime.as_str() {
+        m if m.ends_with("+json") => "json",
+        m if m.ends_with("+xml") => "xml",
+        m if m.starts_with("text/") => "txt",
+        _ => "bin",
+    }
+}
+
+/// Content-Type guessed from a file's extension when uploading it
+pub fn mime_for_path(path: &str) -> &'static str {
+    let extension = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let extension = match extension.as_str() {
+        "jpeg" => "jpg",
+        "htm" => "html",
+        "yml" => "yaml",
+        other => other,
+    };
+    KNOWN_TYPES
+        .iter()
+        .find(|(_, known)| *known == extension)
+        .map(|(mime, _)| *mime)
+        .unwrap_or("application/octet-stream")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_and_text() {
+        assert_eq!(
+            decode(br#"{"ok":true}"#, Some("application/json")),
+            ResponseBody::Json { value: json!({"ok": true}) }
+        );
+        // No content type: valid UTF-8 is still sniffed as JSON or text
+        assert_eq!(decode(b"[1,2]", None), ResponseBody::Json { value: json!([1, 2]) });
+        assert_eq!(
+            decode(b"hello", Some("text/plain")),
+            ResponseBody::Text { text: "hello".to_string(), charset: "utf-8".to_string() }
+        );
+        assert_eq!(decode(b"", None), ResponseBody::default());
+
+        // Text labelled as something other than JSON stays text
+        assert_eq!(
+            decode(b"42", Some("text/plain")),
+            ResponseBody::Text { text: "42".to_string(), charset: "utf-8".to_string() }
+        );
+        assert!(matches!(decode(br#"{"a":1}"#, Some("text/html")), ResponseBody::Text { .. }));
+        assert!(matches!(decode(br#"{"a":1}"#, Some("application/problem+json")), ResponseBody::Json { .. }));
+    }
+
+    #[test]
+    fn test_declared_charsets() {
+        // Latin-1 labels decode as windows-1252, as in browsers
+        assert_eq!(
+            decode(b"caf\xe9", Some("text/plain; charset=ISO-8859-1")),
+            ResponseBody::Text { text: "café".to_string(), charset: "windows-1252".to_string() }
+        );
+        assert_eq!(
+            decode(b"\x82\xb1\x82\xf1", Some("text/plain; charset=Shift_JIS")),
+            ResponseBody::Text { text: "こん".to_string(), charset: "shift_jis".to_string() }
+        );
+        assert_eq!(
+            decode(b"\xcf\xf0\xe8\xe2\xe5\xf2", Some("text/html; charset=cp1251")),
+            ResponseBody::Text { text: "Привет".to_string(), charset: "windows-1251".to_string() }
+        );
+        assert_eq!(
+            decode(b"\x93hi\x94 \x80", Some("text/plain; charset=\"windows-1252\"")),
+            ResponseBody::Text { text: "“hi” €".to_string(), charset: "windows-1252".to_string() }
+        );
+        assert_eq!(
+            decode(b"\xff\xfeh\x00i\x00", Some("text/plain")),
+            ResponseBody::Text { text: "hi".to_string(), charset: "utf-16le".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_binary_is_kept_intact() {
+        let png = b"\x89PNG\r\n\x1a\n\x00\x00";
+        let body = decode(png, Some("image/png"));
+        let ResponseBody::Binary { base64, mime } = body else {
+            panic!("expected binary body");
+        };
+        assert_eq!(mime, "image/png");
+        assert_eq!(base64::engine::general_purpose::STANDARD.decode(base64).unwrap(), png);
+
+        // Text that doesn't decode in its charset isn't mangled either
+        assert!(matches!(decode(b"ok\xff", Some("text/plain")), ResponseBody::Binary { .. }));
+        assert!(matches!(
+            decode(b"\x00\x01", None),
+            ResponseBody::Binary { mime, .. } if mime == "application/octet-stream"
+        ));
+    }
+
+    #[test]
+    fn test_file_extension() {
+        assert_eq!(file_extension("image/png"), "png");
+        assert_eq!(file_extension("application/problem+json; charset=utf-8"), "json");
+        assert_eq!(file_extension("application/octet-stream"), "bin");
+        assert_eq!(mime_for_path("/tmp/Photo.JPEG"), "image/jpeg");
+        assert_eq!(mime_for_path("notes"), "application/octet-stream");
+    }
+
+    #[test]
+    fn test_legacy_history_body() {
+        let typed: ResponseBody = serde_json::from_str(r#"{"kind": "text", "text": "hi", "charset": "utf-8"}"#).unwrap();
+        assert_eq!(typed.text().as_deref(), Some("hi"));
+
+        let response = |body: &str| -> crate::models::ApiResponse {
+            serde_json::from_str(&format!(
+                r#"{{"status": 200, "statusText": "OK", "time": 1, "size": "0", "headers": {{}}, "body": {}}}"#,
+                body
+            ))
+            .unwrap()
+        };
+        assert_eq!(response(r#"{"id": 1}"#).body, ResponseBody::Json { value: json!({"id": 1}) });
+        assert_eq!(response(r#""plain""#).body.text().as_deref(), Some("plain"));
+    }
+}
@@ -0,0 +1,146 @@
+use crate::models::{ApiResponse, Extraction, ExtractionResult, ExtractionSource};
+use super::assertions::json_path;
+use regex::Regex;
+use serde_json::Value;
+
+/// Runs every enabled extraction rule against a response
+pub fn extract(extractions: &[Extraction], response: &ApiResponse) -> Vec<ExtractionResult> {
+    extractions
+        .iter()
+        .filter(|e| e.enabled && !e.variable.trim().is_empty())
+        .map(|extraction| {
+            let (value, error) = match capture(&extraction.source, response) {
+                Ok(value) => (value, None),
+                Err(e) => (None, Some(e)),
+            };
+            ExtractionResult {
+                variable: extraction.variable.trim().to_string(),
+                scope: extraction.scope,
+                value,
+                error,
+            }
+        })
+        .collect()
+}
+
+fn capture(source: &ExtractionSource, response: &ApiResponse) -> Result<Option<String>, String> {
+    match source {
+        ExtractionSource::JsonPath { path } => Ok(response.body.json().and_then(|root| json_path(root, path)).map(|value| match value {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        })),
+        ExtractionSource::Header { name } => Ok(response
+            .headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.clone())),
+        ExtractionSource::Regex { pattern, group } => {
+            let re = Regex::new(pattern)
+                .map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+            let body = response.body.text().unwrap_or_default();
+            Ok(re
+                .captures(&body)
+                .and_then(|caps| caps.get(*group))
+                .map(|m| m.as_str().to_string()))
+        }
+        ExtractionSource::Cookie { name } => Ok(find_cookie(response, name)),
+    }
+}
+
+/// Looks through Set-Cookie headers, which the engine joins with newlines
+fn find_cookie(response: &ApiResponse, name: &str) -> Option<String> {
+    let (_, header) = response
+        .headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("set-cookie"))?;
+
+    header.lines().find_map(|cookie| {
+        let pair = cookie.split(';').next()?;
+        let (key, value) = pair.split_once('=')?;
+        (key.trim() == name).then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ExtractionScope, ResponseBody};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn response() -> ApiResponse {
+        let mut headers = HashMap::new();
+        headers.insert("x-request-id".to_string(), "abc-123".to_string());
+        headers.insert(
+            "set-cookie".to_string(),
+            "theme=dark; Path=/\nsession=\"s3cr3t\"; Expires=Wed, 21 Oct 2026 07:28:00 GMT; HttpOnly".to_string(),
+        );
+        ApiResponse {
+            status: 200,
+            status_text: "OK".to_string(),
+            time: 10,
+            size: "0.01".to_string(),
+            size_bytes: 40,
+            headers,
+            body: ResponseBody::Json { value: json!({"auth": {"token": "jwt.value", "expires": 3600}}) },
+            ..Default::default()
+        }
+    }
+
+    fn rule(variable: &str, source: ExtractionSource) -> Extraction {
+        Extraction {
+            enabled: true,
+            variable: variable.to_string(),
+            scope: ExtractionScope::Environment,
+            source,
+        }
+    }
+
+    #[test]
+    fn test_extract_sources() {
+        let results = extract(
+            &[
+                rule("TOKEN", ExtractionSource::JsonPath { path: "$.auth.token".to_string() }),
+                rule("TTL", ExtractionSource::JsonPath { path: "$.auth.expires".to_string() }),
+                rule("REQ", ExtractionSource::Header { name: "X-Request-Id".to_string() }),
+                rule("SIG", ExtractionSource::Regex { pattern: r"jwt\.(\w+)".to_string(), group: 1 }),
+                rule("SESSION", ExtractionSource::Cookie { name: "session".to_string() }),
+            ],
+            &response(),
+        );
+        let values: Vec<Option<&str>> = results.iter().map(|r| r.value.as_deref()).collect();
+        assert_eq!(
+            values,
+            vec![Some("jwt.value"), Some("3600"), Some("abc-123"), Some("value"), Some("s3cr3t")]
+        );
+    }
+
+    #[test]
+    fn test_missing_and_invalid() {
+        let mut disabled = rule("OFF", ExtractionSource::Header { name: "x-request-id".to_string() });
+        disabled.enabled = false;
+        let results = extract(
+            &[
+                disabled,
+                rule("NONE", ExtractionSource::Cookie { name: "absent".to_string() }),
+                rule("BAD", ExtractionSource::Regex { pattern: "(".to_string(), group: 0 }),
+            ],
+            &response(),
+        );
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].value, None);
+        assert!(results[0].error.is_none());
+        assert!(results[1].error.as_deref().unwrap().starts_with("Invalid pattern"));
+    }
+
+    #[test]
+    fn test_extraction_serde_shape() {
+        let parsed: Extraction = serde_json::from_str(
+            r#"{"variable": "TOKEN", "from": "jsonPath", "path": "$.token", "scope": "global"}"#,
+        )
+        .unwrap();
+        assert!(parsed.enabled);
+        assert_eq!(parsed.scope, ExtractionScope::Global);
+        assert!(matches!(parsed.source, ExtractionSource::JsonPath { .. }));
+    }
+}
@@ -1,13 +1,97 @@
-use crate::models::{ApiRequest, ApiResponse};
-use crate::engine::auth;
+use crate::models::{ApiRequest, ApiResponse, AuthPayload, RequestBody};
+use crate::engine::{auth, body, payload, proxy, timing};
+use crate::engine::pool::{ClientPool, PooledClient};
+use crate::settings::{HttpSettings, HttpVersion};
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use std::collections::HashMap;
 use std::str::FromStr;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-pub async fn execute_request(req: ApiRequest) -> Result<ApiResponse, String> {
-    let start = Instant::now();
+/// Sends a request using the global `settings` merged with the request's own overrides,
+/// over a pooled client for that configuration
+pub async fn execute_request(req: ApiRequest, settings: &HttpSettings, pool: &ClientPool) -> Result<ApiResponse, String> {
+    let settings = settings.with_override(req.settings.as_ref());
+    let (request_builder, pooled) = build_request(&req, &settings, pool).await?;
+    let connections_before = pooled.probe.connections_opened();
 
+    // Execute request
+    let sent_at = Instant::now();
+    let response = send(&req, request_builder).await?;
+    let headers_at = Instant::now();
+
+    // No new connection during the send means an idle pooled one was used.
+    // Best effort: a concurrent send on the same client may open one meanwhile.
+    let connection_reused = pooled.probe.connections_opened() == connections_before;
+
+    // Extract response data
+    let status = response.status().as_u16();
+    let status_text = response.status().canonical_reason()
+        .unwrap_or("Unknown")
+        .to_string();
+    let response_headers = collect_headers(response.headers());
+    let tls = pooled.probe.tls_info(response.url());
+    let proxy = proxy::shown_for_url(&settings.proxy, response.url());
+
+    // Get response body
+    let body_bytes = response.bytes().await
+        .map_err(|e| format!("Failed to read response body: {}", e))?;
+    let finished_at = Instant::now();
+    let connection = (!connection_reused).then(|| pooled.probe.snapshot());
+    let timings = timing::phase_timings(connection, sent_at, headers_at, finished_at);
+
+    let size_kb = body_bytes.len() as f64 / 1024.0;
+    let size = format!("{:.2}", size_kb);
+
+    let content_type = response_headers.get("content-type").map(String::as_str);
+    let body_value = body::decode(&body_bytes, content_type);
+
+    Ok(ApiResponse {
+        status,
+        status_text,
+        // From the send, like the phase timings, so fetching an OAuth token isn't counted
+        time: finished_at.duration_since(sent_at).as_millis(),
+        size,
+        size_bytes: body_bytes.len() as u64,
+        headers: response_headers,
+        body: body_value,
+        assertion_results: Vec::new(),
+        extracted_values: Vec::new(),
+        connection_reused,
+        timings,
+        tls,
+        proxy,
+        warnings: Vec::new(),
+        raw_body: body_bytes.to_vec(),
+    })
+}
+
+/// The bearer auth for an "oauth2" auth, with its token fetched over the pooled
+/// client for the token endpoint's host. None for other auth types.
+pub(crate) async fn oauth2_bearer(
+    auth: &AuthPayload,
+    settings: &HttpSettings,
+    pool: &ClientPool,
+) -> Result<Option<AuthPayload>, String> {
+    if auth.auth_type != "oauth2" {
+        return Ok(None);
+    }
+    let config = auth.oauth2.as_ref().ok_or("OAuth 2.0 is selected but not configured")?;
+    // A missing or malformed token URL is reported by the token request itself
+    let settings = match url::Url::parse(config.token_url.trim()) {
+        Ok(token_url) => settings.for_url(&token_url),
+        Err(_) => settings.clone(),
+    };
+    let pooled = pool.get(&settings)?;
+    auth::oauth2::bearer_auth(auth, &pooled.client).await
+}
+
+/// The request with its params, headers, auth and encoded body applied,
+/// on the pooled client for `settings` (already merged with the request's overrides)
+pub(crate) async fn build_request(
+    req: &ApiRequest,
+    settings: &HttpSettings,
+    pool: &ClientPool,
+) -> Result<(reqwest::RequestBuilder, PooledClient), String> {
     // Parse and build URL with query params
     let mut url = url::Url::parse(&req.url)
         .map_err(|e| format!("Invalid URL: {}", e))?;
@@ -29,86 +113,355 @@ pub async fn execute_request(req: ApiRequest) -> Result<ApiResponse, String> {
         }
     }
 
+    // Reuse the long-lived client for this configuration and host
+    let pooled = pool.get(&settings.for_url(&url))?;
+
     // Apply authentication
-    auth::apply_auth(&req.auth, &mut headers, &mut url)
+    let bearer = oauth2_bearer(&req.auth, settings, pool).await?;
+    auth::apply_auth(bearer.as_ref().unwrap_or(&req.auth), &mut headers, &mut url)
         .map_err(|e| format!("Auth error: {}", e))?;
 
-    // Build HTTP client
-    let mut default_headers = HeaderMap::new();
-    default_headers.insert("Accept", HeaderValue::from_static("application/json, text/plain, */*"));
-    default_headers.insert("Accept-Language", HeaderValue::from_static("en-US,en;q=0.9"));
-    
-    let client = reqwest::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
-        .default_headers(default_headers)
-        .timeout(std::time::Duration::from_secs(30))
-        .build()
-        .map_err(|e| format!("Failed to create client: {}", e))?;
-
     // Build request
     let method = reqwest::Method::from_str(&req.method.to_uppercase())
         .map_err(|e| format!("Invalid HTTP method: {}", e))?;
 
-    let mut request_builder = client.request(method.clone(), url.as_str())
+    // Encode the body if the method allows one
+    let body = if method != reqwest::Method::GET && method != reqwest::Method::DELETE {
+        payload::encode(&req.body).await?
+    } else {
+        None
+    };
+    if let Some(content_type) = body.as_ref().and_then(|b| b.content_type.as_deref()) {
+        // A multipart Content-Type must carry the boundary used, so it always wins
+        let multipart = matches!(req.body, RequestBody::Multipart { .. });
+        if multipart || !headers.contains_key(reqwest::header::CONTENT_TYPE) {
+            let value = HeaderValue::from_str(content_type)
+                .map_err(|e| format!("Invalid body Content-Type '{}': {}", content_type, e))?;
+            headers.insert(reqwest::header::CONTENT_TYPE, value);
+        }
+    }
+    auth::sign_request(&req.auth, method.as_str(), &url, &mut headers, body.as_ref().map_or(&[], |b| &b.bytes))
+        .map_err(|e| format!("Auth error: {}", e))?;
+
+    let mut request_builder = pooled.client.request(method, url.as_str())
         .headers(headers);
+    if let Some(body) = body {
+        request_builder = request_builder.body(body.bytes);
+    }
+    Ok((request_builder, pooled))
+}
 
-    // Add body if present and method allows it
-    if method != reqwest::Method::GET && method != reqwest::Method::DELETE {
-        if let Some(body) = req.body {
-            if !body.trim().is_empty() {
-                request_builder = request_builder.body(body);
-            }
-        }
+/// Sends a built request. Digest auth can't be set up-front without a
+/// challenge: a 401 carrying one is answered with a single retry, and origins
+/// that challenged before are answered up-front with the next nonce count.
+pub(crate) async fn send(req: &ApiRequest, request_builder: reqwest::RequestBuilder) -> Result<reqwest::Response, String> {
+    if req.auth.auth_type != "digest" {
+        return request_builder.send().await.map_err(|e| format!("Request failed: {}", e));
     }
 
-    // Execute request
-    let response = request_builder
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+    let (client, request) = request_builder.build_split();
+    let mut request = request.map_err(|e| format!("Request failed: {}", e))?;
+    let user_authorization = request.headers().contains_key(reqwest::header::AUTHORIZATION);
+    if !user_authorization {
+        if let Some(value) = auth::digest::preemptive(&req.auth, request.method().as_str(), request.url()) {
+            let value = HeaderValue::from_str(&value).map_err(|e| format!("Auth error: {}", e))?;
+            request.headers_mut().insert(reqwest::header::AUTHORIZATION, value);
+        }
+    }
+    let retry = request.try_clone();
+    let method = request.method().clone();
+    let url = request.url().clone();
+    let response = client.execute(request).await.map_err(|e| format!("Request failed: {}", e))?;
+    if response.status() != reqwest::StatusCode::UNAUTHORIZED || user_authorization {
+        return Ok(response);
+    }
 
-    // Extract response data
-    let status = response.status().as_u16();
-    let status_text = response.status().canonical_reason()
-        .unwrap_or("Unknown")
-        .to_string();
+    let Some(mut retry) = retry else { return Ok(response) };
+    let Some(value) = auth::digest::respond(&req.auth, method.as_str(), &url, response.headers()) else {
+        return Ok(response);
+    };
+    let value = HeaderValue::from_str(&value).map_err(|e| format!("Auth error: {}", e))?;
+    retry.headers_mut().insert(reqwest::header::AUTHORIZATION, value);
+    client.execute(retry).await.map_err(|e| format!("Request failed: {}", e))
+}
 
-    // Extract headers
-    let response_headers: HashMap<String, String> = response
-        .headers()
-        .iter()
-        .map(|(k, v)| {
-            (
-                k.to_string(),
-                v.to_str().unwrap_or("").to_string(),
-            )
-        })
-        .collect();
+/// Response headers with repeated ones folded into a single entry.
+/// Set-Cookie can't be comma-joined (Expires contains commas), so it's newline-separated.
+pub(crate) fn collect_headers(headers: &HeaderMap) -> HashMap<String, String> {
+    let mut collected: HashMap<String, String> = HashMap::new();
+    for (k, v) in headers {
+        let value = v.to_str().unwrap_or("");
+        let separator = if k == reqwest::header::SET_COOKIE { "\n" } else { ", " };
+        collected
+            .entry(k.to_string())
+            .and_modify(|existing| {
+                existing.push_str(separator);
+                existing.push_str(value);
+            })
+            .or_insert_with(|| value.to_string());
+    }
+    collected
+}
 
-    // Get response body
-    let body_bytes = response.bytes().await
-        .map_err(|e| format!("Failed to read response body: {}", e))?;
+/// Builds a reqwest client configured from the given settings
+pub fn build_client(settings: &HttpSettings) -> Result<reqwest::Client, String> {
+    client_builder(settings)?
+        .build()
+        .map_err(|e| format!("Failed to create client: {}", e))
+}
 
-    let size_kb = body_bytes.len() as f64 / 1024.0;
-    let size = format!("{:.2}", size_kb);
+/// Client builder with everything from the settings applied
+pub fn client_builder(settings: &HttpSettings) -> Result<reqwest::ClientBuilder, String> {
+    // Request headers with the same name take precedence over these
+    let mut default_headers = HeaderMap::new();
+    for header in settings.default_headers.iter().filter(|h| h.enabled && !h.key.trim().is_empty()) {
+        let name = HeaderName::from_str(header.key.trim())
+            .map_err(|e| format!("Invalid default header '{}': {}", header.key, e))?;
+        let value = HeaderValue::from_str(&header.value)
+            .map_err(|e| format!("Invalid value for default header '{}': {}", header.key, e))?;
+        default_headers.insert(name, value);
+    }
 
-    // Try to parse as JSON, fallback to string
-    let body_value = if let Ok(json) = serde_json::from_slice::<serde_json::Value>(&body_bytes) {
-        json
+    let redirect_policy = if settings.follow_redirects {
+        reqwest::redirect::Policy::limited(settings.max_redirects)
     } else {
-        // If not JSON, return as string
-        let text = String::from_utf8_lossy(&body_bytes).to_string();
-        serde_json::Value::String(text)
+        reqwest::redirect::Policy::none()
     };
 
-    let elapsed = start.elapsed().as_millis();
+    let mut builder = reqwest::Client::builder()
+        .default_headers(default_headers)
+        .redirect(redirect_policy)
+        .gzip(settings.decompress)
+        .brotli(settings.decompress)
+        .deflate(settings.decompress);
 
-    Ok(ApiResponse {
-        status,
-        status_text,
-        time: elapsed,
-        size,
-        headers: response_headers,
-        body: body_value,
-    })
+    if !settings.user_agent.is_empty() {
+        builder = builder.user_agent(settings.user_agent.as_str());
+    }
+    if settings.timeout_ms > 0 {
+        builder = builder.timeout(Duration::from_millis(settings.timeout_ms));
+    }
+    if settings.connect_timeout_ms > 0 {
+        builder = builder.connect_timeout(Duration::from_millis(settings.connect_timeout_ms));
+    }
+    // reqwest's own environment lookup is replaced so the response can show the proxy used
+    builder = builder.no_proxy();
+    if let Some(proxy) = proxy::reqwest_proxy(&settings.proxy)? {
+        builder = builder.proxy(proxy);
+    }
+    builder = match settings.http_version {
+        HttpVersion::Auto => builder,
+        HttpVersion::Http1 => builder.http1_only(),
+        HttpVersion::Http2 => builder.http2_prior_knowledge(),
+    };
+
+    Ok(builder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AuthPayload, KeyValue};
+    use crate::settings::{HttpSettingsOverride, ProxyMode, ProxySettings};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// Answers one connection with `reply` and hands back the raw request
+    fn serve_once(reply: &'static str) -> (String, mpsc::Receiver<String>) {
+        serve_each(vec![reply])
+    }
+
+    /// Answers one connection per reply, in order, handing back each raw request
+    fn serve_each(replies: Vec<&'static str>) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for reply in replies {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 8192];
+                let n = stream.read(&mut buf).unwrap_or(0);
+                let _ = tx.send(String::from_utf8_lossy(&buf[..n]).to_lowercase());
+                let _ = stream.write_all(reply.as_bytes());
+            }
+        });
+        (format!("http://{}", addr), rx)
+    }
+
+    fn request(url: &str) -> ApiRequest {
+        ApiRequest {
+            headers: vec![KeyValue {
+                enabled: true,
+                key: "Accept".to_string(),
+                value: "text/csv".to_string(),
+            }],
+            ..ApiRequest::get(url)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_settings_shape_the_request() {
+        let (url, rx) = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        let mut settings = HttpSettings::default();
+        settings.default_headers.push(KeyValue {
+            enabled: true,
+            key: "X-Team".to_string(),
+            value: "qa".to_string(),
+        });
+        execute_request(request(&url), &settings, &ClientPool::new()).await.unwrap();
+
+        let raw = rx.recv().unwrap();
+        assert!(raw.contains("user-agent: prism/"));
+        assert!(raw.contains("x-team: qa"));
+        // The request's own Accept replaces the default one
+        assert!(raw.contains("accept: text/csv"));
+        assert!(!raw.contains("accept: application/json"));
+    }
+
+    #[tokio::test]
+    async fn test_form_body_sets_content_type() {
+        let (url, rx) = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        let mut req = request(&url);
+        req.method = "POST".to_string();
+        req.body = RequestBody::Urlencoded {
+            fields: vec![KeyValue {
+                enabled: true,
+                key: "name".to_string(),
+                value: "Jane Doe".to_string(),
+            }],
+        };
+        execute_request(req, &HttpSettings::default(), &ClientPool::new()).await.unwrap();
+
+        let raw = rx.recv().unwrap();
+        assert!(raw.contains("content-type: application/x-www-form-urlencoded"));
+        assert!(raw.ends_with("\r\n\r\nname=jane+doe"));
+    }
+
+    #[tokio::test]
+    async fn test_awsv4_signs_final_request() {
+        let (url, rx) = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        let mut req = request(&url);
+        req.method = "POST".to_string();
+        req.body = RequestBody::Urlencoded {
+            fields: vec![KeyValue { enabled: true, key: "a".to_string(), value: "1".to_string() }],
+        };
+        req.auth = AuthPayload {
+            auth_type: "awsv4".to_string(),
+            awsv4: Some(crate::models::AwsV4Config {
+                access_key_id: "AKID".to_string(),
+                secret_access_key: "secret".to_string(),
+                session_token: String::new(),
+                region: "us-east-1".to_string(),
+                service: "execute-api".to_string(),
+            }),
+            ..Default::default()
+        };
+        execute_request(req, &HttpSettings::default(), &ClientPool::new()).await.unwrap();
+
+        let raw = rx.recv().unwrap();
+        assert!(raw.contains("authorization: aws4-hmac-sha256 credential=akid/"));
+        // The body's Content-Type is set before signing, so it is covered
+        assert!(raw.contains("signedheaders=accept;content-type;host;x-amz-date,"));
+    }
+
+    #[tokio::test]
+    async fn test_digest_answers_challenge() {
+        let challenge = "HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Digest realm=\"device\", qop=\"auth\", nonce=\"abc123\"\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+        let ok = "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+        let (url, rx) = serve_each(vec![challenge, ok, ok]);
+        let mut req = request(&format!("{}/status?full=1", url));
+        req.auth = AuthPayload {
+            auth_type: "digest".to_string(),
+            username: Some("admin".to_string()),
+            password: Some("hunter2".to_string()),
+            ..Default::default()
+        };
+
+        let pool = ClientPool::new();
+        let response = execute_request(req.clone(), &HttpSettings::default(), &pool).await.unwrap();
+        assert_eq!(response.status, 200);
+        assert!(!rx.recv().unwrap().contains("authorization:"));
+        let answered = rx.recv().unwrap();
+        assert!(answered.contains(r#"authorization: digest username="admin", realm="device", nonce="abc123", uri="/status?full=1""#));
+        assert!(answered.contains("nc=00000001"));
+
+        // The next request answers up-front with the next nonce count
+        execute_request(req, &HttpSettings::default(), &pool).await.unwrap();
+        assert!(rx.recv().unwrap().contains("nc=00000002"));
+    }
+
+    #[tokio::test]
+    async fn test_per_request_redirect_override() {
+        let (url, _rx) = serve_once("HTTP/1.1 302 Found\r\nLocation: /elsewhere\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        let mut req = request(&url);
+        req.settings = Some(HttpSettingsOverride {
+            follow_redirects: Some(false),
+            ..Default::default()
+        });
+        let response = execute_request(req, &HttpSettings::default(), &ClientPool::new()).await.unwrap();
+        assert_eq!(response.status, 302);
+    }
+
+    #[tokio::test]
+    async fn test_manual_proxy_forwards_request() {
+        let (proxy_url, rx) = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok");
+        let mut req = request("http://api.example.invalid/data");
+        req.settings = Some(HttpSettingsOverride {
+            proxy: Some(ProxySettings {
+                mode: ProxyMode::Manual,
+                url: proxy_url.clone(),
+                username: "alice".to_string(),
+                password: "secret".to_string(),
+                no_proxy: "localhost".to_string(),
+            }),
+            ..Default::default()
+        });
+        let response = execute_request(req, &HttpSettings::default(), &ClientPool::new()).await.unwrap();
+        assert_eq!(response.proxy.as_deref(), Some(proxy_url.as_str()));
+
+        // Plain http goes to the proxy in absolute form, with the proxy credentials
+        let raw = rx.recv().unwrap();
+        assert!(raw.starts_with("get http://api.example.invalid/data http/1.1\r\n"), "{}", raw);
+        assert!(raw.contains("proxy-authorization: basic ywxpy2u6c2vjcmv0"));
+    }
+
+    #[tokio::test]
+    async fn test_pooled_connection_is_reused() {
+        // One keep-alive connection answering two requests
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 8192];
+            for _ in 0..2 {
+                let _ = stream.read(&mut buf);
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok");
+            }
+        });
+
+        let pool = ClientPool::new();
+        let settings = HttpSettings::default();
+        let first = execute_request(request(&url), &settings, &pool).await.unwrap();
+        let second = execute_request(request(&url), &settings, &pool).await.unwrap();
+        assert!(!first.connection_reused);
+        assert!(second.connection_reused);
+        // IP literal over plain http: no DNS or TLS phase, and nothing to connect on reuse
+        assert!(first.timings.connect.is_some());
+        assert_eq!((first.timings.dns, first.timings.tls), (None, None));
+        assert_eq!(second.timings.connect, None);
+        // The total time covers the same span as the phases
+        assert!((first.time as f64 - first.timings.total).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_invalid_default_header_is_rejected() {
+        let mut settings = HttpSettings::default();
+        settings.default_headers.push(KeyValue {
+            enabled: true,
+            key: "Bad Header".to_string(),
+            value: "x".to_string(),
+        });
+        assert!(build_client(&settings).unwrap_err().contains("Invalid default header"));
+    }
 }
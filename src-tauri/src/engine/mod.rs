@@ -1,3 +1,14 @@
 pub mod auth;
+pub mod body;
+pub mod payload;
 pub mod http;
 pub mod curl;
+pub mod assertions;
+pub mod extract;
+pub mod pool;
+pub mod proxy;
+pub mod tls;
+pub mod timing;
+pub mod sse;
+pub mod stream;
+pub mod websocket;
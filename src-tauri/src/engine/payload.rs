@@ -0,0 +1,220 @@
+use crate::models::{FormPart, FormPartKind, KeyValue, RequestBody};
+use super::body::mime_for_path;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+
+/// A request body encoded for the wire, with the Content-Type it implies
+#[derive(Debug)]
+pub struct Payload {
+    pub bytes: Vec<u8>,
+    pub content_type: Option<String>,
+}
+
+/// Encodes a request body, reading any files it refers to.
+/// None when there is nothing to send.
+pub async fn encode(body: &RequestBody) -> Result<Option<Payload>, String> {
+    if body.is_empty() {
+        return Ok(None);
+    }
+
+    let payload = match body {
+        RequestBody::None => return Ok(None),
+        RequestBody::Raw { content, content_type } => Payload {
+            bytes: content.clone().into_bytes(),
+            content_type: content_type.clone(),
+        },
+        RequestBody::Urlencoded { fields } => Payload {
+            bytes: urlencode(fields).into_bytes(),
+            content_type: Some("application/x-www-form-urlencoded".to_string()),
+        },
+        RequestBody::Multipart { parts } => {
+            let boundary = boundary();
+            Payload {
+                bytes: multipart(parts, &boundary).await?,
+                content_type: Some(format!("multipart/form-data; boundary={}", boundary)),
+            }
+        }
+        RequestBody::Binary { path, content_type } => Payload {
+            bytes: read_file(path).await?,
+            content_type: Some(content_type.clone().unwrap_or_else(|| mime_for_path(path).to_string())),
+        },
+        RequestBody::GraphQl { query, variables, operation_name } => Payload {
+            bytes: graphql_envelope(query, variables, operation_name.as_deref())?.to_string().into_bytes(),
+            content_type: Some("application/json".to_string()),
+        },
+    };
+    Ok(Some(payload))
+}
+
+/// `{"query", "variables", "operationName"}` as GraphQL servers expect it
+pub fn graphql_envelope(query: &str, variables: &str, operation_name: Option<&str>) -> Result<serde_json::Value, String> {
+    let mut envelope = serde_json::json!({ "query": query });
+    if !variables.trim().is_empty() {
+        let variables: serde_json::Value = serde_json::from_str(variables)
+            .map_err(|e| format!("GraphQL variables are not valid JSON: {}", e))?;
+        if !variables.is_object() && !variables.is_null() {
+            return Err("GraphQL variables must be a JSON object".to_string());
+        }
+        envelope["variables"] = variables;
+    }
+    if let Some(name) = operation_name.filter(|n| !n.trim().is_empty()) {
+        envelope["operationName"] = serde_json::json!(name);
+    }
+    Ok(envelope)
+}
+
+/// Enabled fields as `application/x-www-form-urlencoded`
+pub fn urlencode(fields: &[KeyValue]) -> String {
+    url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(
+            fields
+                .iter()
+                .filter(|f| f.enabled && !f.key.trim().is_empty())
+                .map(|f| (f.key.as_str(), f.value.as_str())),
+        )
+        .finish()
+}
+
+fn boundary() -> String {
+    let random: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(24)
+        .map(char::from)
+        .collect();
+    format!("----PrismFormBoundary{}", random)
+}
+
+async fn multipart(parts: &[FormPart], boundary: &str) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    for part in parts.iter().filter(|p| p.enabled && !p.key.trim().is_empty()) {
+        out.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+        let mut disposition = format!("Content-Disposition: form-data; name=\"{}\"", quote(&part.key));
+
+        let (data, content_type) = match part.kind {
+            FormPartKind::Text => (part.value.clone().into_bytes(), part.content_type.clone()),
+            FormPartKind::File => {
+                let file_name = std::path::Path::new(&part.value)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("file");
+                disposition.push_str(&format!("; filename=\"{}\"", quote(file_name)));
+                let content_type = part
+                    .content_type
+                    .clone()
+                    .unwrap_or_else(|| mime_for_path(&part.value).to_string());
+                (read_file(&part.value).await?, Some(content_type))
+            }
+        };
+
+        out.extend_from_slice(disposition.as_bytes());
+        out.extend_from_slice(b"\r\n");
+        if let Some(content_type) = content_type {
+            out.extend_from_slice(format!("Content-Type: {}\r\n", content_type).as_bytes());
+        }
+        out.extend_from_slice(b"\r\n");
+        out.extend_from_slice(&data);
+        out.extend_from_slice(b"\r\n");
+    }
+    out.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+    Ok(out)
+}
+
+/// Escapes a field or file name the way browsers do
+fn quote(name: &str) -> String {
+    name.replace('"', "%22").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+async fn read_file(path: &str) -> Result<Vec<u8>, String> {
+    if path.trim().is_empty() {
+        return Err("No file selected for the request body".to_string());
+    }
+    tokio::fs::read(path)
+        .await
+        .map_err(|e| format!("Failed to read file '{}': {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(key: &str, value: &str) -> KeyValue {
+        KeyValue { enabled: true, key: key.to_string(), value: value.to_string() }
+    }
+
+    #[test]
+    fn test_urlencode_skips_disabled() {
+        let mut off = field("debug", "1");
+        off.enabled = false;
+        assert_eq!(urlencode(&[field("q", "a&b c"), off, field("page", "2")]), "q=a%26b+c&page=2");
+    }
+
+    #[tokio::test]
+    async fn test_multipart_with_file() {
+        let path = std::env::temp_dir().join(format!("prism-upload-{}.txt", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "file contents").unwrap();
+        let path_str = path.to_str().unwrap().to_string();
+
+        let body = RequestBody::Multipart {
+            parts: vec![
+                FormPart {
+                    enabled: true,
+                    key: "title".to_string(),
+                    kind: FormPartKind::Text,
+                    value: "Q3 \"final\"".to_string(),
+                    content_type: None,
+                },
+                FormPart {
+                    enabled: true,
+                    key: "doc".to_string(),
+                    kind: FormPartKind::File,
+                    value: path_str.clone(),
+                    content_type: None,
+                },
+            ],
+        };
+        let payload = encode(&body).await.unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let content_type = payload.content_type.unwrap();
+        let boundary = content_type.strip_prefix("multipart/form-data; boundary=").unwrap();
+        let text = String::from_utf8(payload.bytes).unwrap();
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        assert_eq!(
+            text,
+            format!(
+                "--{b}\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nQ3 \"final\"\r\n\
+                 --{b}\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"{f}\"\r\nContent-Type: text/plain\r\n\r\nfile contents\r\n\
+                 --{b}--\r\n",
+                b = boundary,
+                f = file_name
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_graphql_envelope() {
+        let body = RequestBody::GraphQl {
+            query: "query User($id: ID!) { user(id: $id) { name } }".to_string(),
+            variables: r#"{"id": "42"}"#.to_string(),
+            operation_name: Some("User".to_string()),
+        };
+        let payload = encode(&body).await.unwrap().unwrap();
+        assert_eq!(payload.content_type.as_deref(), Some("application/json"));
+        let sent: serde_json::Value = serde_json::from_slice(&payload.bytes).unwrap();
+        assert_eq!(sent["variables"]["id"], "42");
+        assert_eq!(sent["operationName"], "User");
+
+        // Empty variables are left out; malformed ones fail before anything is sent
+        let bare = graphql_envelope("{ me { id } }", "  ", None).unwrap();
+        assert_eq!(bare, serde_json::json!({ "query": "{ me { id } }" }));
+        assert!(graphql_envelope("{ me { id } }", "{id: 1}", None).unwrap_err().contains("not valid JSON"));
+        assert!(graphql_envelope("{ me { id } }", "[1]", None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_missing_file_is_an_error() {
+        let body = RequestBody::Binary { path: "/nonexistent/prism.bin".to_string(), content_type: None };
+        assert!(encode(&body).await.unwrap_err().contains("Failed to read file '/nonexistent/prism.bin'"));
+        assert!(encode(&RequestBody::raw("  ")).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,83 @@
+use crate::settings::HttpSettings;
+use super::timing::{ConnectProbe, ProbeLayer, TimingResolver, TimingSessionStore};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Upper bound on cached clients; per-request overrides each get their own
+const MAX_CLIENTS: usize = 16;
+
+/// Long-lived clients keyed by the settings they were built with, so sends
+/// with the same configuration share connections (and TLS sessions).
+/// Callers narrow the TLS entries to the host first (`HttpSettings::for_url`).
+#[derive(Default)]
+pub struct ClientPool {
+    clients: Mutex<HashMap<HttpSettings, PooledClient>>,
+}
+
+/// A client plus the probe recording how it opens connections
+#[derive(Clone)]
+pub struct PooledClient {
+    pub client: reqwest::Client,
+    pub probe: Arc<ConnectProbe>,
+}
+
+impl ClientPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The cached client for these settings, building one if needed
+    pub fn get(&self, settings: &HttpSettings) -> Result<PooledClient, String> {
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(client) = clients.get(settings) {
+            return Ok(client.clone());
+        }
+
+        let probe = Arc::new(ConnectProbe::default());
+        let tls = super::tls::client_config(settings, Arc::new(TimingSessionStore::new(probe.clone())))?;
+        let client = super::http::client_builder(settings)?
+            .use_preconfigured_tls(tls)
+            .dns_resolver(Arc::new(TimingResolver { probe: probe.clone() }))
+            .connector_layer(ProbeLayer { probe: probe.clone() })
+            .build()
+            .map_err(|e| format!("Failed to create client: {}", e))?;
+        let pooled = PooledClient { client, probe };
+
+        if clients.len() >= MAX_CLIENTS {
+            clients.clear();
+        }
+        clients.insert(settings.clone(), pooled.clone());
+        Ok(pooled)
+    }
+
+    /// Drops every cached client so the next sends open fresh connections.
+    /// Returns how many clients were dropped.
+    pub fn reset(&self) -> usize {
+        let mut clients = self.clients.lock().unwrap();
+        let count = clients.len();
+        clients.clear();
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clients_are_shared_per_config() {
+        let pool = ClientPool::new();
+        let defaults = HttpSettings::default();
+        let first = pool.get(&defaults).unwrap();
+        let second = pool.get(&defaults).unwrap();
+        assert!(Arc::ptr_eq(&first.probe, &second.probe));
+
+        let other = HttpSettings { timeout_ms: 1, ..Default::default() };
+        let third = pool.get(&other).unwrap();
+        assert!(!Arc::ptr_eq(&first.probe, &third.probe));
+
+        assert_eq!(pool.reset(), 2);
+        let fresh = pool.get(&defaults).unwrap();
+        assert!(!Arc::ptr_eq(&first.probe, &fresh.probe));
+    }
+}
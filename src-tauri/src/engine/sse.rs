@@ -0,0 +1,140 @@
+/// One event of a `text/event-stream` body
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseEvent {
+    /// "message" unless the server named it
+    pub event: String,
+    pub data: String,
+    /// The last event ID seen so far on the stream
+    pub id: Option<String>,
+    pub retry: Option<u64>,
+}
+
+/// Incremental parser for server-sent events, fed chunks as they arrive.
+/// Follows the HTML event stream rules: lines end in CR, LF or CRLF, a blank
+/// line dispatches the event, and events without data are dropped.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    line: Vec<u8>,
+    after_cr: bool,
+    started: bool,
+    event: String,
+    data: String,
+    last_id: Option<String>,
+    retry: Option<u64>,
+}
+
+impl SseParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a chunk, returning the events it completes
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        let mut chunk = chunk;
+        if !self.started && !chunk.is_empty() {
+            self.started = true;
+            chunk = chunk.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(chunk);
+        }
+
+        let mut events = Vec::new();
+        for &byte in chunk {
+            if std::mem::take(&mut self.after_cr) && byte == b'\n' {
+                continue;
+            }
+            match byte {
+                b'\r' | b'\n' => {
+                    self.after_cr = byte == b'\r';
+                    let line = std::mem::take(&mut self.line);
+                    if let Some(event) = self.process_line(&String::from_utf8_lossy(&line)) {
+                        events.push(event);
+                    }
+                }
+                _ => self.line.push(byte),
+            }
+        }
+        events
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = value.to_string(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => self.last_id = Some(value.to_string()),
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                self.retry = value.parse().ok();
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event = std::mem::take(&mut self.event);
+        if self.data.is_empty() {
+            return None;
+        }
+        let mut data = std::mem::take(&mut self.data);
+        data.pop();
+        Some(SseEvent {
+            event: if event.is_empty() { "message".to_string() } else { event },
+            data,
+            id: self.last_id.clone(),
+            // A retry without data rides along with the next event
+            retry: self.retry.take(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event: &str, data: &str, id: Option<&str>, retry: Option<u64>) -> SseEvent {
+        SseEvent {
+            event: event.to_string(),
+            data: data.to_string(),
+            id: id.map(String::from),
+            retry,
+        }
+    }
+
+    #[test]
+    fn test_fields_and_dispatch() {
+        let mut parser = SseParser::new();
+        let events = parser.push(
+            b"\xEF\xBB\xBF: keep-alive\n\nretry: 3000\n\nid: 1\nevent: delta\ndata: {\"token\":\"Hel\"}\n\ndata:lo\ndata:  world\n\nid\ndata\n\n",
+        );
+        assert_eq!(
+            events,
+            vec![
+                event("delta", r#"{"token":"Hel"}"#, Some("1"), Some(3000)),
+                event("message", "lo\n world", Some("1"), None),
+                event("message", "", Some(""), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_events_split_across_chunks() {
+        let mut parser = SseParser::new();
+        assert!(parser.push(b"event: ping\r").is_empty());
+        assert!(parser.push(b"\ndata: a").is_empty());
+        assert!(parser.push(b"bc\r\n").is_empty());
+        assert_eq!(parser.push(b"\r\ndata: next\r\r"), vec![event("ping", "abc", None, None), event("message", "next", None, None)]);
+        // An unterminated event at the end of the stream is never dispatched
+        assert!(parser.push(b"data: partial\n").is_empty());
+    }
+}
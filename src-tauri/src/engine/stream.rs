@@ -0,0 +1,327 @@
+use crate::engine::http::{build_request, collect_headers, send};
+use crate::engine::pool::ClientPool;
+use crate::engine::proxy;
+use crate::engine::sse::SseParser;
+use crate::engine::timing::{self, ConnectMarks};
+use crate::engine::body;
+use crate::models::{ApiRequest, ApiResponse, Direction, Frame, TlsInfo, TranscriptEntry};
+use crate::settings::HttpSettings;
+use base64::Engine;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+use tokio::sync::oneshot;
+
+/// A response whose headers have arrived but whose body is still streaming
+pub struct OpenStream {
+    pub id: String,
+    pub status: u16,
+    pub status_text: String,
+    pub headers: HashMap<String, String>,
+    tls: Option<TlsInfo>,
+    proxy: Option<String>,
+    response: reqwest::Response,
+    sent_at: Instant,
+    headers_at: Instant,
+    connection: Option<ConnectMarks>,
+}
+
+/// Returned to the UI once the response headers are in
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamStart {
+    #[serde(rename = "streamId")]
+    pub stream_id: String,
+    pub status: u16,
+    #[serde(rename = "statusText")]
+    pub status_text: String,
+    pub headers: HashMap<String, String>,
+}
+
+/// Sent to the UI as the body arrives
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StreamEvent {
+    Message {
+        #[serde(rename = "streamId")]
+        stream_id: String,
+        entry: TranscriptEntry,
+    },
+    Error {
+        #[serde(rename = "streamId")]
+        stream_id: String,
+        message: String,
+    },
+    Finished {
+        #[serde(rename = "streamId")]
+        stream_id: String,
+        cancelled: bool,
+    },
+}
+
+/// What a finished stream leaves for history
+pub struct StreamRecord {
+    /// The response with the whole body as received
+    pub response: ApiResponse,
+    pub transcript: Vec<TranscriptEntry>,
+    pub cancelled: bool,
+}
+
+/// Sends the request and waits for the response headers only. The total
+/// timeout doesn't apply: a stream runs until the server ends it or it's cancelled.
+pub async fn open(req: &ApiRequest, settings: &HttpSettings, pool: &ClientPool) -> Result<OpenStream, String> {
+    let mut settings = settings.with_override(req.settings.as_ref());
+    settings.timeout_ms = 0;
+    let (request_builder, pooled) = build_request(req, &settings, pool).await?;
+    let connections_before = pooled.probe.connections_opened();
+
+    let sent_at = Instant::now();
+    let response = send(req, request_builder).await?;
+    let headers_at = Instant::now();
+    let connection_reused = pooled.probe.connections_opened() == connections_before;
+
+    Ok(OpenStream {
+        id: uuid::Uuid::new_v4().to_string(),
+        status: response.status().as_u16(),
+        status_text: response.status().canonical_reason().unwrap_or("Unknown").to_string(),
+        headers: collect_headers(response.headers()),
+        tls: pooled.probe.tls_info(response.url()),
+        proxy: proxy::shown_for_url(&settings.proxy, response.url()),
+        response,
+        sent_at,
+        headers_at,
+        connection: (!connection_reused).then(|| pooled.probe.snapshot()),
+    })
+}
+
+impl OpenStream {
+    pub fn start(&self) -> StreamStart {
+        StreamStart {
+            stream_id: self.id.clone(),
+            status: self.status,
+            status_text: self.status_text.clone(),
+            headers: self.headers.clone(),
+        }
+    }
+}
+
+/// Reads the body until it ends or `cancel` fires, reporting each chunk through
+/// `on_event`: parsed events for `text/event-stream`, otherwise text or binary chunks.
+pub async fn run(stream: OpenStream, mut cancel: oneshot::Receiver<()>, on_event: impl Fn(StreamEvent)) -> StreamRecord {
+    let OpenStream { id, status, status_text, headers, tls, proxy, mut response, sent_at, headers_at, connection } = stream;
+    let content_type = headers.get("content-type").cloned().unwrap_or_default();
+    let is_sse = content_type
+        .split(';')
+        .next()
+        .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("text/event-stream"));
+    let mut textual = body::is_text_content_type(&content_type);
+
+    let mut parser = SseParser::new();
+    let mut pending = Vec::new();
+    let mut raw = Vec::new();
+    let mut transcript = Vec::new();
+    let mut cancelled = false;
+
+    let mut record = |frame: Frame| {
+        let entry = TranscriptEntry {
+            direction: Direction::Received,
+            frame,
+            elapsed_ms: headers_at.elapsed().as_millis() as u64,
+        };
+        on_event(StreamEvent::Message { stream_id: id.clone(), entry: entry.clone() });
+        transcript.push(entry);
+    };
+
+    loop {
+        tokio::select! {
+            Ok(()) = &mut cancel => {
+                cancelled = true;
+                break;
+            }
+            chunk = response.chunk() => match chunk {
+                Ok(Some(bytes)) => {
+                    raw.extend_from_slice(&bytes);
+                    if is_sse {
+                        for event in parser.push(&bytes) {
+                            record(Frame::Event { event: event.event, data: event.data, id: event.id, retry: event.retry });
+                        }
+                        continue;
+                    }
+                    pending.extend_from_slice(&bytes);
+                    match textual.then(|| take_utf8(&mut pending)).flatten() {
+                        Some(text) if text.is_empty() => {}
+                        Some(text) => record(Frame::Text { text }),
+                        None => {
+                            // Not text after all; the rest is passed on as binary
+                            textual = false;
+                            record(Frame::Binary { base64: base64::engine::general_purpose::STANDARD.encode(&pending) });
+                            pending.clear();
+                        }
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    on_event(StreamEvent::Error { stream_id: id.clone(), message: format!("Failed to read response body: {}", e) });
+                    break;
+                }
+            },
+        }
+    }
+    if !pending.is_empty() {
+        record(Frame::Text { text: String::from_utf8_lossy(&pending).into_owned() });
+    }
+    on_event(StreamEvent::Finished { stream_id: id.clone(), cancelled });
+
+    let finished_at = Instant::now();
+    let response = ApiResponse {
+        status,
+        status_text,
+        time: finished_at.duration_since(sent_at).as_millis(),
+        size: format!("{:.2}", raw.len() as f64 / 1024.0),
+        size_bytes: raw.len() as u64,
+        body: body::decode(&raw, Some(&content_type).filter(|c| !c.is_empty()).map(String::as_str)),
+        headers,
+        assertion_results: Vec::new(),
+        extracted_values: Vec::new(),
+        connection_reused: connection.is_none(),
+        timings: timing::phase_timings(connection, sent_at, headers_at, finished_at),
+        tls,
+        proxy,
+        warnings: Vec::new(),
+        raw_body: raw,
+    };
+    StreamRecord { response, transcript, cancelled }
+}
+
+/// Takes the complete UTF-8 text from `pending`, leaving a character split
+/// across chunks for the next one. None when the bytes aren't UTF-8.
+fn take_utf8(pending: &mut Vec<u8>) -> Option<String> {
+    let valid = match std::str::from_utf8(pending) {
+        Ok(_) => pending.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => return None,
+    };
+    let rest = pending.split_off(valid);
+    let text = std::mem::replace(pending, rest);
+    String::from_utf8(text).ok()
+}
+
+/// Cancel switches for the streams currently running, keyed by stream id
+#[derive(Default)]
+pub struct Streams {
+    cancels: Mutex<HashMap<String, oneshot::Sender<()>>>,
+}
+
+impl Streams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a stream; the receiver is handed to its `run` loop
+    pub fn register(&self, id: &str) -> oneshot::Receiver<()> {
+        let (sender, receiver) = oneshot::channel();
+        self.cancels.lock().unwrap().insert(id.to_string(), sender);
+        receiver
+    }
+
+    pub fn cancel(&self, id: &str) -> Result<(), String> {
+        let sender = self.cancels.lock().unwrap().remove(id)
+            .ok_or_else(|| format!("No running stream '{}'", id))?;
+        // The stream may have finished on its own meanwhile
+        let _ = sender.send(());
+        Ok(())
+    }
+
+    pub fn remove(&self, id: &str) {
+        self.cancels.lock().unwrap().remove(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    /// Writes a chunked response with the given chunks, pausing between them.
+    /// With `hold_open` the body never ends.
+    async fn serve_chunks(content_type: &'static str, chunks: Vec<&'static [u8]>, hold_open: bool) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            let head = format!("HTTP/1.1 200 OK\r\nContent-Type: {}\r\nTransfer-Encoding: chunked\r\n\r\n", content_type);
+            socket.write_all(head.as_bytes()).await.unwrap();
+            for chunk in chunks {
+                socket.write_all(format!("{:x}\r\n", chunk.len()).as_bytes()).await.unwrap();
+                socket.write_all(chunk).await.unwrap();
+                socket.write_all(b"\r\n").await.unwrap();
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            if hold_open {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+            }
+            let _ = socket.write_all(b"0\r\n\r\n").await;
+        });
+        format!("http://{}/events", addr)
+    }
+
+    #[tokio::test]
+    async fn test_sse_events_are_parsed() {
+        let url = serve_chunks(
+            "text/event-stream",
+            vec![b"id: 1\ndata: {\"delta\":\"Hel", b"lo\"}\n\n", b"event: done\ndata: [DONE]\n\n"],
+            false,
+        ).await;
+        let stream = open(&ApiRequest::get(url), &HttpSettings::default(), &ClientPool::new()).await.unwrap();
+        assert_eq!(stream.start().status, 200);
+
+        let streams = Streams::new();
+        let cancel = streams.register(&stream.id);
+        let (events_tx, mut events) = mpsc::unbounded_channel();
+        let record = run(stream, cancel, move |event| events_tx.send(event).unwrap()).await;
+
+        let frames: Vec<Frame> = record.transcript.into_iter().map(|e| e.frame).collect();
+        assert_eq!(
+            frames,
+            vec![
+                Frame::Event { event: "message".to_string(), data: r#"{"delta":"Hello"}"#.to_string(), id: Some("1".to_string()), retry: None },
+                Frame::Event { event: "done".to_string(), data: "[DONE]".to_string(), id: Some("1".to_string()), retry: None },
+            ]
+        );
+        assert!(!record.cancelled);
+        assert_eq!(record.response.body.text().as_deref(), Some("id: 1\ndata: {\"delta\":\"Hello\"}\n\nevent: done\ndata: [DONE]\n\n"));
+        assert!(matches!(events.recv().await, Some(StreamEvent::Message { .. })));
+        assert!(matches!(events.recv().await, Some(StreamEvent::Message { .. })));
+        assert!(matches!(events.recv().await, Some(StreamEvent::Finished { cancelled: false, .. })));
+    }
+
+    #[tokio::test]
+    async fn test_text_chunks_and_cancel() {
+        // "é" is split across the first two chunks
+        let url = serve_chunks("application/x-ndjson", vec![b"{\"a\":\"caf\xc3", b"\xa9\"}\n"], true).await;
+        let stream = open(&ApiRequest::get(url), &HttpSettings::default(), &ClientPool::new()).await.unwrap();
+        let streams = Streams::new();
+        let cancel = streams.register(&stream.id);
+        let id = stream.id.clone();
+        let (events_tx, mut events) = mpsc::unbounded_channel();
+        let running = tokio::spawn(run(stream, cancel, move |event| events_tx.send(event).unwrap()));
+
+        let mut text = String::new();
+        while text != "{\"a\":\"café\"}\n" {
+            match events.recv().await {
+                Some(StreamEvent::Message { entry: TranscriptEntry { frame: Frame::Text { text: chunk }, .. }, .. }) => text.push_str(&chunk),
+                other => panic!("unexpected event {:?}", other),
+            }
+        }
+        streams.cancel(&id).unwrap();
+        let record = running.await.unwrap();
+        assert!(record.cancelled);
+        assert_eq!(record.transcript.len(), 2);
+        assert!(streams.cancel(&id).is_err());
+    }
+}
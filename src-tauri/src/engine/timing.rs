@@ -0,0 +1,300 @@
+use crate::engine::tls::{self, Handshake};
+use crate::models::{ResponseTimings, TlsInfo};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use rustls::client::{ClientSessionMemoryCache, ClientSessionStore};
+use rustls::pki_types::ServerName;
+use rustls::NamedGroup;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Instants recorded while a client opened its most recent connection
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ConnectMarks {
+    pub connect_start: Option<Instant>,
+    pub dns_start: Option<Instant>,
+    pub dns_end: Option<Instant>,
+    pub tls_start: Option<Instant>,
+    pub connect_end: Option<Instant>,
+}
+
+/// Shared by a pooled client's resolver, connector and TLS session store.
+/// Sends running concurrently on one client can overwrite each other's
+/// marks, so the breakdown is best effort in that case.
+#[derive(Debug, Default)]
+pub struct ConnectProbe {
+    connects: AtomicU64,
+    marks: Mutex<ConnectMarks>,
+    /// Details of the latest TLS handshake with each server name
+    tls: Mutex<HashMap<String, TlsInfo>>,
+}
+
+impl ConnectProbe {
+    /// Total connections opened so far
+    pub fn connections_opened(&self) -> u64 {
+        self.connects.load(Ordering::SeqCst)
+    }
+
+    pub fn snapshot(&self) -> ConnectMarks {
+        *self.marks.lock().unwrap()
+    }
+
+    fn begin_connect(&self) {
+        self.connects.fetch_add(1, Ordering::SeqCst);
+        *self.marks.lock().unwrap() = ConnectMarks {
+            connect_start: Some(Instant::now()),
+            ..Default::default()
+        };
+    }
+
+    fn mark(&self, update: impl FnOnce(&mut ConnectMarks, Instant)) {
+        update(&mut self.marks.lock().unwrap(), Instant::now());
+    }
+
+    /// What was negotiated the last time this client connected to the URL's
+    /// host; None for plain http
+    pub fn tls_info(&self, url: &url::Url) -> Option<TlsInfo> {
+        if url.scheme() != "https" {
+            return None;
+        }
+        let host = url.host_str()?.trim_start_matches('[').trim_end_matches(']');
+        self.tls.lock().unwrap().get(host).cloned()
+    }
+
+    fn record_handshake(&self, handshake: Handshake) {
+        let (Some(server_name), Some(mut info)) = (handshake.server_name.clone(), handshake.info()) else {
+            return;
+        };
+        let mut known = self.tls.lock().unwrap();
+        // Resumed sessions skip certificate verification; the chain is the one seen before
+        if info.certificates.is_empty() {
+            if let Some(previous) = known.get(&server_name) {
+                info.certificates = previous.certificates.clone();
+                info.verification_skipped = previous.verification_skipped;
+            }
+        }
+        known.insert(server_name, info);
+    }
+}
+
+/// Splits a send into phases. `connection` holds the marks of the connection
+/// opened for this send, or None when a pooled connection was reused.
+pub fn phase_timings(
+    connection: Option<ConnectMarks>,
+    sent_at: Instant,
+    headers_at: Instant,
+    finished_at: Instant,
+) -> ResponseTimings {
+    let mut timings = ResponseTimings {
+        dns: None,
+        connect: None,
+        tls: None,
+        ttfb: millis(headers_at.saturating_duration_since(sent_at)),
+        download: millis(finished_at.saturating_duration_since(headers_at)),
+        total: millis(finished_at.saturating_duration_since(sent_at)),
+    };
+
+    let Some(marks) = connection else {
+        return timings;
+    };
+    let (Some(connect_start), Some(connect_end)) = (marks.connect_start, marks.connect_end) else {
+        return timings;
+    };
+
+    // IP literals skip the resolver, so TCP starts right away
+    let tcp_start = match (marks.dns_start, marks.dns_end) {
+        (Some(start), Some(end)) => {
+            timings.dns = Some(millis(end.saturating_duration_since(start)));
+            end
+        }
+        _ => connect_start,
+    };
+    let tcp_end = match marks.tls_start {
+        Some(tls_start) => {
+            timings.tls = Some(millis(connect_end.saturating_duration_since(tls_start)));
+            tls_start
+        }
+        None => connect_end,
+    };
+    timings.connect = Some(millis(tcp_end.saturating_duration_since(tcp_start)));
+    // Time to first byte counts from the moment the connection was ready
+    timings.ttfb = millis(headers_at.saturating_duration_since(connect_end));
+    timings
+}
+
+/// Milliseconds rounded to two decimals
+fn millis(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 100_000.0).round() / 100.0
+}
+
+/// System resolver that records when lookups start and finish
+pub struct TimingResolver {
+    pub probe: Arc<ConnectProbe>,
+}
+
+impl Resolve for TimingResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let probe = self.probe.clone();
+        Box::pin(async move {
+            probe.mark(|m, now| m.dns_start = Some(now));
+            // Port 0 is replaced by reqwest with the URL's port
+            let addrs: Vec<_> = tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            probe.mark(|m, now| m.dns_end = Some(now));
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+/// rustls session cache that notes when a TLS handshake starts, and with
+/// which server: rustls asks it for a key-exchange hint while building the ClientHello
+#[derive(Debug)]
+pub struct TimingSessionStore {
+    inner: ClientSessionMemoryCache,
+    probe: Arc<ConnectProbe>,
+}
+
+impl TimingSessionStore {
+    pub fn new(probe: Arc<ConnectProbe>) -> Self {
+        Self {
+            inner: ClientSessionMemoryCache::new(256),
+            probe,
+        }
+    }
+}
+
+impl ClientSessionStore for TimingSessionStore {
+    fn set_kx_hint(&self, server_name: ServerName<'static>, group: NamedGroup) {
+        self.inner.set_kx_hint(server_name, group)
+    }
+
+    fn kx_hint(&self, server_name: &ServerName<'_>) -> Option<NamedGroup> {
+        self.probe.mark(|m, now| m.tls_start = Some(now));
+        tls::record_server_name(server_name);
+        self.inner.kx_hint(server_name)
+    }
+
+    fn set_tls12_session(&self, server_name: ServerName<'static>, value: rustls::client::Tls12ClientSessionValue) {
+        self.inner.set_tls12_session(server_name, value)
+    }
+
+    fn tls12_session(&self, server_name: &ServerName<'_>) -> Option<rustls::client::Tls12ClientSessionValue> {
+        self.inner.tls12_session(server_name)
+    }
+
+    fn remove_tls12_session(&self, server_name: &ServerName<'static>) {
+        self.inner.remove_tls12_session(server_name)
+    }
+
+    fn insert_tls13_ticket(&self, server_name: ServerName<'static>, value: rustls::client::Tls13ClientSessionValue) {
+        self.inner.insert_tls13_ticket(server_name, value)
+    }
+
+    fn take_tls13_ticket(&self, server_name: &ServerName<'static>) -> Option<rustls::client::Tls13ClientSessionValue> {
+        self.inner.take_tls13_ticket(server_name)
+    }
+}
+
+/// Connector layer that counts connections, times them end to end and
+/// collects what their TLS handshakes report
+#[derive(Clone)]
+pub struct ProbeLayer {
+    pub probe: Arc<ConnectProbe>,
+}
+
+impl<S> Layer<S> for ProbeLayer {
+    type Service = ProbedConnector<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ProbedConnector {
+            inner,
+            probe: self.probe.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ProbedConnector<S> {
+    inner: S,
+    probe: Arc<ConnectProbe>,
+}
+
+impl<S, R> Service<R> for ProbedConnector<S>
+where
+    S: Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        self.probe.begin_connect();
+        let probe = self.probe.clone();
+        let connecting = self.inner.call(request);
+        Box::pin(async move {
+            let (result, handshake) = tls::capture_handshake(connecting).await;
+            probe.mark(|m, now| m.connect_end = Some(now));
+            probe.record_handshake(handshake);
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(base: Instant, ms: u64) -> Option<Instant> {
+        Some(base + Duration::from_millis(ms))
+    }
+
+    #[test]
+    fn test_new_tls_connection_breakdown() {
+        let t0 = Instant::now();
+        let marks = ConnectMarks {
+            connect_start: at(t0, 1),
+            dns_start: at(t0, 1),
+            dns_end: at(t0, 11),
+            tls_start: at(t0, 31),
+            connect_end: at(t0, 71),
+        };
+        let timings = phase_timings(Some(marks), t0, t0 + Duration::from_millis(171), t0 + Duration::from_millis(191));
+        assert_eq!(timings.dns, Some(10.0));
+        assert_eq!(timings.connect, Some(20.0));
+        assert_eq!(timings.tls, Some(40.0));
+        assert_eq!(timings.ttfb, 100.0);
+        assert_eq!(timings.download, 20.0);
+        assert_eq!(timings.total, 191.0);
+    }
+
+    #[test]
+    fn test_plain_ip_connection_and_reuse() {
+        let t0 = Instant::now();
+        let marks = ConnectMarks {
+            connect_start: at(t0, 0),
+            connect_end: at(t0, 5),
+            ..Default::default()
+        };
+        let headers_at = t0 + Duration::from_millis(25);
+        let timings = phase_timings(Some(marks), t0, headers_at, headers_at);
+        assert_eq!(timings.dns, None);
+        assert_eq!(timings.tls, None);
+        assert_eq!(timings.connect, Some(5.0));
+        assert_eq!(timings.ttfb, 20.0);
+
+        let reused = phase_timings(None, t0, headers_at, headers_at);
+        assert_eq!(reused.connect, None);
+        assert_eq!(reused.ttfb, 25.0);
+    }
+}
@@ -0,0 +1,588 @@
+use crate::models::{CertificateInfo, TlsInfo};
+use crate::settings::{ClientIdentity, HttpSettings, HttpVersion, TlsHostSettings, TlsVersion};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::{ClientSessionStore, ResolvesClientCert, WebPkiServerVerifier};
+use rustls::crypto::cipher::{
+    AeadKey, Iv, KeyBlockShape, MessageDecrypter, MessageEncrypter, Tls12AeadAlgorithm, Tls13AeadAlgorithm,
+    UnsupportedOperationError,
+};
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::sign::CertifiedKey;
+use rustls::{
+    CipherSuite, CipherSuiteCommon, ConnectionTrafficSecrets, DigitallySignedStruct, SignatureScheme,
+    SupportedCipherSuite, Tls12CipherSuite, Tls13CipherSuite,
+};
+use std::cell::RefCell;
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+
+/// rustls configuration handed to reqwest for every pooled client.
+/// Building it here (rather than letting reqwest do it) gives the engine a
+/// hook into the handshake through the session store, the certificate
+/// verifier and the cipher suites.
+///
+/// `settings.tls` is expected to hold at most the entry for the host being
+/// sent to (see `HttpSettings::for_url`). Redirects can take the client to
+/// other hosts, so the entry's trust settings and identity only apply to
+/// server names it matches; its minimum version applies to the whole client.
+pub fn client_config(
+    settings: &HttpSettings,
+    session_store: Arc<dyn ClientSessionStore>,
+) -> Result<rustls::ClientConfig, String> {
+    let entry = settings.tls.first();
+    let provider = Arc::new(CryptoProvider {
+        cipher_suites: recording_suites().to_vec(),
+        ..rustls::crypto::ring::default_provider()
+    });
+
+    let versions: &[&rustls::SupportedProtocolVersion] = match entry.map(|e| e.min_version) {
+        Some(TlsVersion::Tls13) => &[&rustls::version::TLS13],
+        _ => rustls::DEFAULT_VERSIONS,
+    };
+    let builder = rustls::ClientConfig::builder_with_provider(provider.clone())
+        .with_protocol_versions(versions)
+        .map_err(|e| format!("Invalid TLS configuration: {}", e))?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(HostVerifier::new(entry, provider.clone())?));
+
+    let mut config = match entry.and_then(|e| e.client_identity.as_ref().map(|identity| (e, identity))) {
+        Some((entry, identity)) => {
+            let (chain, key) = load_identity(identity)?;
+            let key = CertifiedKey::from_der(chain, key, &provider)
+                .map_err(|e| format!("Client certificate doesn't match its key: {}", e))?;
+            builder.with_client_cert_resolver(Arc::new(HostClientCert { entry: entry.clone(), key: Arc::new(key) }))
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    // reqwest leaves ALPN alone for a preconfigured config, so mirror its choices
+    config.alpn_protocols = match settings.http_version {
+        HttpVersion::Auto => vec![b"h2".to_vec(), b"http/1.1".to_vec()],
+        HttpVersion::Http1 => vec![b"http/1.1".to_vec()],
+        HttpVersion::Http2 => vec![b"h2".to_vec()],
+    };
+    config.resumption = rustls::client::Resumption::store(session_store);
+
+    Ok(config)
+}
+
+/// Loads the certificates and keys of every TLS entry, so broken paths are
+/// reported when the settings are saved rather than on the next send
+pub fn validate(settings: &HttpSettings) -> Result<(), String> {
+    for entry in &settings.tls {
+        let single = HttpSettings { tls: vec![entry.clone()], ..settings.clone() };
+        client_config(&single, Arc::new(rustls::client::ClientSessionMemoryCache::new(1)))
+            .map_err(|e| format!("TLS settings for '{}': {}", entry.host, e))?;
+    }
+    Ok(())
+}
+
+/// What a connection's TLS handshake reported about itself
+#[derive(Debug, Default)]
+pub struct Handshake {
+    pub server_name: Option<String>,
+    pub protocol: Option<&'static str>,
+    pub cipher: Option<CipherSuite>,
+    /// Empty when a resumed session skipped certificate verification
+    pub chain: Vec<CertificateDer<'static>>,
+    pub verification_skipped: bool,
+}
+
+impl Handshake {
+    /// None when no TLS handshake completed
+    pub fn info(&self) -> Option<TlsInfo> {
+        let (protocol, cipher) = (self.protocol?, self.cipher?);
+        Some(TlsInfo {
+            protocol: protocol.to_string(),
+            cipher: cipher.as_str().map_or_else(|| format!("{:?}", cipher), str::to_string),
+            certificates: self.chain.iter().filter_map(|c| certificate_info(c)).collect(),
+            verification_skipped: self.verification_skipped,
+        })
+    }
+}
+
+tokio::task_local! {
+    static HANDSHAKE: RefCell<Handshake>;
+}
+
+/// Runs a connect future, collecting what its TLS handshake (if any) reports.
+/// rustls calls back into the engine synchronously while the future is polled.
+pub async fn capture_handshake<F: Future>(connecting: F) -> (F::Output, Handshake) {
+    HANDSHAKE
+        .scope(RefCell::new(Handshake::default()), async move {
+            let output = connecting.await;
+            (output, HANDSHAKE.with(|h| h.take()))
+        })
+        .await
+}
+
+/// Notes the server name of the handshake in progress
+pub fn record_server_name(server_name: &ServerName<'_>) {
+    record(|h| h.server_name = Some(server_name.to_str().into_owned()));
+}
+
+fn record(update: impl FnOnce(&mut Handshake)) {
+    // Outside `capture_handshake` there is nobody to report to
+    let _ = HANDSHAKE.try_with(|h| update(&mut h.borrow_mut()));
+}
+
+fn current_server_name() -> Option<String> {
+    HANDSHAKE.try_with(|h| h.borrow().server_name.clone()).ok().flatten()
+}
+
+/// Subject, issuer, validity and fingerprint of a DER certificate
+pub fn certificate_info(der: &[u8]) -> Option<CertificateInfo> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der).ok()?;
+    let timestamp = |t: x509_parser::time::ASN1Time| {
+        chrono::DateTime::from_timestamp(t.timestamp(), 0).map(|d| d.to_rfc3339()).unwrap_or_default()
+    };
+    let digest = ring::digest::digest(&ring::digest::SHA256, der);
+    Some(CertificateInfo {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        not_before: timestamp(cert.validity().not_before),
+        not_after: timestamp(cert.validity().not_after),
+        serial: cert.raw_serial_as_string(),
+        sha256: digest.as_ref().iter().map(|b| format!("{:02x}", b)).collect(),
+    })
+}
+
+fn read(path: &str, what: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("Failed to read {} '{}': {}", what, path, e))
+}
+
+fn load_certificates(path: &str, what: &str) -> Result<Vec<CertificateDer<'static>>, String> {
+    let pem = read(path, what)?;
+    let certs = CertificateDer::pem_slice_iter(&pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Invalid {} '{}': {}", what, path, e))?;
+    if certs.is_empty() {
+        return Err(format!("No certificates found in '{}'", path));
+    }
+    Ok(certs)
+}
+
+/// Certificate chain and private key from a PEM pair or a PKCS#12 archive
+fn load_identity(identity: &ClientIdentity) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), String> {
+    match identity {
+        ClientIdentity::Pem { cert_path, key_path } => {
+            let chain = load_certificates(cert_path, "client certificate")?;
+            let key_path = if key_path.trim().is_empty() { cert_path } else { key_path };
+            let key = PrivateKeyDer::from_pem_slice(&read(key_path, "client key")?)
+                .map_err(|e| format!("No private key found in '{}': {}", key_path, e))?;
+            Ok((chain, key))
+        }
+        ClientIdentity::Pkcs12 { path, password } => {
+            let store = p12_keystore::KeyStore::from_pkcs12(&read(path, "PKCS#12 file")?, password)
+                .map_err(|e| format!("Failed to open PKCS#12 file '{}': {}", path, e))?;
+            let (_, entry) = store
+                .private_key_chain()
+                .ok_or_else(|| format!("No private key found in '{}'", path))?;
+            let chain = entry.chain().iter().map(|c| CertificateDer::from(c.as_der().to_vec())).collect();
+            let key = PrivateKeyDer::Pkcs8(entry.key().to_vec().into());
+            Ok((chain, key))
+        }
+    }
+}
+
+/// Offers the client identity to the servers its entry matches
+#[derive(Debug)]
+struct HostClientCert {
+    entry: TlsHostSettings,
+    key: Arc<CertifiedKey>,
+}
+
+impl ResolvesClientCert for HostClientCert {
+    fn resolve(&self, _root_hint_subjects: &[&[u8]], _sigschemes: &[SignatureScheme]) -> Option<Arc<CertifiedKey>> {
+        match current_server_name() {
+            Some(name) if !self.entry.matches(&name, None) => None,
+            _ => Some(self.key.clone()),
+        }
+    }
+
+    fn has_certs(&self) -> bool {
+        true
+    }
+}
+
+/// Checks server certificates against the built-in roots, plus the entry's
+/// CAs or not at all for the servers it matches, and records the chain seen
+#[derive(Debug)]
+struct HostVerifier {
+    entry: Option<TlsHostSettings>,
+    builtin: Arc<WebPkiServerVerifier>,
+    /// Built-in roots plus the entry's CA certificates
+    extended: Arc<WebPkiServerVerifier>,
+}
+
+impl HostVerifier {
+    fn new(entry: Option<&TlsHostSettings>, provider: Arc<CryptoProvider>) -> Result<Self, String> {
+        let verifier = |extra: &[String]| -> Result<Arc<WebPkiServerVerifier>, String> {
+            let mut roots = rustls::RootCertStore::empty();
+            roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+            for path in extra.iter().filter(|p| !p.trim().is_empty()) {
+                for cert in load_certificates(path, "CA certificate")? {
+                    roots.add(cert).map_err(|e| format!("Invalid CA certificate '{}': {}", path, e))?;
+                }
+            }
+            WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+                .build()
+                .map_err(|e| format!("Invalid TLS configuration: {}", e))
+        };
+        let builtin = verifier(&[])?;
+        let extended = match entry {
+            Some(e) if !e.ca_certificates.is_empty() => verifier(&e.ca_certificates)?,
+            _ => builtin.clone(),
+        };
+        Ok(Self { entry: entry.cloned(), builtin, extended })
+    }
+}
+
+impl ServerCertVerifier for HostVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        record(|h| {
+            h.chain = std::iter::once(end_entity).chain(intermediates).map(|c| c.clone().into_owned()).collect();
+        });
+        let entry = self.entry.as_ref().filter(|e| e.matches(&server_name.to_str(), None));
+        match entry {
+            Some(e) if e.skip_verification => {
+                record(|h| h.verification_skipped = true);
+                Ok(ServerCertVerified::assertion())
+            }
+            Some(_) => self.extended.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now),
+            None => self.builtin.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now),
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.builtin.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.builtin.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.builtin.supported_verify_schemes()
+    }
+}
+
+/// ring's cipher suites, changed only to report which one the handshake
+/// settled on: rustls builds the record encrypter once keys are agreed.
+/// Built once and leaked, as rustls wants the suites to be `'static`.
+fn recording_suites() -> &'static [SupportedCipherSuite] {
+    static SUITES: OnceLock<Vec<SupportedCipherSuite>> = OnceLock::new();
+    SUITES.get_or_init(|| {
+        let common = |c: &CipherSuiteCommon| CipherSuiteCommon {
+            suite: c.suite,
+            hash_provider: c.hash_provider,
+            confidentiality_limit: c.confidentiality_limit,
+        };
+        rustls::crypto::ring::default_provider()
+            .cipher_suites
+            .into_iter()
+            .map(|suite| match suite {
+                SupportedCipherSuite::Tls13(s) => SupportedCipherSuite::Tls13(Box::leak(Box::new(Tls13CipherSuite {
+                    common: common(&s.common),
+                    hkdf_provider: s.hkdf_provider,
+                    aead_alg: Box::leak(Box::new(Recording { inner: s.aead_alg, suite: s.common.suite })),
+                    quic: s.quic,
+                }))),
+                SupportedCipherSuite::Tls12(s) => SupportedCipherSuite::Tls12(Box::leak(Box::new(Tls12CipherSuite {
+                    common: common(&s.common),
+                    prf_provider: s.prf_provider,
+                    kx: s.kx,
+                    sign: s.sign,
+                    aead_alg: Box::leak(Box::new(Recording { inner: s.aead_alg, suite: s.common.suite })),
+                }))),
+            })
+            .collect()
+    })
+}
+
+/// AEAD algorithm that notes its suite when an encrypter is built
+struct Recording<A: ?Sized + 'static> {
+    inner: &'static A,
+    suite: CipherSuite,
+}
+
+impl Tls13AeadAlgorithm for Recording<dyn Tls13AeadAlgorithm> {
+    fn encrypter(&self, key: AeadKey, iv: Iv) -> Box<dyn MessageEncrypter> {
+        record(|h| (h.protocol, h.cipher) = (Some("TLSv1.3"), Some(self.suite)));
+        self.inner.encrypter(key, iv)
+    }
+
+    fn decrypter(&self, key: AeadKey, iv: Iv) -> Box<dyn MessageDecrypter> {
+        self.inner.decrypter(key, iv)
+    }
+
+    fn key_len(&self) -> usize {
+        self.inner.key_len()
+    }
+
+    fn extract_keys(&self, key: AeadKey, iv: Iv) -> Result<ConnectionTrafficSecrets, UnsupportedOperationError> {
+        self.inner.extract_keys(key, iv)
+    }
+
+    fn fips(&self) -> bool {
+        self.inner.fips()
+    }
+}
+
+impl Tls12AeadAlgorithm for Recording<dyn Tls12AeadAlgorithm> {
+    fn encrypter(&self, key: AeadKey, iv: &[u8], extra: &[u8]) -> Box<dyn MessageEncrypter> {
+        record(|h| (h.protocol, h.cipher) = (Some("TLSv1.2"), Some(self.suite)));
+        self.inner.encrypter(key, iv, extra)
+    }
+
+    fn decrypter(&self, key: AeadKey, iv: &[u8]) -> Box<dyn MessageDecrypter> {
+        self.inner.decrypter(key, iv)
+    }
+
+    fn key_block_shape(&self) -> KeyBlockShape {
+        self.inner.key_block_shape()
+    }
+
+    fn extract_keys(
+        &self,
+        key: AeadKey,
+        iv: &[u8],
+        explicit: &[u8],
+    ) -> Result<ConnectionTrafficSecrets, UnsupportedOperationError> {
+        self.inner.extract_keys(key, iv, explicit)
+    }
+
+    fn fips(&self) -> bool {
+        self.inner.fips()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::pool::ClientPool;
+    use rcgen::{BasicConstraints, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa, KeyPair};
+    use rustls::server::WebPkiClientVerifier;
+    use std::path::PathBuf;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// A CA with a server certificate for localhost and a client certificate,
+    /// written as PEM files (plus a PKCS#12 archive of the client identity)
+    struct Pki {
+        dir: PathBuf,
+        ca: rcgen::Certificate,
+        server: (rcgen::Certificate, KeyPair),
+    }
+
+    impl Pki {
+        fn new(name: &str) -> Self {
+            let ca_key = KeyPair::generate().unwrap();
+            let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            params.distinguished_name.push(DnType::CommonName, "Prism Test CA");
+            let ca = params.self_signed(&ca_key).unwrap();
+
+            let leaf = |cn: &str, usage: ExtendedKeyUsagePurpose| {
+                let key = KeyPair::generate().unwrap();
+                let mut params = CertificateParams::new(vec![cn.to_string()]).unwrap();
+                params.distinguished_name.push(DnType::CommonName, cn);
+                params.extended_key_usages = vec![usage];
+                (params.signed_by(&key, &ca, &ca_key).unwrap(), key)
+            };
+            let server = leaf("localhost", ExtendedKeyUsagePurpose::ServerAuth);
+            let client = leaf("prism-client", ExtendedKeyUsagePurpose::ClientAuth);
+
+            let dir = std::env::temp_dir().join(format!("prism-tls-{}-{}", name, std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("ca.pem"), ca.pem()).unwrap();
+            std::fs::write(dir.join("client.pem"), client.0.pem()).unwrap();
+            std::fs::write(dir.join("client.key"), client.1.serialize_pem()).unwrap();
+            let mut store = p12_keystore::KeyStore::new();
+            let chain = p12_keystore::PrivateKeyChain::new(
+                client.1.serialize_der(),
+                [1u8],
+                [p12_keystore::Certificate::from_der(client.0.der()).unwrap()],
+            );
+            store.add_entry("client", p12_keystore::KeyStoreEntry::PrivateKeyChain(chain));
+            std::fs::write(dir.join("client.p12"), store.writer("secret").write().unwrap()).unwrap();
+            Self { dir, ca, server }
+        }
+
+        fn path(&self, file: &str) -> String {
+            self.dir.join(file).to_string_lossy().into_owned()
+        }
+
+        /// HTTPS server on localhost answering "ok"; client certificates
+        /// signed by the CA are required when `mtls` is set
+        async fn serve(&self, mtls: bool, versions: &[&'static rustls::SupportedProtocolVersion]) -> String {
+            let provider = Arc::new(rustls::crypto::ring::default_provider());
+            let builder = rustls::ServerConfig::builder_with_provider(provider.clone())
+                .with_protocol_versions(versions)
+                .unwrap();
+            let builder = if mtls {
+                let mut roots = rustls::RootCertStore::empty();
+                roots.add(self.ca.der().clone()).unwrap();
+                let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider).build().unwrap();
+                builder.with_client_cert_verifier(verifier)
+            } else {
+                builder.with_no_client_auth()
+            };
+            let key = PrivateKeyDer::Pkcs8(self.server.1.serialize_der().into());
+            let config = builder.with_single_cert(vec![self.server.0.der().clone(), self.ca.der().clone()], key).unwrap();
+            let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(config));
+
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let acceptor = acceptor.clone();
+                    tokio::spawn(async move {
+                        let Ok(mut stream) = acceptor.accept(stream).await else {
+                            return;
+                        };
+                        let mut buf = [0u8; 4096];
+                        let _ = stream.read(&mut buf).await;
+                        let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok").await;
+                        let _ = stream.shutdown().await;
+                    });
+                }
+            });
+            format!("https://localhost:{}/", port)
+        }
+    }
+
+    impl Drop for Pki {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    /// Sends a GET over a pooled client the way the engine does
+    async fn get(settings: &HttpSettings, url: &str) -> Result<Option<TlsInfo>, String> {
+        let url = url::Url::parse(url).unwrap();
+        let pooled = ClientPool::new().get(&settings.for_url(&url))?;
+        let response = pooled.client.get(url.as_str()).send().await.map_err(|e| format!("{:?}", e))?;
+        assert_eq!(response.text().await.unwrap(), "ok");
+        Ok(pooled.probe.tls_info(&url))
+    }
+
+    fn settings(entry: TlsHostSettings) -> HttpSettings {
+        HttpSettings { tls: vec![entry], ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn test_custom_ca_and_client_certificate() {
+        let pki = Pki::new("mtls");
+        let url = pki.serve(true, rustls::DEFAULT_VERSIONS).await;
+        let trusted = TlsHostSettings {
+            host: "localhost".to_string(),
+            ca_certificates: vec![pki.path("ca.pem")],
+            ..Default::default()
+        };
+
+        // Unknown CA, then known CA but no identity
+        assert!(get(&HttpSettings::default(), &url).await.is_err());
+        assert!(get(&settings(trusted.clone()), &url).await.is_err());
+
+        let pem = ClientIdentity::Pem { cert_path: pki.path("client.pem"), key_path: pki.path("client.key") };
+        let info = get(&settings(TlsHostSettings { client_identity: Some(pem), ..trusted.clone() }), &url)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.protocol, "TLSv1.3");
+        assert!(info.cipher.starts_with("TLS13_"), "{}", info.cipher);
+        assert!(!info.verification_skipped);
+        assert_eq!(info.certificates.len(), 2);
+        assert_eq!(info.certificates[0].subject, "CN=localhost");
+        assert_eq!(info.certificates[0].issuer, "CN=Prism Test CA");
+        assert_eq!(info.certificates[0].sha256.len(), 64);
+        assert!(info.certificates[0].not_after > info.certificates[0].not_before);
+
+        let p12 = ClientIdentity::Pkcs12 { path: pki.path("client.p12"), password: "secret".to_string() };
+        let with_p12 = settings(TlsHostSettings { client_identity: Some(p12), ..trusted.clone() });
+        assert!(get(&with_p12, &url).await.is_ok());
+
+        // The identity is only offered to the hosts the entry matches
+        let elsewhere = TlsHostSettings {
+            host: "*.localhost".to_string(),
+            ca_certificates: vec![pki.path("ca.pem")],
+            client_identity: Some(ClientIdentity::Pem { cert_path: pki.path("client.pem"), key_path: pki.path("client.key") }),
+            ..Default::default()
+        };
+        let config = client_config(&settings(elsewhere), Arc::new(rustls::client::ClientSessionMemoryCache::new(1))).unwrap();
+        let resolver = config.client_auth_cert_resolver.clone();
+        let (offered, _) = capture_handshake(async {
+            record_server_name(&ServerName::try_from("localhost").unwrap());
+            resolver.resolve(&[], &[]).is_some()
+        })
+        .await;
+        assert!(!offered);
+        assert!(resolver.resolve(&[], &[]).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_skip_verification_and_min_version() {
+        let pki = Pki::new("skip");
+        let url = pki.serve(false, &[&rustls::version::TLS12]).await;
+        let skip = TlsHostSettings {
+            host: "localhost".to_string(),
+            skip_verification: true,
+            ..Default::default()
+        };
+
+        let info = get(&settings(skip.clone()), &url).await.unwrap().unwrap();
+        assert_eq!(info.protocol, "TLSv1.2");
+        assert!(info.cipher.starts_with("TLS_ECDHE_"), "{}", info.cipher);
+        assert!(info.verification_skipped);
+        assert_eq!(info.certificates[0].subject, "CN=localhost");
+
+        // Other hosts are still verified
+        let other = TlsHostSettings { host: "example.com".to_string(), ..skip.clone() };
+        assert!(get(&settings(other), &url).await.is_err());
+
+        let tls13 = TlsHostSettings { min_version: TlsVersion::Tls13, ..skip };
+        assert!(get(&settings(tls13), &url).await.is_err());
+    }
+
+    #[test]
+    fn test_broken_files_are_reported() {
+        let entry = TlsHostSettings {
+            host: "localhost".to_string(),
+            client_identity: Some(ClientIdentity::Pkcs12 { path: "/nonexistent/id.p12".to_string(), password: String::new() }),
+            ..Default::default()
+        };
+        let err = validate(&settings(entry)).unwrap_err();
+        assert!(err.starts_with("TLS settings for 'localhost': Failed to read PKCS#12 file"), "{}", err);
+
+        let pki = Pki::new("broken");
+        let wrong_password = ClientIdentity::Pkcs12 { path: pki.path("client.p12"), password: "nope".to_string() };
+        assert!(load_identity(&wrong_password).unwrap_err().contains("Failed to open PKCS#12 file"));
+        let no_key = ClientIdentity::Pem { cert_path: pki.path("client.pem"), key_path: String::new() };
+        assert!(load_identity(&no_key).unwrap_err().contains("No private key found"));
+        let not_a_ca = TlsHostSettings {
+            host: "localhost".to_string(),
+            ca_certificates: vec![pki.path("client.key")],
+            ..Default::default()
+        };
+        assert!(validate(&settings(not_a_ca)).unwrap_err().contains("No certificates found"));
+    }
+}
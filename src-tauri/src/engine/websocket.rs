@@ -0,0 +1,497 @@
+use crate::engine::pool::ClientPool;
+use crate::engine::{auth, http, proxy, tls};
+use crate::models::{ApiRequest, ApiResponse, Direction, Frame, ResponseBody, TranscriptEntry};
+use crate::settings::HttpSettings;
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::{Connector, MaybeTlsStream, WebSocketStream};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// How long to wait for the server to answer our Close frame before dropping the connection
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A connected WebSocket whose session hasn't started yet
+pub struct Connection {
+    pub id: String,
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    socket: Socket,
+    opened_at: Instant,
+    /// Proxy the connection was tunnelled through, without credentials
+    proxy: Option<String>,
+}
+
+/// Sent to the UI as a session runs
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum WsEvent {
+    Message {
+        #[serde(rename = "sessionId")]
+        session_id: String,
+        entry: TranscriptEntry,
+    },
+    Closed {
+        #[serde(rename = "sessionId")]
+        session_id: String,
+        code: Option<u16>,
+        reason: String,
+    },
+    Error {
+        #[serde(rename = "sessionId")]
+        session_id: String,
+        message: String,
+    },
+}
+
+/// Returned to the UI once connected
+#[derive(Debug, Clone, Serialize)]
+pub struct Handshake {
+    #[serde(rename = "sessionId")]
+    pub session_id: String,
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+}
+
+/// What a finished session leaves for history
+pub struct SessionRecord {
+    /// The handshake response, with the session's duration and bytes received
+    pub response: ApiResponse,
+    pub transcript: Vec<TranscriptEntry>,
+}
+
+/// Opens a WebSocket to the request's URL with its params, headers and auth,
+/// plus the default headers and user agent from `settings`.
+/// http(s) URLs are upgraded as ws(s). Goes through the configured proxy
+/// (HTTP CONNECT or SOCKS5) unless the host is on the no-proxy list.
+pub async fn connect(req: &ApiRequest, settings: &HttpSettings, pool: &ClientPool) -> Result<Connection, String> {
+    let mut url = websocket_url(&req.url)?;
+    let merged = settings.with_override(req.settings.as_ref());
+    let settings = merged.for_url(&url);
+    for param in req.params.iter().filter(|p| p.enabled && !p.key.trim().is_empty()) {
+        url.query_pairs_mut().append_pair(&param.key, &param.value);
+    }
+
+    let mut headers = HeaderMap::new();
+    for header in req.headers.iter().filter(|h| h.enabled && !h.key.trim().is_empty()) {
+        if let (Ok(name), Ok(value)) = (HeaderName::from_str(&header.key), HeaderValue::from_str(&header.value)) {
+            headers.insert(name, value);
+        }
+    }
+    let bearer = http::oauth2_bearer(&req.auth, &merged, pool).await?;
+    auth::apply_auth(bearer.as_ref().unwrap_or(&req.auth), &mut headers, &mut url)
+        .map_err(|e| format!("Auth error: {}", e))?;
+    for header in settings.default_headers.iter().filter(|h| h.enabled && !h.key.trim().is_empty()) {
+        if let (Ok(name), Ok(value)) = (HeaderName::from_str(header.key.trim()), HeaderValue::from_str(&header.value)) {
+            headers.entry(name).or_insert(value);
+        }
+    }
+    if !settings.user_agent.is_empty() {
+        if let Ok(value) = HeaderValue::from_str(&settings.user_agent) {
+            headers.entry(reqwest::header::USER_AGENT).or_insert(value);
+        }
+    }
+
+    auth::sign_request(&req.auth, "GET", &url, &mut headers, &[])
+        .map_err(|e| format!("Auth error: {}", e))?;
+
+    let mut request = url.as_str().into_client_request()
+        .map_err(|e| format!("Invalid WebSocket request: {}", e))?;
+    // The handshake headers tungstenite generates can't be overridden
+    for (name, value) in headers {
+        if let Some(name) = name {
+            request.headers_mut().entry(name).or_insert(value);
+        }
+    }
+
+    // Same trust roots as HTTP sends; the upgrade always runs over HTTP/1.1
+    let session_store = Arc::new(rustls::client::ClientSessionMemoryCache::new(8));
+    let mut tls_config = tls::client_config(&settings, session_store)?;
+    tls_config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    let connector = Connector::Rustls(Arc::new(tls_config));
+
+    let via = proxy::for_url(&settings.proxy, &url);
+    let host = url.host_str().unwrap_or("").trim_start_matches('[').trim_end_matches(']').to_string();
+    let port = url.port_or_known_default().unwrap_or(80);
+    let handshake = async {
+        let stream = match &via {
+            Some(via) => proxy::tunnel(via, &host, port).await?,
+            None => TcpStream::connect((host.as_str(), port))
+                .await
+                .map_err(|e| format!("WebSocket connection failed: {}", e))?,
+        };
+        tokio_tungstenite::client_async_tls_with_config(request, stream, None, Some(connector))
+            .await
+            .map_err(|e| match e {
+                tungstenite::Error::Http(response) => format!(
+                    "WebSocket handshake failed: HTTP {} {}",
+                    response.status().as_u16(),
+                    response.status().canonical_reason().unwrap_or("Unknown")
+                ),
+                e => format!("WebSocket connection failed: {}", e),
+            })
+    };
+    let (socket, response) = if settings.connect_timeout_ms > 0 {
+        tokio::time::timeout(Duration::from_millis(settings.connect_timeout_ms), handshake)
+            .await
+            .map_err(|_| format!("WebSocket connection timed out after {}ms", settings.connect_timeout_ms))??
+    } else {
+        handshake.await?
+    };
+
+    Ok(Connection {
+        id: uuid::Uuid::new_v4().to_string(),
+        status: response.status().as_u16(),
+        headers: response
+            .headers()
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+            .collect(),
+        socket,
+        opened_at: Instant::now(),
+        proxy: proxy::shown_for_url(&settings.proxy, &url),
+    })
+}
+
+impl Connection {
+    pub fn handshake(&self) -> Handshake {
+        Handshake {
+            session_id: self.id.clone(),
+            status: self.status,
+            headers: self.headers.clone(),
+        }
+    }
+}
+
+/// Runs a session until either side closes it: frames from `outgoing` are sent,
+/// and everything sent or received is reported through `on_event` as it happens.
+/// Dropping the sender closes the connection; the session ends when the server
+/// answers the Close frame, or after `CLOSE_TIMEOUT` if it doesn't.
+pub async fn run(connection: Connection, mut outgoing: mpsc::UnboundedReceiver<Message>, on_event: impl Fn(WsEvent)) -> SessionRecord {
+    let Connection { id, status, headers, mut socket, opened_at, proxy } = connection;
+    let mut transcript = Vec::new();
+    let mut received_bytes = 0;
+    let mut closed_with = (None, String::new());
+    // Set once our Close frame is out
+    let mut close_deadline: Option<tokio::time::Instant> = None;
+
+    let mut record = |direction: Direction, frame: Frame| {
+        let entry = TranscriptEntry {
+            direction,
+            frame,
+            elapsed_ms: opened_at.elapsed().as_millis() as u64,
+        };
+        on_event(WsEvent::Message { session_id: id.clone(), entry: entry.clone() });
+        transcript.push(entry);
+    };
+
+    loop {
+        tokio::select! {
+            incoming = socket.next() => match incoming {
+                Some(Ok(message)) => {
+                    received_bytes += message.len();
+                    let Some(frame) = frame_of(&message) else { continue };
+                    if let Frame::Close { code, reason } = &frame {
+                        closed_with = (*code, reason.clone());
+                    }
+                    record(Direction::Received, frame);
+                }
+                Some(Err(tungstenite::Error::ConnectionClosed)) | None => break,
+                Some(Err(e)) => {
+                    on_event(WsEvent::Error { session_id: id.clone(), message: format!("WebSocket error: {}", e) });
+                    break;
+                }
+            },
+            command = outgoing.recv(), if close_deadline.is_none() => match command {
+                Some(message) => {
+                    let frame = frame_of(&message);
+                    if message.is_close() {
+                        close_deadline = Some(tokio::time::Instant::now() + CLOSE_TIMEOUT);
+                    }
+                    if let Err(e) = socket.send(message).await {
+                        on_event(WsEvent::Error { session_id: id.clone(), message: format!("Failed to send: {}", e) });
+                        break;
+                    }
+                    if let Some(frame) = frame {
+                        record(Direction::Sent, frame);
+                    }
+                }
+                None => {
+                    close_deadline = Some(tokio::time::Instant::now() + CLOSE_TIMEOUT);
+                    if socket.close(None).await.is_err() {
+                        break;
+                    }
+                }
+            },
+            _ = tokio::time::sleep_until(close_deadline.unwrap_or_else(tokio::time::Instant::now)), if close_deadline.is_some() => {
+                on_event(WsEvent::Error {
+                    session_id: id.clone(),
+                    message: format!("The server did not answer the close within {}s", CLOSE_TIMEOUT.as_secs()),
+                });
+                break;
+            }
+        }
+    }
+
+    let (code, reason) = closed_with;
+    on_event(WsEvent::Closed { session_id: id.clone(), code, reason });
+
+    let sent = transcript.iter().filter(|e| e.direction == Direction::Sent).count();
+    let received = transcript.len() - sent;
+    let response = ApiResponse {
+        status,
+        status_text: "Switching Protocols".to_string(),
+        time: opened_at.elapsed().as_millis(),
+        size: format!("{:.2}", received_bytes as f64 / 1024.0),
+        size_bytes: received_bytes as u64,
+        headers,
+        body: ResponseBody::Text {
+            text: format!("{} messages sent, {} received", sent, received),
+            charset: "utf-8".to_string(),
+        },
+        proxy,
+        ..Default::default()
+    };
+    SessionRecord { response, transcript }
+}
+
+/// Senders for the sessions currently open, keyed by connection id
+#[derive(Default)]
+pub struct WsSessions {
+    senders: Mutex<HashMap<String, mpsc::UnboundedSender<Message>>>,
+}
+
+impl WsSessions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a session; the receiver feeds its `run` loop
+    pub fn register(&self, id: &str) -> mpsc::UnboundedReceiver<Message> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.senders.lock().unwrap().insert(id.to_string(), sender);
+        receiver
+    }
+
+    /// Queues a frame for sending; binary frames are given as base64
+    pub fn send(&self, id: &str, frame: Frame) -> Result<(), String> {
+        let message = message_of(frame)?;
+        let senders = self.senders.lock().unwrap();
+        let sender = senders.get(id).ok_or_else(|| format!("No open WebSocket session '{}'", id))?;
+        sender.send(message).map_err(|_| "The WebSocket session has ended".to_string())
+    }
+
+    pub fn remove(&self, id: &str) {
+        self.senders.lock().unwrap().remove(id);
+    }
+}
+
+/// ws(s) URL for the request URL, upgrading http(s)
+fn websocket_url(raw: &str) -> Result<url::Url, String> {
+    let mut url = url::Url::parse(raw).map_err(|e| format!("Invalid URL: {}", e))?;
+    let scheme = match url.scheme() {
+        "ws" | "http" => "ws",
+        "wss" | "https" => "wss",
+        other => return Err(format!("Unsupported WebSocket scheme '{}'", other)),
+    };
+    url.set_scheme(scheme).map_err(|_| format!("Unsupported WebSocket URL '{}'", raw))?;
+    Ok(url)
+}
+
+/// The transcript form of a message; None for control frames
+fn frame_of(message: &Message) -> Option<Frame> {
+    match message {
+        Message::Text(text) => Some(Frame::Text { text: text.to_string() }),
+        Message::Binary(data) => Some(Frame::Binary {
+            base64: base64::engine::general_purpose::STANDARD.encode(data),
+        }),
+        Message::Close(close) => Some(match close {
+            Some(close) => Frame::Close { code: Some(u16::from(close.code)), reason: close.reason.to_string() },
+            None => Frame::Close { code: None, reason: String::new() },
+        }),
+        Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => None,
+    }
+}
+
+fn message_of(frame: Frame) -> Result<Message, String> {
+    Ok(match frame {
+        Frame::Text { text } => Message::text(text),
+        Frame::Binary { base64 } => Message::binary(
+            base64::engine::general_purpose::STANDARD
+                .decode(base64.trim())
+                .map_err(|e| format!("Binary frame is not valid base64: {}", e))?,
+        ),
+        Frame::Close { code, reason } => Message::Close(Some(CloseFrame {
+            code: code.map(CloseCode::from).unwrap_or(CloseCode::Normal),
+            reason: reason.into(),
+        })),
+        Frame::Event { .. } => return Err("Server-sent events can't be sent over a WebSocket".to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AuthPayload, KeyValue};
+    use crate::settings::{ProxyMode, ProxySettings};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+
+    fn request(url: String) -> ApiRequest {
+        ApiRequest {
+            params: vec![KeyValue { enabled: true, key: "room".to_string(), value: "42".to_string() }],
+            headers: vec![KeyValue { enabled: true, key: "X-Client".to_string(), value: "prism".to_string() }],
+            auth: AuthPayload {
+                auth_type: "bearer".to_string(),
+                token: Some("secret".to_string()),
+                ..Default::default()
+            },
+            ..ApiRequest::get(url)
+        }
+    }
+
+    /// Echoes every data frame back once, after checking the handshake
+    async fn echo_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            // tungstenite's handshake callback type fixes the large error variant
+            #[allow(clippy::result_large_err)]
+            let check = |request: &Request, response: Response| {
+                assert_eq!(request.uri().query(), Some("room=42"));
+                assert_eq!(request.headers()["authorization"], "Bearer secret");
+                assert_eq!(request.headers()["x-client"], "prism");
+                assert!(request.headers()["user-agent"].to_str().unwrap().starts_with("Prism/"));
+                Ok(response)
+            };
+            let mut socket = tokio_tungstenite::accept_hdr_async(stream, check).await.unwrap();
+            while let Some(Ok(message)) = socket.next().await {
+                if message.is_text() || message.is_binary() {
+                    socket.send(message).await.unwrap();
+                }
+            }
+        });
+        // http:// is upgraded to ws://
+        format!("http://{}/chat", addr)
+    }
+
+    #[tokio::test]
+    async fn test_session_transcript() {
+        let url = echo_server().await;
+        let connection = connect(&request(url), &HttpSettings::default(), &ClientPool::new()).await.unwrap();
+        assert_eq!(connection.status, 101);
+
+        let sessions = WsSessions::new();
+        let outgoing = sessions.register(&connection.id);
+        let id = connection.id.clone();
+        let (events_tx, mut events) = mpsc::unbounded_channel();
+        let session = tokio::spawn(run(connection, outgoing, move |event| {
+            events_tx.send(event).unwrap();
+        }));
+
+        // Each frame is echoed before the next one goes out, so the order is fixed
+        for frame in [Frame::Text { text: "hello".to_string() }, Frame::Binary { base64: "AQID".to_string() }] {
+            sessions.send(&id, frame).unwrap();
+            for _ in 0..2 {
+                assert!(matches!(events.recv().await, Some(WsEvent::Message { .. })));
+            }
+        }
+        assert!(sessions.send(&id, Frame::Binary { base64: "%%".to_string() }).is_err());
+        sessions.send(&id, Frame::Close { code: Some(1000), reason: "done".to_string() }).unwrap();
+
+        let record = session.await.unwrap();
+        sessions.remove(&id);
+        let summary: Vec<(Direction, Frame)> = record.transcript.into_iter().map(|e| (e.direction, e.frame)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Direction::Sent, Frame::Text { text: "hello".to_string() }),
+                (Direction::Received, Frame::Text { text: "hello".to_string() }),
+                (Direction::Sent, Frame::Binary { base64: "AQID".to_string() }),
+                (Direction::Received, Frame::Binary { base64: "AQID".to_string() }),
+                (Direction::Sent, Frame::Close { code: Some(1000), reason: "done".to_string() }),
+                (Direction::Received, Frame::Close { code: Some(1000), reason: "done".to_string() }),
+            ]
+        );
+        assert_eq!(record.response.status, 101);
+        assert_eq!(record.response.body.text().as_deref(), Some("3 messages sent, 3 received"));
+        assert!(matches!(events.recv().await, Some(WsEvent::Message { .. })));
+        assert!(matches!(events.recv().await, Some(WsEvent::Message { .. })));
+        assert!(matches!(events.recv().await, Some(WsEvent::Closed { code: Some(1000), .. })));
+        assert!(sessions.send(&id, Frame::Text { text: "late".to_string() }).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_close_times_out_when_server_is_silent() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            // Never reads, so the Close frame is never answered
+            let _socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+        });
+        let req = ApiRequest::get(format!("ws://{}/", addr));
+        let connection = connect(&req, &HttpSettings::default(), &ClientPool::new()).await.unwrap();
+
+        tokio::time::pause();
+        let (sender, outgoing) = mpsc::unbounded_channel();
+        drop(sender);
+        let (events_tx, mut events) = mpsc::unbounded_channel();
+        run(connection, outgoing, move |event| events_tx.send(event).unwrap()).await;
+        assert!(matches!(events.recv().await, Some(WsEvent::Error { message, .. }) if message.contains("did not answer the close")));
+        assert!(matches!(events.recv().await, Some(WsEvent::Closed { code: None, .. })));
+    }
+
+    #[tokio::test]
+    async fn test_connects_through_http_proxy() {
+        let target = echo_server().await;
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let (mut client, _) = listener.accept().await.unwrap();
+            let mut head = Vec::new();
+            while !head.ends_with(b"\r\n\r\n") {
+                head.push(client.read_u8().await.unwrap());
+            }
+            let head = String::from_utf8(head).unwrap();
+            let authority = head.strip_prefix("CONNECT ").unwrap().split(' ').next().unwrap().to_string();
+            let mut upstream = TcpStream::connect(authority).await.unwrap();
+            client.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n").await.unwrap();
+            let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+        });
+
+        let settings = HttpSettings {
+            proxy: ProxySettings {
+                mode: ProxyMode::Manual,
+                url: proxy_addr.to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let connection = connect(&request(target), &settings, &ClientPool::new()).await.unwrap();
+        assert_eq!(connection.status, 101);
+        assert_eq!(connection.proxy, Some(format!("http://{}", proxy_addr)));
+    }
+
+    #[test]
+    fn test_websocket_url() {
+        assert_eq!(websocket_url("https://example.com/ws?a=1").unwrap().as_str(), "wss://example.com/ws?a=1");
+        assert_eq!(websocket_url("ws://localhost:8080").unwrap().as_str(), "ws://localhost:8080/");
+        assert!(websocket_url("ftp://example.com").is_err());
+    }
+}
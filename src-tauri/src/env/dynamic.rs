@@ -0,0 +1,182 @@
+use base64::Engine;
+use chrono::{Duration, Utc};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+
+/// Names of the built-ins that `evaluate` understands
+pub const BUILTINS: &[&str] = &[
+    "$uuid", "$guid", "$timestamp", "$timestampMs", "$isoTimestamp", "$isoDate",
+    "$randomInt", "$randomString", "$randomEmail", "$base64",
+];
+
+/// Evaluates a `$`-prefixed built-in such as `$uuid` or `$randomInt 1 10`.
+/// `lookup` resolves other variables (used by `$base64`).
+/// Returns `None` for unknown built-ins so they stay unresolved.
+pub fn evaluate<F>(expr: &str, lookup: F) -> Option<Result<String, String>>
+where
+    F: FnOnce(&str) -> Result<Option<String>, String>,
+{
+    let mut parts = expr.split_whitespace();
+    let name = parts.next()?;
+    let args: Vec<&str> = parts.collect();
+
+    let value = match name {
+        "$uuid" | "$guid" => Ok(uuid::Uuid::new_v4().to_string()),
+        "$timestamp" => offset_now(&args).map(|t| t.timestamp().to_string()),
+        "$timestampMs" => offset_now(&args).map(|t| t.timestamp_millis().to_string()),
+        "$isoTimestamp" => offset_now(&args).map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)),
+        "$isoDate" => offset_now(&args).map(|t| t.format("%Y-%m-%d").to_string()),
+        "$randomInt" => random_int(&args),
+        "$randomString" => random_string(&args),
+        "$randomEmail" => Ok(format!("user-{}@example.com", alphanumeric(8).to_lowercase())),
+        "$base64" => {
+            let var = match args.as_slice() {
+                [var] => var,
+                _ => return Some(Err("$base64 expects a single variable name".to_string())),
+            };
+            match lookup(var) {
+                Ok(Some(value)) => Ok(base64::engine::general_purpose::STANDARD.encode(value)),
+                Ok(None) => Err(format!("$base64: variable '{}' is not defined", var)),
+                Err(e) => Err(e),
+            }
+        }
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+/// Current time shifted by an optional offset like `+1d`, `-30m`, `+2w`
+fn offset_now(args: &[&str]) -> Result<chrono::DateTime<Utc>, String> {
+    let now = Utc::now();
+    match args {
+        [] => Ok(now),
+        [offset] => now
+            .checked_add_signed(parse_offset(offset)?)
+            .ok_or_else(|| format!("Time offset '{}' is out of range", offset)),
+        _ => Err("Timestamp variables accept a single offset, e.g. +1d".to_string()),
+    }
+}
+
+fn parse_offset(offset: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid time offset '{}', expected e.g. +1h or -30m", offset);
+
+    let (sign, rest) = match offset.chars().next() {
+        Some('+') => (1, &offset[1..]),
+        Some('-') => (-1, &offset[1..]),
+        _ => return Err(invalid()),
+    };
+    // The unit is the last character, which may not be ASCII in bad input
+    let Some((split, unit)) = rest.char_indices().last() else {
+        return Err(invalid());
+    };
+    let amount: i64 = rest[..split].parse().map_err(|_| invalid())?;
+    let amount = sign * amount;
+
+    let duration = match unit {
+        's' => Duration::try_seconds(amount),
+        'm' => Duration::try_minutes(amount),
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        'w' => Duration::try_weeks(amount),
+        _ => None,
+    };
+    duration.ok_or_else(invalid)
+}
+
+fn random_int(args: &[&str]) -> Result<String, String> {
+    let parse = |s: &str| s.parse::<i64>().map_err(|_| format!("$randomInt: '{}' is not an integer", s));
+
+    let (min, max) = match args {
+        [] => (0, 1000),
+        [max] => (0, parse(max)?),
+        [min, max] => (parse(min)?, parse(max)?),
+        _ => return Err("$randomInt accepts at most two bounds".to_string()),
+    };
+    if min > max {
+        return Err(format!("$randomInt: min {} is greater than max {}", min, max));
+    }
+
+    Ok(rand::thread_rng().gen_range(min..=max).to_string())
+}
+
+fn random_string(args: &[&str]) -> Result<String, String> {
+    let len = match args {
+        [] => 16,
+        [len] => len.parse::<usize>().map_err(|_| format!("$randomString: '{}' is not a length", len))?,
+        _ => return Err("$randomString accepts a single length".to_string()),
+    };
+    Ok(alphanumeric(len))
+}
+
+fn alphanumeric(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(expr: &str) -> Option<Result<String, String>> {
+        evaluate(expr, |_| Ok(None))
+    }
+
+    #[test]
+    fn test_uuid() {
+        let value = eval("$uuid").unwrap().unwrap();
+        assert!(uuid::Uuid::parse_str(&value).is_ok());
+    }
+
+    #[test]
+    fn test_timestamp_offset() {
+        let now: i64 = eval("$timestamp").unwrap().unwrap().parse().unwrap();
+        let later: i64 = eval("$timestamp +1h").unwrap().unwrap().parse().unwrap();
+        assert!((later - now - 3600).abs() <= 1);
+
+        assert!(eval("$timestamp 1h").unwrap().is_err());
+        assert!(eval("$isoDate -2x").unwrap().is_err());
+        assert!(eval("$isoTimestamp +1é").unwrap().is_err());
+        assert!(eval("$timestamp +é").unwrap().is_err());
+        assert!(eval("$isoTimestamp +99999999999999d").unwrap().is_err());
+        assert!(eval("$timestamp +9999999999w").unwrap().is_err());
+    }
+
+    #[test]
+    fn test_random_int_bounds() {
+        for _ in 0..50 {
+            let value: i64 = eval("$randomInt 5 7").unwrap().unwrap().parse().unwrap();
+            assert!((5..=7).contains(&value));
+        }
+        assert!(eval("$randomInt 9 1").unwrap().is_err());
+    }
+
+    #[test]
+    fn test_random_string_length() {
+        assert_eq!(eval("$randomString 24").unwrap().unwrap().len(), 24);
+    }
+
+    #[test]
+    fn test_base64_of_variable() {
+        let value = evaluate("$base64 CREDS", |name| {
+            assert_eq!(name, "CREDS");
+            Ok(Some("user:pass".to_string()))
+        });
+        assert_eq!(value.unwrap().unwrap(), "dXNlcjpwYXNz");
+    }
+
+    #[test]
+    fn test_builtins_table_matches_evaluate() {
+        for name in BUILTINS {
+            assert!(eval(name).is_some(), "{} is listed but not evaluated", name);
+        }
+    }
+
+    #[test]
+    fn test_unknown_builtin() {
+        assert!(eval("$nope").is_none());
+    }
+}
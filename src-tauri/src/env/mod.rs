@@ -1,7 +1,8 @@
 pub mod models;
 pub mod store;
 pub mod resolver;
+pub mod dynamic;
 
-pub use models::EnvVar;
+pub use models::{EnvVar, Environment};
 pub use store::EnvStore;
-pub use resolver::resolve_variables;
+pub use resolver::{build_variable_map, resolve_request, resolve_variables_checked, RequestResolution, Resolved};
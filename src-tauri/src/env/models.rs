@@ -9,3 +9,11 @@ pub struct EnvVar {
     #[serde(default = "default_enabled")]
     pub enabled: bool,
 } 
+
+/// A named set of variables (e.g. dev, staging, prod)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Environment {
+    pub id: i64,
+    pub name: String,
+    pub active: bool,
+}
@@ -1,10 +1,216 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use serde::Serialize;
+use crate::models::{ApiRequest, AssertionCheck, ExtractionSource, RequestBody};
+use super::dynamic;
+use super::models::EnvVar;
+
+/// Outcome of resolving a single string
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Resolved {
+    pub value: String,
+    pub unresolved: Vec<String>,
+}
+
+/// A request with variables substituted, plus the names that could not be resolved
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestResolution {
+    pub request: ApiRequest,
+    pub unresolved: Vec<String>,
+    /// Concrete values generated for `{{$...}}` built-ins during this resolution
+    #[serde(rename = "dynamicValues")]
+    pub dynamic_values: BTreeMap<String, String>,
+}
+
+/// State shared while expanding one string or one whole request
+struct Context<'a> {
+    env_vars: &'a HashMap<String, String>,
+    stack: Vec<String>,
+    unresolved: Vec<String>,
+    dynamic_values: BTreeMap<String, String>,
+}
+
+impl<'a> Context<'a> {
+    fn new(env_vars: &'a HashMap<String, String>) -> Self {
+        Self {
+            env_vars,
+            stack: Vec::new(),
+            unresolved: Vec::new(),
+            dynamic_values: BTreeMap::new(),
+        }
+    }
+}
+
+/// Builds the lookup map from global and environment scopes.
+/// Disabled variables are skipped; environment values override globals.
+pub fn build_variable_map(globals: Vec<EnvVar>, env_vars: Vec<EnvVar>) -> HashMap<String, String> {
+    globals
+        .into_iter()
+        .chain(env_vars)
+        .filter(|var| var.enabled)
+        .map(|var| (var.key, var.value))
+        .collect()
+}
 
 /// Resolves {{VAR}} syntax in strings using provided environment variables
 /// - Exact match, case-sensitive
-/// - Single pass, no recursion
-/// - Unresolved variables remain unchanged
+/// - Nested references are expanded ({{BASE}} = "{{HOST}}/api")
+/// - `$` built-ins ({{$uuid}}, {{$timestamp +1h}}, ...) are generated
+/// - Unresolved variables and circular references remain unchanged
 pub fn resolve_variables(input: &str, env_vars: &HashMap<String, String>) -> String {
+    resolve_variables_checked(input, env_vars)
+        .map(|resolved| resolved.value)
+        .unwrap_or_else(|_| input.to_string())
+}
+
+/// Like `resolve_variables`, but reports unresolved names and fails on circular references
+pub fn resolve_variables_checked(input: &str, env_vars: &HashMap<String, String>) -> Result<Resolved, String> {
+    let mut ctx = Context::new(env_vars);
+    let value = expand(input, &mut ctx)?;
+    Ok(Resolved { value, unresolved: ctx.unresolved })
+}
+
+/// Resolves variables in every field of an ApiRequest that supports them.
+/// Each `$` built-in is evaluated once, so repeated uses share one value.
+pub fn resolve_request(mut req: ApiRequest, env_vars: &HashMap<String, String>) -> Result<RequestResolution, String> {
+    let mut ctx = Context::new(env_vars);
+    let mut resolve = |input: &str| expand(input, &mut ctx);
+
+    // Resolve URL
+    req.url = resolve(&req.url)?;
+
+    // Resolve params
+    for param in &mut req.params {
+        param.key = resolve(&param.key)?;
+        param.value = resolve(&param.value)?;
+    }
+
+    // Resolve headers
+    for header in &mut req.headers {
+        header.key = resolve(&header.key)?;
+        header.value = resolve(&header.value)?;
+    }
+
+    // Resolve body, including file paths
+    match &mut req.body {
+        RequestBody::None => {}
+        RequestBody::Raw { content, content_type } => {
+            *content = resolve(content)?;
+            if let Some(content_type) = content_type {
+                *content_type = resolve(content_type)?;
+            }
+        }
+        RequestBody::Urlencoded { fields } => {
+            for field in fields {
+                field.key = resolve(&field.key)?;
+                field.value = resolve(&field.value)?;
+            }
+        }
+        RequestBody::Multipart { parts } => {
+            for part in parts {
+                part.key = resolve(&part.key)?;
+                part.value = resolve(&part.value)?;
+                if let Some(content_type) = &mut part.content_type {
+                    *content_type = resolve(content_type)?;
+                }
+            }
+        }
+        RequestBody::Binary { path, content_type } => {
+            *path = resolve(path)?;
+            if let Some(content_type) = content_type {
+                *content_type = resolve(content_type)?;
+            }
+        }
+        RequestBody::GraphQl { query, variables, operation_name } => {
+            *query = resolve(query)?;
+            *variables = resolve(variables)?;
+            if let Some(operation_name) = operation_name {
+                *operation_name = resolve(operation_name)?;
+            }
+        }
+    }
+
+    // Resolve auth fields
+    let auth = &mut req.auth;
+    for field in [
+        &mut auth.token,
+        &mut auth.api_key,
+        &mut auth.api_value,
+        &mut auth.username,
+        &mut auth.password,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        *field = resolve(field)?;
+    }
+    if let Some(oauth2) = &mut auth.oauth2 {
+        for field in [
+            &mut oauth2.token_url,
+            &mut oauth2.auth_url,
+            &mut oauth2.client_id,
+            &mut oauth2.client_secret,
+            &mut oauth2.scope,
+            &mut oauth2.username,
+            &mut oauth2.password,
+        ] {
+            *field = resolve(field)?;
+        }
+    }
+    if let Some(awsv4) = &mut auth.awsv4 {
+        for field in [
+            &mut awsv4.access_key_id,
+            &mut awsv4.secret_access_key,
+            &mut awsv4.session_token,
+            &mut awsv4.region,
+            &mut awsv4.service,
+        ] {
+            *field = resolve(field)?;
+        }
+    }
+
+    // Resolve assertion expectations
+    for assertion in &mut req.assertions {
+        match &mut assertion.check {
+            AssertionCheck::HeaderPresent { name } => *name = resolve(name)?,
+            AssertionCheck::HeaderMatches { name, pattern } => {
+                *name = resolve(name)?;
+                *pattern = resolve(pattern)?;
+            }
+            AssertionCheck::JsonPathEquals { path, value } => {
+                *path = resolve(path)?;
+                if let serde_json::Value::String(text) = value {
+                    *text = resolve(text)?;
+                }
+            }
+            AssertionCheck::JsonPathExists { path } | AssertionCheck::JsonPathType { path, .. } => {
+                *path = resolve(path)?;
+            }
+            AssertionCheck::BodyContains { value } => *value = resolve(value)?,
+            AssertionCheck::BodyMatches { pattern } => *pattern = resolve(pattern)?,
+            AssertionCheck::StatusEquals { .. }
+            | AssertionCheck::StatusInRange { .. }
+            | AssertionCheck::ResponseTimeBelow { .. }
+            | AssertionCheck::SizeBelow { .. } => {}
+        }
+    }
+
+    // Resolve extraction rules (the target variable name is taken literally)
+    for extraction in &mut req.extractions {
+        match &mut extraction.source {
+            ExtractionSource::JsonPath { path } => *path = resolve(path)?,
+            ExtractionSource::Header { name } | ExtractionSource::Cookie { name } => *name = resolve(name)?,
+            ExtractionSource::Regex { pattern, .. } => *pattern = resolve(pattern)?,
+        }
+    }
+
+    Ok(RequestResolution {
+        request: req,
+        unresolved: ctx.unresolved,
+        dynamic_values: ctx.dynamic_values,
+    })
+}
+
+fn expand(input: &str, ctx: &mut Context) -> Result<String, String> {
     let mut result = String::with_capacity(input.len());
     let mut chars = input.chars().peekable();
     
@@ -16,16 +222,26 @@ pub fn resolve_variables(input: &str, env_vars: &HashMap<String, String>) -> Str
                     chars.next(); // consume second {
                     
                     // Extract variable name
-                    let var_name = extract_var_name(&mut chars);
-                    
-                    // Try to resolve
-                    if let Some(value) = env_vars.get(&var_name) {
-                        result.push_str(value);
-                    } else {
-                        // Keep original if not found
+                    let (var_name, closed) = extract_var_name(&mut chars);
+                    if !closed {
+                        // Malformed, keep as-is
                         result.push_str("{{");
                         result.push_str(&var_name);
-                        result.push_str("}}");
+                        continue;
+                    }
+                    
+                    // Try to resolve
+                    match lookup(&var_name, ctx)? {
+                        Some(value) => result.push_str(&value),
+                        None => {
+                            // Keep original if not found
+                            result.push_str("{{");
+                            result.push_str(&var_name);
+                            result.push_str("}}");
+                            if !ctx.unresolved.contains(&var_name) {
+                                ctx.unresolved.push(var_name);
+                            }
+                        }
                     }
                     continue;
                 }
@@ -34,32 +250,58 @@ pub fn resolve_variables(input: &str, env_vars: &HashMap<String, String>) -> Str
         result.push(ch);
     }
     
-    result
+    Ok(result)
+}
+
+/// Fully expands a single variable, or returns None if it is not defined
+fn lookup(var_name: &str, ctx: &mut Context) -> Result<Option<String>, String> {
+    if let Some(value) = ctx.env_vars.get(var_name) {
+        if let Some(start) = ctx.stack.iter().position(|name| name == var_name) {
+            let mut cycle = ctx.stack[start..].to_vec();
+            cycle.push(var_name.to_string());
+            return Err(format!("Circular variable reference: {}", cycle.join(" -> ")));
+        }
+        ctx.stack.push(var_name.to_string());
+        let expanded = expand(value, ctx)?;
+        ctx.stack.pop();
+        return Ok(Some(expanded));
+    }
+
+    if !var_name.starts_with('$') {
+        return Ok(None);
+    }
+
+    // Built-ins are generated once per resolution
+    if let Some(value) = ctx.dynamic_values.get(var_name) {
+        return Ok(Some(value.clone()));
+    }
+    match dynamic::evaluate(var_name, |name| lookup(name, ctx)) {
+        Some(value) => {
+            let value = value?;
+            ctx.dynamic_values.insert(var_name.to_string(), value.clone());
+            Ok(Some(value))
+        }
+        None => Ok(None),
+    }
 }
 
-fn extract_var_name(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+/// Reads up to the closing `}}`; the flag is false if the input ended first
+fn extract_var_name(chars: &mut std::iter::Peekable<std::str::Chars>) -> (String, bool) {
     let mut var_name = String::new();
-    let mut found_closing = false;
     
     while let Some(ch) = chars.next() {
         if ch == '}' {
             if let Some(&next_ch) = chars.peek() {
                 if next_ch == '}' {
                     chars.next(); // consume second }
-                    found_closing = true;
-                    break;
+                    return (var_name, true);
                 }
             }
         }
         var_name.push(ch);
     }
     
-    if !found_closing {
-        // Malformed, return as-is (will be wrapped back with {{}} by caller)
-        var_name
-    } else {
-        var_name
-    }
+    (var_name, false)
 }
 
 #[cfg(test)]
@@ -120,4 +362,161 @@ mod tests {
         let result = resolve_variables("{single}", &vars);
         assert_eq!(result, "{single}");
     }
+
+    #[test]
+    fn test_nested_variables() {
+        let mut vars = HashMap::new();
+        vars.insert("HOST".to_string(), "example.com".to_string());
+        vars.insert("BASE".to_string(), "https://{{HOST}}/api".to_string());
+        
+        let result = resolve_variables("{{BASE}}/users", &vars);
+        assert_eq!(result, "https://example.com/api/users");
+    }
+
+    #[test]
+    fn test_cycle_is_reported() {
+        let mut vars = HashMap::new();
+        vars.insert("A".to_string(), "{{B}}".to_string());
+        vars.insert("B".to_string(), "x{{A}}".to_string());
+        
+        let err = resolve_variables_checked("{{A}}", &vars).unwrap_err();
+        assert!(err.contains("A -> B -> A"), "{}", err);
+
+        // Lenient variant leaves the input untouched
+        assert_eq!(resolve_variables("{{A}}", &vars), "{{A}}");
+    }
+
+    #[test]
+    fn test_unresolved_names_are_listed() {
+        let mut vars = HashMap::new();
+        vars.insert("BASE".to_string(), "{{HOST}}/api".to_string());
+        
+        let resolved = resolve_variables_checked("{{BASE}}/{{ID}}?t={{ID}}", &vars).unwrap();
+        assert_eq!(resolved.value, "{{HOST}}/api/{{ID}}?t={{ID}}");
+        assert_eq!(resolved.unresolved, vec!["HOST".to_string(), "ID".to_string()]);
+    }
+
+    #[test]
+    fn test_unclosed_braces() {
+        let vars = HashMap::new();
+        
+        let result = resolve_variables("{{OPEN", &vars);
+        assert_eq!(result, "{{OPEN");
+    }
+
+    #[test]
+    fn test_disabled_vars_are_excluded() {
+        let globals = vec![
+            EnvVar { key: "HOST".to_string(), value: "global.local".to_string(), enabled: true },
+        ];
+        let env_vars = vec![
+            EnvVar { key: "TOKEN".to_string(), value: "secret".to_string(), enabled: false },
+            EnvVar { key: "HOST".to_string(), value: "dev.local".to_string(), enabled: true },
+        ];
+        
+        let map = build_variable_map(globals, env_vars);
+        assert_eq!(map.get("HOST").map(String::as_str), Some("dev.local"));
+        assert!(!map.contains_key("TOKEN"));
+    }
+
+    #[test]
+    fn test_resolve_request_collects_unresolved() {
+        use crate::models::{AuthPayload, KeyValue};
+
+        let mut vars = HashMap::new();
+        vars.insert("HOST".to_string(), "example.com".to_string());
+
+        let req = ApiRequest {
+            method: "GET".to_string(),
+            url: "https://{{HOST}}/users".to_string(),
+            headers: vec![
+                KeyValue {
+                    enabled: true,
+                    key: "X-Trace".to_string(),
+                    value: "{{TRACE_ID}}".to_string(),
+                },
+            ],
+            auth: AuthPayload {
+                auth_type: "bearer".to_string(),
+                token: Some("{{TOKEN}}".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let resolution = resolve_request(req, &vars).unwrap();
+        assert_eq!(resolution.request.url, "https://example.com/users");
+        assert_eq!(resolution.unresolved, vec!["TRACE_ID".to_string(), "TOKEN".to_string()]);
+    }
+
+    #[test]
+    fn test_dynamic_values_are_shared_per_request() {
+        use crate::models::KeyValue;
+
+        let vars = HashMap::new();
+        let req = ApiRequest {
+            method: "POST".to_string(),
+            url: "https://example.com/orders/{{$uuid}}".to_string(),
+            headers: vec![
+                KeyValue {
+                    enabled: true,
+                    key: "Idempotency-Key".to_string(),
+                    value: "{{$uuid}}".to_string(),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let resolution = resolve_request(req, &vars).unwrap();
+        let id = resolution.dynamic_values.get("$uuid").unwrap();
+        assert_eq!(resolution.request.url, format!("https://example.com/orders/{}", id));
+        assert_eq!(&resolution.request.headers[0].value, id);
+        assert!(resolution.unresolved.is_empty());
+    }
+
+    #[test]
+    fn test_body_file_paths_resolve() {
+        use crate::models::{FormPart, FormPartKind};
+
+        let mut vars = HashMap::new();
+        vars.insert("FIXTURES".to_string(), "/srv/fixtures".to_string());
+        let req = ApiRequest {
+            method: "POST".to_string(),
+            url: "https://example.com/upload".to_string(),
+            body: RequestBody::Multipart {
+                parts: vec![FormPart {
+                    enabled: true,
+                    key: "avatar".to_string(),
+                    kind: FormPartKind::File,
+                    value: "{{FIXTURES}}/avatar.png".to_string(),
+                    content_type: None,
+                }],
+            },
+            ..Default::default()
+        };
+
+        let resolution = resolve_request(req, &vars).unwrap();
+        let RequestBody::Multipart { parts } = resolution.request.body else {
+            panic!("expected a multipart body");
+        };
+        assert_eq!(parts[0].value, "/srv/fixtures/avatar.png");
+    }
+
+    #[test]
+    fn test_dynamic_base64_uses_env() {
+        let mut vars = HashMap::new();
+        vars.insert("USER".to_string(), "admin".to_string());
+        vars.insert("CREDS".to_string(), "{{USER}}:pw".to_string());
+
+        let result = resolve_variables("Basic {{$base64 CREDS}}", &vars);
+        assert_eq!(result, "Basic YWRtaW46cHc=");
+    }
+
+    #[test]
+    fn test_unknown_dynamic_is_unresolved() {
+        let vars = HashMap::new();
+        let resolved = resolve_variables_checked("{{$unknown}}", &vars).unwrap();
+        assert_eq!(resolved.value, "{{$unknown}}");
+        assert_eq!(resolved.unresolved, vec!["$unknown".to_string()]);
+    }
 }
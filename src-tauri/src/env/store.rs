@@ -1,6 +1,8 @@
-use rusqlite::{Connection, Result as SqlResult};
+use rusqlite::{Connection, OptionalExtension, Result as SqlResult};
 use std::sync::Mutex;
-use super::models::EnvVar;
+use super::models::{EnvVar, Environment};
+
+const DEFAULT_ENVIRONMENT: &str = "Default";
 
 pub struct EnvStore {
     conn: Mutex<Connection>,
@@ -8,11 +10,32 @@ pub struct EnvStore {
 
 impl EnvStore {
     pub fn new(db_path: &str) -> SqlResult<Self> {
-        let conn = Connection::open(db_path)?;
-        
-        // Create env_vars table if not exists (includes enabled flag)
+        let mut conn = Connection::open(db_path)?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS environments (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                active INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+
+        // Variables belonging to a named environment
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS environment_vars (
+                environment_id INTEGER NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                enabled INTEGER DEFAULT 1,
+                PRIMARY KEY (environment_id, key)
+            )",
+            [],
+        )?;
+
+        // Global scope, used as fallback when the active environment has no match
         conn.execute(
-            "CREATE TABLE IF NOT EXISTS env_vars (
+            "CREATE TABLE IF NOT EXISTS global_vars (
                 key TEXT PRIMARY KEY,
                 value TEXT NOT NULL,
                 enabled INTEGER DEFAULT 1
@@ -20,31 +43,76 @@ impl EnvStore {
             [],
         )?;
 
-        // Ensure backward compatibility: add 'enabled' column if missing
-        {
-            let mut stmt = conn.prepare("PRAGMA table_info(env_vars)")?;
-            let mut rows = stmt.query([])?;
-            let mut has_enabled = false;
-            while let Some(row) = rows.next()? {
-                let col_name: String = row.get(1)?;
-                if col_name == "enabled" {
-                    has_enabled = true;
-                    break;
-                }
-            }
-            if !has_enabled {
-                conn.execute("ALTER TABLE env_vars ADD COLUMN enabled INTEGER DEFAULT 1", [])?;
-            }
-        }
+        migrate_legacy_env_vars(&mut conn)?;
+        ensure_active_environment(&conn)?;
 
         Ok(EnvStore {
             conn: Mutex::new(conn),
         })
     }
 
+    /// Variables of the active environment
     pub fn get_all(&self) -> SqlResult<Vec<EnvVar>> {
+        let env_id = self.active_environment()?.id;
+        self.get_vars(env_id)
+    }
+
+    /// Sets a variable in the active environment
+    pub fn set(&self, key: &str, value: &str, enabled: bool) -> SqlResult<()> {
+        let env_id = self.active_environment()?.id;
+        self.set_var(env_id, key, value, enabled)
+    }
+
+    /// Deletes a variable from the active environment
+    pub fn delete(&self, key: &str) -> SqlResult<()> {
+        let env_id = self.active_environment()?.id;
+        self.delete_var(env_id, key)
+    }
+
+    pub fn get_vars(&self, environment_id: i64) -> SqlResult<Vec<EnvVar>> {
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare("SELECT key, value, COALESCE(enabled,1) FROM env_vars ORDER BY key")?;
+        let mut stmt = conn.prepare(
+            "SELECT key, value, COALESCE(enabled,1) FROM environment_vars
+             WHERE environment_id = ?1 ORDER BY key"
+        )?;
+
+        let vars = stmt.query_map([environment_id], |row| {
+            Ok(EnvVar {
+                key: row.get(0)?,
+                value: row.get(1)?,
+                enabled: row.get(2)?,
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for var in vars {
+            result.push(var?);
+        }
+        Ok(result)
+    }
+
+    pub fn set_var(&self, environment_id: i64, key: &str, value: &str, enabled: bool) -> SqlResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO environment_vars (environment_id, key, value, enabled)
+             VALUES (?1, ?2, ?3, ?4)",
+            (environment_id, key, value, enabled as i32),
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_var(&self, environment_id: i64, key: &str) -> SqlResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM environment_vars WHERE environment_id = ?1 AND key = ?2",
+            (environment_id, key),
+        )?;
+        Ok(())
+    }
+
+    pub fn get_globals(&self) -> SqlResult<Vec<EnvVar>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT key, value, COALESCE(enabled,1) FROM global_vars ORDER BY key")?;
 
         let vars = stmt.query_map([], |row| {
             Ok(EnvVar {
@@ -61,18 +129,264 @@ impl EnvStore {
         Ok(result)
     }
 
-    pub fn set(&self, key: &str, value: &str, enabled: bool) -> SqlResult<()> {
+    pub fn set_global(&self, key: &str, value: &str, enabled: bool) -> SqlResult<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT OR REPLACE INTO env_vars (key, value, enabled) VALUES (?1, ?2, ?3)",
+            "INSERT OR REPLACE INTO global_vars (key, value, enabled) VALUES (?1, ?2, ?3)",
             (key, value, enabled as i32),
         )?;
         Ok(())
     }
 
-    pub fn delete(&self, key: &str) -> SqlResult<()> {
+    pub fn delete_global(&self, key: &str) -> SqlResult<()> {
         let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM env_vars WHERE key = ?1", [key])?;
+        conn.execute("DELETE FROM global_vars WHERE key = ?1", [key])?;
         Ok(())
     }
+
+    pub fn list_environments(&self) -> SqlResult<Vec<Environment>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT id, name, active FROM environments ORDER BY id")?;
+
+        let envs = stmt.query_map([], |row| {
+            Ok(Environment {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                active: row.get(2)?,
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for env in envs {
+            result.push(env?);
+        }
+        Ok(result)
+    }
+
+    pub fn active_environment(&self) -> SqlResult<Environment> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, name, active FROM environments WHERE active = 1 LIMIT 1",
+            [],
+            |row| {
+                Ok(Environment {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    active: row.get(2)?,
+                })
+            },
+        )
+    }
+
+    pub fn create_environment(&self, name: &str) -> SqlResult<Environment> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("INSERT INTO environments (name, active) VALUES (?1, 0)", [name])?;
+        Ok(Environment {
+            id: conn.last_insert_rowid(),
+            name: name.to_string(),
+            active: false,
+        })
+    }
+
+    pub fn rename_environment(&self, id: i64, name: &str) -> SqlResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("UPDATE environments SET name = ?1 WHERE id = ?2", (name, id))?;
+        Ok(())
+    }
+
+    /// Creates a copy of an environment, including all of its variables
+    pub fn clone_environment(&self, id: i64, name: &str) -> SqlResult<Environment> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        tx.execute("INSERT INTO environments (name, active) VALUES (?1, 0)", [name])?;
+        let new_id = tx.last_insert_rowid();
+        tx.execute(
+            "INSERT INTO environment_vars (environment_id, key, value, enabled)
+             SELECT ?1, key, value, enabled FROM environment_vars WHERE environment_id = ?2",
+            (new_id, id),
+        )?;
+        tx.commit()?;
+
+        Ok(Environment {
+            id: new_id,
+            name: name.to_string(),
+            active: false,
+        })
+    }
+
+    /// Deletes an environment and its variables. If it was active, another
+    /// environment becomes active (a fresh "Default" one if none remain).
+    pub fn delete_environment(&self, id: i64) -> SqlResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        tx.execute("DELETE FROM environment_vars WHERE environment_id = ?1", [id])?;
+        tx.execute("DELETE FROM environments WHERE id = ?1", [id])?;
+        ensure_active_environment(&tx)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn set_active_environment(&self, id: i64) -> SqlResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        // Make sure the target exists before deactivating the current one
+        tx.query_row("SELECT id FROM environments WHERE id = ?1", [id], |row| row.get::<_, i64>(0))?;
+        tx.execute("UPDATE environments SET active = CASE WHEN id = ?1 THEN 1 ELSE 0 END", [id])?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+/// Moves rows from the old single-scope `env_vars` table into a "Default" environment
+fn migrate_legacy_env_vars(conn: &mut Connection) -> SqlResult<()> {
+    let has_legacy: Option<String> = conn
+        .query_row(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'env_vars'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    if has_legacy.is_none() {
+        return Ok(());
+    }
+
+    let tx = conn.transaction()?;
+
+    // Older databases may predate the 'enabled' column
+    let has_enabled = {
+        let mut stmt = tx.prepare("PRAGMA table_info(env_vars)")?;
+        let mut rows = stmt.query([])?;
+        let mut found = false;
+        while let Some(row) = rows.next()? {
+            let col_name: String = row.get(1)?;
+            if col_name == "enabled" {
+                found = true;
+                break;
+            }
+        }
+        found
+    };
+
+    tx.execute(
+        "INSERT OR IGNORE INTO environments (name, active) VALUES (?1, 0)",
+        [DEFAULT_ENVIRONMENT],
+    )?;
+    let default_id: i64 = tx.query_row(
+        "SELECT id FROM environments WHERE name = ?1",
+        [DEFAULT_ENVIRONMENT],
+        |row| row.get(0),
+    )?;
+
+    let copy_sql = if has_enabled {
+        "INSERT OR IGNORE INTO environment_vars (environment_id, key, value, enabled)
+         SELECT ?1, key, value, COALESCE(enabled,1) FROM env_vars"
+    } else {
+        "INSERT OR IGNORE INTO environment_vars (environment_id, key, value, enabled)
+         SELECT ?1, key, value, 1 FROM env_vars"
+    };
+    tx.execute(copy_sql, [default_id])?;
+    tx.execute("DROP TABLE env_vars", [])?;
+
+    // Keep the migrated values in effect
+    let any_active: i64 = tx.query_row("SELECT COUNT(*) FROM environments WHERE active = 1", [], |row| row.get(0))?;
+    if any_active == 0 {
+        tx.execute("UPDATE environments SET active = 1 WHERE id = ?1", [default_id])?;
+    }
+
+    tx.commit()
+}
+
+/// Guarantees exactly one environment is active, creating "Default" if the table is empty
+fn ensure_active_environment(conn: &Connection) -> SqlResult<()> {
+    let count: i64 = conn.query_row("SELECT COUNT(*) FROM environments", [], |row| row.get(0))?;
+    if count == 0 {
+        conn.execute(
+            "INSERT INTO environments (name, active) VALUES (?1, 1)",
+            [DEFAULT_ENVIRONMENT],
+        )?;
+        return Ok(());
+    }
+
+    let active: i64 = conn.query_row("SELECT COUNT(*) FROM environments WHERE active = 1", [], |row| row.get(0))?;
+    if active != 1 {
+        conn.execute(
+            "UPDATE environments SET active = CASE WHEN id = (SELECT MIN(id) FROM environments) THEN 1 ELSE 0 END",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("prism-{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_migrates_legacy_vars_into_default() {
+        let path = temp_db("env-migrate");
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute("CREATE TABLE env_vars (key TEXT PRIMARY KEY, value TEXT NOT NULL)", []).unwrap();
+            conn.execute("INSERT INTO env_vars (key, value) VALUES ('HOST', 'localhost')", []).unwrap();
+        }
+
+        let store = EnvStore::new(&path).unwrap();
+        let active = store.active_environment().unwrap();
+        assert_eq!(active.name, "Default");
+
+        let vars = store.get_all().unwrap();
+        assert_eq!(vars.len(), 1);
+        assert_eq!(vars[0].key, "HOST");
+        assert!(vars[0].enabled);
+
+        // Re-opening must not duplicate or fail
+        drop(store);
+        let store = EnvStore::new(&path).unwrap();
+        assert_eq!(store.list_environments().unwrap().len(), 1);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_environments_are_isolated() {
+        let store = EnvStore::new(":memory:").unwrap();
+        let dev = store.active_environment().unwrap();
+        store.set("HOST", "dev.local", true).unwrap();
+
+        let prod = store.clone_environment(dev.id, "prod").unwrap();
+        store.set_var(prod.id, "HOST", "prod.example.com", true).unwrap();
+        store.set_active_environment(prod.id).unwrap();
+
+        assert_eq!(store.get_all().unwrap()[0].value, "prod.example.com");
+        assert_eq!(store.get_vars(dev.id).unwrap()[0].value, "dev.local");
+
+        store.rename_environment(prod.id, "production").unwrap();
+        assert_eq!(store.active_environment().unwrap().name, "production");
+    }
+
+    #[test]
+    fn test_delete_active_environment_falls_back() {
+        let store = EnvStore::new(":memory:").unwrap();
+        let first = store.active_environment().unwrap();
+        let staging = store.create_environment("staging").unwrap();
+        store.set_active_environment(staging.id).unwrap();
+
+        store.delete_environment(staging.id).unwrap();
+        assert_eq!(store.active_environment().unwrap().id, first.id);
+
+        store.delete_environment(first.id).unwrap();
+        let envs = store.list_environments().unwrap();
+        assert_eq!(envs.len(), 1);
+        assert!(envs[0].active);
+    }
 }
@@ -0,0 +1,98 @@
+use crate::engine::http::execute_request;
+use crate::engine::pool::ClientPool;
+use crate::models::{ApiRequest, RequestBody};
+use crate::settings::HttpSettings;
+use serde_json::Value;
+
+/// The standard introspection query, deep enough for lists of non-null types
+pub const INTROSPECTION_QUERY: &str = r#"query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types { ...FullType }
+  }
+}
+
+fragment FullType on __Type {
+  kind
+  name
+  description
+  fields(includeDeprecated: true) {
+    name
+    description
+    args { ...InputValue }
+    type { ...TypeRef }
+    isDeprecated
+  }
+  inputFields { ...InputValue }
+  enumValues(includeDeprecated: true) { name description }
+  possibleTypes { name }
+}
+
+fragment InputValue on __InputValue {
+  name
+  description
+  type { ...TypeRef }
+  defaultValue
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType { kind name ofType { kind name ofType { kind name ofType { kind name ofType { kind name } } } } }
+}"#;
+
+/// Fetches the schema of the endpoint `req` points at, sending the
+/// introspection query with the request's own headers and auth.
+/// Returns the `__schema` object.
+pub async fn introspect(req: &ApiRequest, settings: &HttpSettings, pool: &ClientPool) -> Result<Value, String> {
+    let mut req = req.clone();
+    req.method = "POST".to_string();
+    req.body = RequestBody::GraphQl {
+        query: INTROSPECTION_QUERY.to_string(),
+        variables: String::new(),
+        operation_name: Some("IntrospectionQuery".to_string()),
+    };
+    req.assertions.clear();
+    req.extractions.clear();
+
+    let response = execute_request(req, settings, pool).await?;
+    if !(200..300).contains(&response.status) {
+        return Err(format!("Introspection failed: HTTP {} {}", response.status, response.status_text));
+    }
+    schema_from_response(response.body.json().ok_or("Introspection response is not JSON")?)
+}
+
+fn schema_from_response(body: &Value) -> Result<Value, String> {
+    if let Some(errors) = body.get("errors").and_then(Value::as_array).filter(|e| !e.is_empty()) {
+        let messages: Vec<&str> = errors
+            .iter()
+            .map(|e| e.get("message").and_then(Value::as_str).unwrap_or("unknown error"))
+            .collect();
+        return Err(format!("Introspection failed: {}", messages.join("; ")));
+    }
+    body.pointer("/data/__schema")
+        .filter(|schema| schema.is_object())
+        .cloned()
+        .ok_or_else(|| "Introspection response has no data.__schema".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_schema_from_response() {
+        let schema = json!({ "queryType": { "name": "Query" }, "types": [] });
+        assert_eq!(schema_from_response(&json!({ "data": { "__schema": schema } })).unwrap(), schema);
+        assert_eq!(
+            schema_from_response(&json!({ "errors": [{ "message": "introspection is disabled" }] })).unwrap_err(),
+            "Introspection failed: introspection is disabled"
+        );
+        assert!(schema_from_response(&json!({ "data": null })).is_err());
+        // The query itself must be valid GraphQL
+        assert!(super::super::validate::validate(INTROSPECTION_QUERY, None).is_empty());
+    }
+}
@@ -0,0 +1,7 @@
+pub mod introspection;
+pub mod store;
+pub mod validate;
+
+pub use introspection::{introspect, INTROSPECTION_QUERY};
+pub use store::{CachedSchema, SchemaStore};
+pub use validate::validate;
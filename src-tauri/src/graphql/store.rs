@@ -0,0 +1,123 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+/// An introspected schema as cached for an endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedSchema {
+    pub endpoint: String,
+    /// The `__schema` object from the introspection response
+    pub schema: serde_json::Value,
+    #[serde(rename = "fetchedAt")]
+    pub fetched_at: i64,
+}
+
+/// Introspected GraphQL schemas keyed by endpoint, for autocompletion and
+/// validating queries offline
+pub struct SchemaStore {
+    conn: Mutex<Connection>,
+}
+
+impl SchemaStore {
+    pub fn new(db_path: &str) -> Result<Self, String> {
+        let conn = Connection::open(db_path)
+            .map_err(|e| format!("Failed to open GraphQL schema database: {}", e))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS schemas (
+                endpoint TEXT PRIMARY KEY,
+                schema_json TEXT NOT NULL,
+                fetched_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(db_err)?;
+
+        Ok(SchemaStore {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    pub fn get(&self, url: &str) -> Result<Option<CachedSchema>, String> {
+        let endpoint = endpoint_key(url);
+        let conn = self.conn.lock().unwrap();
+        let row: Option<(String, i64)> = conn
+            .query_row(
+                "SELECT schema_json, fetched_at FROM schemas WHERE endpoint = ?1",
+                params![endpoint],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(db_err)?;
+
+        match row {
+            Some((json, fetched_at)) => Ok(Some(CachedSchema {
+                schema: serde_json::from_str(&json)
+                    .map_err(|e| format!("Failed to parse cached schema: {}", e))?,
+                endpoint,
+                fetched_at,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    /// Caches a schema for the endpoint, replacing any older one
+    pub fn set(&self, url: &str, schema: &serde_json::Value) -> Result<CachedSchema, String> {
+        let cached = CachedSchema {
+            endpoint: endpoint_key(url),
+            schema: schema.clone(),
+            fetched_at: chrono::Utc::now().timestamp_millis(),
+        };
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO schemas (endpoint, schema_json, fetched_at) VALUES (?1, ?2, ?3)",
+            params![cached.endpoint, schema.to_string(), cached.fetched_at],
+        )
+        .map_err(db_err)?;
+        Ok(cached)
+    }
+
+    pub fn delete(&self, url: &str) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM schemas WHERE endpoint = ?1", params![endpoint_key(url)])
+            .map_err(db_err)?;
+        Ok(())
+    }
+}
+
+/// Schemas belong to the endpoint, not to a particular query string
+fn endpoint_key(url: &str) -> String {
+    match url::Url::parse(url.trim()) {
+        Ok(mut parsed) => {
+            parsed.set_query(None);
+            parsed.set_fragment(None);
+            parsed.to_string()
+        }
+        Err(_) => url.trim().to_string(),
+    }
+}
+
+fn db_err(e: rusqlite::Error) -> String {
+    format!("GraphQL schema database error: {}", e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_schema_round_trip() {
+        let store = SchemaStore::new(":memory:").unwrap();
+        assert_eq!(store.get("https://api.example.com/graphql").unwrap(), None);
+
+        let schema = json!({ "queryType": { "name": "Query" }, "types": [] });
+        store.set("https://api.example.com/graphql?debug=1", &schema).unwrap();
+        let cached = store.get("https://api.example.com/graphql").unwrap().unwrap();
+        assert_eq!(cached.endpoint, "https://api.example.com/graphql");
+        assert_eq!(cached.schema, schema);
+
+        store.delete("https://api.example.com/graphql#x").unwrap();
+        assert_eq!(store.get("https://api.example.com/graphql").unwrap(), None);
+    }
+}
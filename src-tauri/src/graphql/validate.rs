@@ -0,0 +1,592 @@
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// Checks a GraphQL document before it is sent: syntax always, and fields,
+/// arguments, types and fragments when an introspected `__schema` is given.
+/// Returns human readable problems; empty means the query looks valid.
+pub fn validate(query: &str, schema: Option<&Value>) -> Vec<String> {
+    let document = match Parser::new(query).and_then(|mut parser| parser.document()) {
+        Ok(document) => document,
+        Err(e) => return vec![e],
+    };
+
+    let mut errors = Vec::new();
+    let anonymous = document.operations.iter().filter(|op| op.name.is_none()).count();
+    if anonymous > 0 && document.operations.len() > 1 {
+        errors.push("An anonymous operation must be the only operation in the document".to_string());
+    }
+    if let Some(schema) = schema {
+        SchemaCheck::new(schema, &document).run(&mut errors);
+    }
+    errors
+}
+
+/// The syntax error in a GraphQL document, if it cannot be parsed at all.
+/// Only these make a query unsendable; schema problems may be out of date.
+pub fn syntax_error(query: &str) -> Option<String> {
+    Parser::new(query).and_then(|mut parser| parser.document()).err()
+}
+
+#[derive(Debug)]
+struct Document {
+    operations: Vec<Operation>,
+    fragments: HashMap<String, Fragment>,
+}
+
+#[derive(Debug)]
+struct Operation {
+    kind: String,
+    name: Option<String>,
+    selections: Vec<Selection>,
+}
+
+#[derive(Debug)]
+struct Fragment {
+    type_condition: String,
+    selections: Vec<Selection>,
+}
+
+#[derive(Debug)]
+enum Selection {
+    Field {
+        name: String,
+        arguments: Vec<String>,
+        selections: Vec<Selection>,
+        line: usize,
+    },
+    Spread {
+        name: String,
+        line: usize,
+    },
+    Inline {
+        type_condition: Option<String>,
+        selections: Vec<Selection>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Punct(char),
+    Spread,
+    Name(String),
+    /// Strings and numbers; only their position matters here
+    Literal,
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(source: &str) -> Result<Self, String> {
+        Ok(Parser { tokens: tokenize(source)?, pos: 0 })
+    }
+
+    fn document(&mut self) -> Result<Document, String> {
+        let mut document = Document { operations: Vec::new(), fragments: HashMap::new() };
+        if self.tokens.is_empty() {
+            return Err("Syntax error: the query is empty".to_string());
+        }
+        while self.peek().is_some() {
+            match self.peek() {
+                Some(Token::Punct('{')) => document.operations.push(Operation {
+                    kind: "query".to_string(),
+                    name: None,
+                    selections: self.selection_set()?,
+                }),
+                Some(Token::Name(word)) if word == "fragment" => {
+                    self.next();
+                    let name = self.name()?;
+                    self.keyword("on")?;
+                    let type_condition = self.name()?;
+                    self.directives()?;
+                    let selections = self.selection_set()?;
+                    document.fragments.insert(name, Fragment { type_condition, selections });
+                }
+                Some(Token::Name(word)) if matches!(word.as_str(), "query" | "mutation" | "subscription") => {
+                    let kind = word.clone();
+                    self.next();
+                    let name = match self.peek() {
+                        Some(Token::Name(_)) => Some(self.name()?),
+                        _ => None,
+                    };
+                    if self.peek() == Some(&Token::Punct('(')) {
+                        self.skip_group('(', ')')?;
+                    }
+                    self.directives()?;
+                    let selections = self.selection_set()?;
+                    document.operations.push(Operation { kind, name, selections });
+                }
+                _ => return Err(self.unexpected("an operation or fragment")),
+            }
+        }
+        if document.operations.is_empty() {
+            return Err("Syntax error: the document has no operation".to_string());
+        }
+        Ok(document)
+    }
+
+    fn selection_set(&mut self) -> Result<Vec<Selection>, String> {
+        self.expect('{')?;
+        let mut selections = Vec::new();
+        loop {
+            match self.peek() {
+                Some(Token::Punct('}')) => {
+                    self.next();
+                    break;
+                }
+                Some(Token::Spread) => {
+                    let line = self.line();
+                    self.next();
+                    match self.peek() {
+                        Some(Token::Name(word)) if word == "on" => {
+                            self.next();
+                            let type_condition = Some(self.name()?);
+                            self.directives()?;
+                            selections.push(Selection::Inline { type_condition, selections: self.selection_set()? });
+                        }
+                        Some(Token::Name(_)) => {
+                            let name = self.name()?;
+                            self.directives()?;
+                            selections.push(Selection::Spread { name, line });
+                        }
+                        _ => {
+                            self.directives()?;
+                            selections.push(Selection::Inline { type_condition: None, selections: self.selection_set()? });
+                        }
+                    }
+                }
+                Some(Token::Name(_)) => selections.push(self.field()?),
+                _ => return Err(self.unexpected("a field or '}'")),
+            }
+        }
+        if selections.is_empty() {
+            return Err(format!("Syntax error: empty selection set on line {}", self.line()));
+        }
+        Ok(selections)
+    }
+
+    fn field(&mut self) -> Result<Selection, String> {
+        let line = self.line();
+        let mut name = self.name()?;
+        // alias: field
+        if self.peek() == Some(&Token::Punct(':')) {
+            self.next();
+            name = self.name()?;
+        }
+        let mut arguments = Vec::new();
+        if self.peek() == Some(&Token::Punct('(')) {
+            self.next();
+            while self.peek() != Some(&Token::Punct(')')) {
+                arguments.push(self.name()?);
+                self.expect(':')?;
+                self.value()?;
+            }
+            self.next();
+        }
+        self.directives()?;
+        let selections = if self.peek() == Some(&Token::Punct('{')) {
+            self.selection_set()?
+        } else {
+            Vec::new()
+        };
+        Ok(Selection::Field { name, arguments, selections, line })
+    }
+
+    fn value(&mut self) -> Result<(), String> {
+        match self.peek() {
+            Some(Token::Punct('$')) => {
+                self.next();
+                self.name().map(|_| ())
+            }
+            Some(Token::Punct('[')) => self.skip_group('[', ']'),
+            Some(Token::Punct('{')) => self.skip_group('{', '}'),
+            Some(Token::Punct('-')) => {
+                self.next();
+                self.value()
+            }
+            Some(Token::Literal) | Some(Token::Name(_)) => {
+                self.next();
+                Ok(())
+            }
+            _ => Err(self.unexpected("a value")),
+        }
+    }
+
+    fn directives(&mut self) -> Result<(), String> {
+        while self.peek() == Some(&Token::Punct('@')) {
+            self.next();
+            self.name()?;
+            if self.peek() == Some(&Token::Punct('(')) {
+                self.skip_group('(', ')')?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Skips a balanced bracketed group whose contents don't matter here
+    fn skip_group(&mut self, open: char, close: char) -> Result<(), String> {
+        let line = self.line();
+        self.expect(open)?;
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next() {
+                Some(Token::Punct(c)) if c == open => depth += 1,
+                Some(Token::Punct(c)) if c == close => depth -= 1,
+                Some(_) => {}
+                None => return Err(format!("Syntax error: '{}' opened on line {} is never closed", open, line)),
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(Token::Name(name)) => {
+                let name = name.clone();
+                self.next();
+                Ok(name)
+            }
+            _ => Err(self.unexpected("a name")),
+        }
+    }
+
+    fn keyword(&mut self, word: &str) -> Result<(), String> {
+        match self.peek() {
+            Some(Token::Name(name)) if name == word => {
+                self.next();
+                Ok(())
+            }
+            _ => Err(self.unexpected(&format!("'{}'", word))),
+        }
+    }
+
+    fn expect(&mut self, punct: char) -> Result<(), String> {
+        if self.peek() == Some(&Token::Punct(punct)) {
+            self.next();
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("'{}'", punct)))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> String {
+        let found = match self.peek() {
+            Some(Token::Punct(c)) => format!("'{}'", c),
+            Some(Token::Spread) => "'...'".to_string(),
+            Some(Token::Name(name)) => format!("'{}'", name),
+            Some(Token::Literal) => "a literal".to_string(),
+            None => return format!("Syntax error: expected {} but the query ended", expected),
+        };
+        format!("Syntax error: expected {} but found {} on line {}", expected, found, self.line())
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(token, _)| token.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map(|(_, line)| *line)
+            .unwrap_or(1)
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, String> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() || c == ',' || c == '\u{feff}' => {}
+            '#' => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            '.' => {
+                if chars.next() != Some('.') || chars.next() != Some('.') {
+                    return Err(format!("Syntax error: unexpected '.' on line {}", line));
+                }
+                tokens.push((Token::Spread, line));
+            }
+            '"' => {
+                let start = line;
+                let block = chars.peek() == Some(&'"') && {
+                    chars.next();
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        true
+                    } else {
+                        // "" is an empty string
+                        tokens.push((Token::Literal, line));
+                        continue;
+                    }
+                };
+                let mut quotes = 0;
+                loop {
+                    match chars.next() {
+                        Some('\\') if !block => {
+                            chars.next();
+                        }
+                        Some('"') if !block => break,
+                        Some('"') => {
+                            quotes += 1;
+                            if quotes == 3 {
+                                break;
+                            }
+                            continue;
+                        }
+                        Some('\n') if !block => {
+                            return Err(format!("Syntax error: unterminated string on line {}", start));
+                        }
+                        Some('\n') => line += 1,
+                        Some(_) => {}
+                        None => return Err(format!("Syntax error: unterminated string on line {}", start)),
+                    }
+                    quotes = 0;
+                }
+                tokens.push((Token::Literal, start));
+            }
+            c if c.is_ascii_digit() => {
+                while chars.peek().is_some_and(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-')) {
+                    chars.next();
+                }
+                tokens.push((Token::Literal, line));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut name = c.to_string();
+                while let Some(&c) = chars.peek().filter(|c| c.is_ascii_alphanumeric() || **c == '_') {
+                    name.push(c);
+                    chars.next();
+                }
+                tokens.push((Token::Name(name), line));
+            }
+            '{' | '}' | '(' | ')' | '[' | ']' | ':' | '$' | '!' | '=' | '@' | '|' | '&' | '-' => {
+                tokens.push((Token::Punct(c), line));
+            }
+            other => return Err(format!("Syntax error: unexpected character '{}' on line {}", other, line)),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Walks a parsed document against an introspected schema
+struct SchemaCheck<'a> {
+    types: HashMap<&'a str, &'a Value>,
+    roots: HashMap<&'static str, &'a str>,
+    document: &'a Document,
+}
+
+impl<'a> SchemaCheck<'a> {
+    fn new(schema: &'a Value, document: &'a Document) -> Self {
+        let types = schema
+            .get("types")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|t| Some((t.get("name")?.as_str()?, t)))
+            .collect();
+        let mut roots = HashMap::new();
+        for (kind, key) in [("query", "queryType"), ("mutation", "mutationType"), ("subscription", "subscriptionType")] {
+            if let Some(name) = schema.get(key).and_then(|t| t.get("name")).and_then(Value::as_str) {
+                roots.insert(kind, name);
+            }
+        }
+        SchemaCheck { types, roots, document }
+    }
+
+    fn run(&self, errors: &mut Vec<String>) {
+        for operation in &self.document.operations {
+            match self.roots.get(operation.kind.as_str()) {
+                Some(root) => self.check_selections(root, &operation.selections, &mut HashSet::new(), errors),
+                None => errors.push(format!("The schema does not support {} operations", operation.kind)),
+            }
+        }
+    }
+
+    fn check_selections(&self, type_name: &str, selections: &'a [Selection], seen: &mut HashSet<&'a str>, errors: &mut Vec<String>) {
+        let Some(parent) = self.types.get(type_name) else {
+            errors.push(format!("Unknown type '{}'", type_name));
+            return;
+        };
+
+        for selection in selections {
+            match selection {
+                Selection::Field { name, arguments, selections, line } => {
+                    if name == "__typename" || (self.roots.get("query") == Some(&type_name) && matches!(name.as_str(), "__schema" | "__type")) {
+                        continue;
+                    }
+                    let field = parent
+                        .get("fields")
+                        .and_then(Value::as_array)
+                        .and_then(|fields| fields.iter().find(|f| f.get("name").and_then(Value::as_str) == Some(name)));
+                    let Some(field) = field else {
+                        errors.push(format!("Cannot query field '{}' on type '{}' (line {})", name, type_name, line));
+                        continue;
+                    };
+
+                    for argument in arguments {
+                        let known = field
+                            .get("args")
+                            .and_then(Value::as_array)
+                            .is_some_and(|args| args.iter().any(|a| a.get("name").and_then(Value::as_str) == Some(argument)));
+                        if !known {
+                            errors.push(format!("Unknown argument '{}' on field '{}.{}' (line {})", argument, type_name, name, line));
+                        }
+                    }
+
+                    let field_type = named_type(field.get("type"));
+                    let leaf = self
+                        .types
+                        .get(field_type)
+                        .and_then(|t| t.get("kind"))
+                        .and_then(Value::as_str)
+                        .is_some_and(|kind| kind == "SCALAR" || kind == "ENUM");
+                    match (leaf, selections.is_empty()) {
+                        (true, false) => errors.push(format!(
+                            "Field '{}' of type '{}' cannot have a selection set (line {})",
+                            name, field_type, line
+                        )),
+                        (false, true) => errors.push(format!(
+                            "Field '{}' of type '{}' must have a selection of subfields (line {})",
+                            name, field_type, line
+                        )),
+                        (false, false) => self.check_selections(field_type, selections, seen, errors),
+                        (true, true) => {}
+                    }
+                }
+                Selection::Spread { name, line } => match self.document.fragments.get(name) {
+                    Some(fragment) => {
+                        // Guards against fragment cycles
+                        if seen.insert(name.as_str()) {
+                            self.check_selections(&fragment.type_condition, &fragment.selections, seen, errors);
+                            seen.remove(name.as_str());
+                        }
+                    }
+                    None => errors.push(format!("Unknown fragment '{}' (line {})", name, line)),
+                },
+                Selection::Inline { type_condition, selections } => {
+                    let target = type_condition.as_deref().unwrap_or(type_name);
+                    self.check_selections(target, selections, seen, errors);
+                }
+            }
+        }
+    }
+}
+
+/// The named type under any NON_NULL / LIST wrappers
+fn named_type(type_ref: Option<&Value>) -> &str {
+    let mut current = type_ref;
+    while let Some(t) = current {
+        if let Some(name) = t.get("name").and_then(Value::as_str) {
+            return name;
+        }
+        current = t.get("ofType");
+    }
+    ""
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        let scalar = |name: &str| json!({ "kind": "SCALAR", "name": name });
+        let field = |name: &str, ty: Value, args: &[&str]| {
+            json!({ "name": name, "type": ty, "args": args.iter().map(|a| json!({ "name": a })).collect::<Vec<_>>() })
+        };
+        let named = |kind: &str, name: &str| json!({ "kind": kind, "name": name });
+        let non_null = |inner: Value| json!({ "kind": "NON_NULL", "name": null, "ofType": inner });
+        let list = |inner: Value| json!({ "kind": "LIST", "name": null, "ofType": inner });
+
+        json!({
+            "queryType": { "name": "Query" },
+            "mutationType": null,
+            "types": [
+                { "kind": "OBJECT", "name": "Query", "fields": [
+                    field("user", named("OBJECT", "User"), &["id"]),
+                    field("search", list(named("UNION", "Result")), &["term"]),
+                ] },
+                { "kind": "OBJECT", "name": "User", "fields": [
+                    field("id", non_null(named("SCALAR", "ID")), &[]),
+                    field("name", named("SCALAR", "String"), &[]),
+                    field("friends", non_null(list(non_null(named("OBJECT", "User")))), &["first"]),
+                ] },
+                { "kind": "UNION", "name": "Result", "possibleTypes": [named("OBJECT", "User")] },
+                scalar("ID"),
+                scalar("String"),
+            ]
+        })
+    }
+
+    #[test]
+    fn test_valid_queries() {
+        let schema = schema();
+        let query = r#"
+            # Users and their friends
+            query User($id: ID!, $n: Int = 3) @cached(ttl: 60) {
+                me: user(id: $id) {
+                    ...Basics
+                    friends(first: $n) { name, __typename }
+                }
+                search(term: "a \"quoted\" }") {
+                    ... on User { id }
+                }
+            }
+            fragment Basics on User { id name }
+        "#;
+        assert_eq!(validate(query, Some(&schema)), Vec::<String>::new());
+        assert!(validate("{ __schema { types { name } } }", Some(&schema)).is_empty());
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        assert_eq!(
+            validate("query {\n  user(id: 1) {\n    name\n", None),
+            vec!["Syntax error: expected a field or '}' but the query ended".to_string()]
+        );
+        assert_eq!(validate("  ", None), vec!["Syntax error: the query is empty".to_string()]);
+        assert!(validate("{ user { } }", None)[0].starts_with("Syntax error: empty selection set"));
+        assert!(validate("{ a } { b }", None)[0].contains("anonymous operation"));
+
+        assert!(syntax_error("{ user { } }").is_some());
+        assert_eq!(syntax_error("{ user { nope } }"), None);
+    }
+
+    #[test]
+    fn test_schema_errors() {
+        let schema = schema();
+        let errors = validate(
+            "query {\n  user(id: 1, name: \"x\") {\n    email\n    friends\n    name { first }\n  }\n  search(term: \"a\") { id }\n  ...Missing\n}",
+            Some(&schema),
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "Unknown argument 'name' on field 'Query.user' (line 2)",
+                "Cannot query field 'email' on type 'User' (line 3)",
+                "Field 'friends' of type 'User' must have a selection of subfields (line 4)",
+                "Field 'name' of type 'String' cannot have a selection set (line 5)",
+                "Cannot query field 'id' on type 'Result' (line 7)",
+                "Unknown fragment 'Missing' (line 8)",
+            ]
+        );
+        assert_eq!(
+            validate("mutation { addUser }", Some(&schema)),
+            vec!["The schema does not support mutation operations".to_string()]
+        );
+    }
+}
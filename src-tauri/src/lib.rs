@@ -0,0 +1,10 @@
+//! Core of Prism, shared by the desktop app and the `prism-cli` binary
+
+pub mod models;
+pub mod engine;
+pub mod store;
+pub mod env;
+pub mod workspace;
+pub mod runner;
+pub mod settings;
+pub mod graphql;
@@ -1,24 +1,44 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod models;
-mod engine;
-mod store;
-mod env;
-mod workspace;
+use prism_lib::{models, engine, store, env, workspace, runner, settings, graphql};
 
-use models::{ApiRequest, ApiResponse};
+use models::{ApiRequest, ApiResponse, ExtractionScope, Frame, RequestBody};
 use store::{Store, models::HistoryEntry};
-use env::{EnvStore, EnvVar, resolve_variables};
-use workspace::{save_request_to_file, load_request_from_file};
-use std::sync::Arc;
+use env::{EnvStore, EnvVar, Environment, RequestResolution, Resolved, build_variable_map, resolve_request, resolve_variables_checked};
+use workspace::{CollectionStore, save_request_to_file, load_request_from_file};
+use workspace::models::{CollectionNode, ConversionReport, ImportOutcome, SavedRequest};
+use runner::{RunOptions, RunReport};
+use settings::{HttpSettings, SettingsStore};
+use graphql::{CachedSchema, SchemaStore};
+use engine::auth::oauth2::{self, Token};
+use engine::pool::ClientPool;
+use engine::stream::{StreamStart, Streams};
+use engine::websocket::{Handshake, WsSessions};
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use chrono;
-use tauri::{State, Manager, AppHandle};
+use tauri::{State, Manager, AppHandle, Emitter};
 
 struct AppState {
     store: Arc<Store>,
     env_store: Arc<EnvStore>,
+    collection_store: Arc<CollectionStore>,
+    settings_store: Arc<SettingsStore>,
+    schema_store: Arc<SchemaStore>,
+    client_pool: Arc<ClientPool>,
+    ws_sessions: Arc<WsSessions>,
+    streams: Arc<Streams>,
+    workspace: Mutex<Option<OpenWorkspace>>,
+    /// Raw bytes and Content-Type of the last response, for `save_response_body`
+    last_response_body: Mutex<Option<(Vec<u8>, Option<String>)>>,
+}
+
+/// Folder-based workspace currently open, kept alive with its file watcher
+struct OpenWorkspace {
+    root: PathBuf,
+    _watcher: notify::RecommendedWatcher,
 }
 
 #[tauri::command]
@@ -26,16 +46,47 @@ async fn send_request(
     req: ApiRequest,
     state: State<'_, AppState>,
 ) -> Result<ApiResponse, String> {
-    // Resolve environment variables
-    let resolved_req = resolve_request_variables(req.clone(), &state.env_store)?;
+    // Resolve environment variables (dynamic built-ins are generated here, once per send)
+    let resolution = resolve_with_report(req.clone(), &state.env_store)?;
     
-    let response = engine::http::execute_request(resolved_req.clone()).await?;
+    // Syntax errors block the send; schema problems come back as warnings since the cached schema may be stale
+    let mut warnings = Vec::new();
+    if let RequestBody::GraphQl { query, .. } = &resolution.request.body {
+        if let Some(error) = graphql::validate::syntax_error(query) {
+            return Err(format!("GraphQL validation failed: {}", error));
+        }
+        let cached = state.schema_store.get(&resolution.request.url)?;
+        warnings = graphql::validate(query, cached.as_ref().map(|c| &c.schema));
+    }
+    
+    let assertions = resolution.request.assertions.clone();
+    let extractions = resolution.request.extractions.clone();
+    let settings = state.settings_store.get_http_settings()?;
+    let mut response = engine::http::execute_request(resolution.request, &settings, &state.client_pool).await?;
+    response.warnings = warnings;
+    let content_type = response.headers.get("content-type").cloned();
+    *state.last_response_body.lock().unwrap() = Some((std::mem::take(&mut response.raw_body), content_type));
+    response.assertion_results = engine::assertions::evaluate(&assertions, &response);
+    response.extracted_values = engine::extract::extract(&extractions, &response);
+    
+    // Store captured values so the next send picks them up
+    for extracted in &response.extracted_values {
+        let Some(value) = &extracted.value else { continue };
+        let saved = match extracted.scope {
+            ExtractionScope::Environment => state.env_store.set(&extracted.variable, value, true),
+            ExtractionScope::Global => state.env_store.set_global(&extracted.variable, value, true),
+        };
+        if let Err(e) = saved {
+            eprintln!("Warning: Failed to store extracted variable {}: {}", extracted.variable, e);
+        }
+    }
     
     // Save to history (use original request for full restoration)
-    let entry = HistoryEntry::new(
+    let mut entry = HistoryEntry::new(
         req,
         response.clone(),
     );
+    entry.dynamic_values = resolution.dynamic_values;
     
     // Don't fail the request if history save fails
     if let Err(e) = state.store.add_history_entry(entry) {
@@ -45,6 +96,201 @@ async fn send_request(
     Ok(response)
 }
 
+/// Sends the request and streams the body as it arrives: parsed events for
+/// `text/event-stream`, raw chunks otherwise, emitted as `stream-event`s.
+/// Returns once the headers are in; the transcript goes to history when it ends.
+#[tauri::command]
+async fn send_streaming_request(
+    req: ApiRequest,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<StreamStart, String> {
+    let resolution = resolve_with_report(req.clone(), &state.env_store)?;
+    let settings = state.settings_store.get_http_settings()?;
+    let stream = engine::stream::open(&resolution.request, &settings, &state.client_pool).await?;
+    let start = stream.start();
+    
+    let cancel = state.streams.register(&stream.id);
+    let streams = state.streams.clone();
+    let store = state.store.clone();
+    let dynamic_values = resolution.dynamic_values;
+    tauri::async_runtime::spawn(async move {
+        let id = stream.id.clone();
+        let record = engine::stream::run(stream, cancel, |event| {
+            if let Err(e) = app.emit("stream-event", event) {
+                eprintln!("Warning: Failed to emit stream event: {}", e);
+            }
+        }).await;
+        streams.remove(&id);
+        
+        let mut entry = HistoryEntry::new(req, record.response);
+        entry.dynamic_values = dynamic_values;
+        entry.transcript = record.transcript;
+        if let Err(e) = store.add_history_entry(entry) {
+            eprintln!("Warning: Failed to save history: {}", e);
+        }
+    });
+    
+    Ok(start)
+}
+
+/// Stops a running stream; what arrived so far is kept in history
+#[tauri::command]
+fn cancel_stream(stream_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.streams.cancel(&stream_id)
+}
+
+/// Opens a WebSocket session for the request. Messages and the close are
+/// emitted as `ws-event`s; the transcript goes to history when it ends.
+#[tauri::command]
+async fn ws_connect(
+    req: ApiRequest,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Handshake, String> {
+    let resolution = resolve_with_report(req.clone(), &state.env_store)?;
+    let settings = state.settings_store.get_http_settings()?;
+    let connection = engine::websocket::connect(&resolution.request, &settings, &state.client_pool).await?;
+    let handshake = connection.handshake();
+    
+    let outgoing = state.ws_sessions.register(&connection.id);
+    let sessions = state.ws_sessions.clone();
+    let store = state.store.clone();
+    let dynamic_values = resolution.dynamic_values;
+    tauri::async_runtime::spawn(async move {
+        let id = connection.id.clone();
+        let record = engine::websocket::run(connection, outgoing, |event| {
+            if let Err(e) = app.emit("ws-event", event) {
+                eprintln!("Warning: Failed to emit WebSocket event: {}", e);
+            }
+        }).await;
+        sessions.remove(&id);
+        
+        let mut entry = HistoryEntry::new(req, record.response);
+        entry.method = "WS".to_string();
+        entry.dynamic_values = dynamic_values;
+        entry.transcript = record.transcript;
+        if let Err(e) = store.add_history_entry(entry) {
+            eprintln!("Warning: Failed to save history: {}", e);
+        }
+    });
+    
+    Ok(handshake)
+}
+
+/// Sends a text, binary (base64) or close frame on an open session
+#[tauri::command]
+fn ws_send(session_id: String, frame: Frame, state: State<'_, AppState>) -> Result<(), String> {
+    state.ws_sessions.send(&session_id, frame)
+}
+
+#[tauri::command]
+fn ws_close(session_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.ws_sessions.send(&session_id, Frame::Close { code: None, reason: String::new() })
+}
+
+/// Writes the last response body to disk exactly as it was received
+#[tauri::command]
+async fn save_response_body(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    use tauri_plugin_dialog::{DialogExt, FilePath};
+    use std::fs;
+
+    let (bytes, content_type) = state.last_response_body.lock().unwrap().clone()
+        .ok_or_else(|| "No response to save".to_string())?;
+    let extension = engine::body::file_extension(content_type.as_deref().unwrap_or(""));
+
+    let file_path = app.dialog()
+        .file()
+        .set_title("Save Response Body")
+        .set_file_name(format!("response.{}", extension))
+        .blocking_save_file();
+
+    if let Some(path) = file_path {
+        let path_str = match path {
+            FilePath::Path(p) => p.to_str().ok_or_else(|| "Invalid file path".to_string())?.to_string(),
+            FilePath::Url(_u) => return Err("URL paths not supported".to_string()),
+        };
+        fs::write(path_str, bytes).map_err(|e| format!("Failed to write file: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Fetches the schema of the request's endpoint and caches it for autocompletion
+#[tauri::command]
+async fn introspect_graphql_schema(req: ApiRequest, state: State<'_, AppState>) -> Result<CachedSchema, String> {
+    let resolution = resolve_with_report(req, &state.env_store)?;
+    let settings = state.settings_store.get_http_settings()?;
+    let schema = graphql::introspect(&resolution.request, &settings, &state.client_pool).await?;
+    state.schema_store.set(&resolution.request.url, &schema)
+}
+
+/// The cached schema for an endpoint URL (variables allowed), if it was introspected
+#[tauri::command]
+fn get_graphql_schema(url: String, state: State<'_, AppState>) -> Result<Option<CachedSchema>, String> {
+    let url = resolve_variables_checked(&url, &active_variable_map(&state.env_store)?)?.value;
+    state.schema_store.get(&url)
+}
+
+/// Problems with a query, checked against the endpoint's cached schema when there is one
+#[tauri::command]
+fn validate_graphql_query(query: String, url: String, state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let schema = get_graphql_schema(url, state)?;
+    Ok(graphql::validate(&query, schema.as_ref().map(|c| &c.schema)))
+}
+
+/// Runs the request's OAuth 2.0 grant for a fresh access token, opening the
+/// system browser for the authorization code grant
+#[tauri::command]
+async fn oauth2_get_token(req: ApiRequest, state: State<'_, AppState>) -> Result<Token, String> {
+    let req = resolve_request_variables(req, &state.env_store)?;
+    let config = req.auth.oauth2.as_ref().ok_or("OAuth 2.0 is not configured")?;
+    let mut settings = state.settings_store.get_http_settings()?.with_override(req.settings.as_ref());
+    if let Ok(token_url) = url::Url::parse(&config.token_url) {
+        settings = settings.for_url(&token_url);
+    }
+    let pooled = state.client_pool.get(&settings)?;
+    oauth2::authorize(config, &pooled.client, &open_in_browser).await
+}
+
+/// The cached access token for the request's OAuth 2.0 config, if any
+#[tauri::command]
+fn oauth2_token_status(req: ApiRequest, state: State<'_, AppState>) -> Result<Option<Token>, String> {
+    let req = resolve_request_variables(req, &state.env_store)?;
+    Ok(req.auth.oauth2.as_ref().and_then(oauth2::cached))
+}
+
+#[tauri::command]
+fn oauth2_clear_token(req: ApiRequest, state: State<'_, AppState>) -> Result<(), String> {
+    let req = resolve_request_variables(req, &state.env_store)?;
+    if let Some(config) = &req.auth.oauth2 {
+        oauth2::clear(config);
+    }
+    Ok(())
+}
+
+fn open_in_browser(url: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = std::process::Command::new("rundll32");
+        command.args(["url.dll,FileProtocolHandler", url]);
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = std::process::Command::new("open");
+        command.arg(url);
+        command
+    };
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = {
+        let mut command = std::process::Command::new("xdg-open");
+        command.arg(url);
+        command
+    };
+    command.spawn().map(|_| ()).map_err(|e| format!("Failed to open the browser: {}", e))
+}
+
 #[tauri::command]
 fn get_history(state: State<'_, AppState>) -> Result<Vec<HistoryEntry>, String> {
     state.store.get_history()
@@ -58,21 +304,99 @@ fn clear_history(state: State<'_, AppState>) -> Result<(), String> {
 #[tauri::command]
 fn export_curl(req: ApiRequest, state: State<'_, AppState>) -> Result<String, String> {
     // Resolve environment variables before generating cURL
-    let resolved_req = resolve_request_variables(req, &state.env_store)?;
+    let mut resolved_req = resolve_request_variables(req, &state.env_store)?;
+    // Show the proxy the send would use, the global one unless the request has its own
+    let proxy = state.settings_store.get_http_settings()?.with_override(resolved_req.settings.as_ref()).proxy;
+    resolved_req.settings.get_or_insert_with(Default::default).proxy = Some(proxy);
     Ok(engine::curl::to_curl(&resolved_req))
 }
 
 #[tauri::command]
-fn get_env_vars(state: State<'_, AppState>) -> Result<Vec<EnvVar>, String> {
-    state.env_store.get_all()
-        .map_err(|e| format!("Failed to get env vars: {}", e))
+fn preview_request_variables(req: ApiRequest, state: State<'_, AppState>) -> Result<RequestResolution, String> {
+    resolve_with_report(req, &state.env_store)
+}
+
+#[tauri::command]
+fn preview_variable_text(input: String, state: State<'_, AppState>) -> Result<Resolved, String> {
+    resolve_variables_checked(&input, &active_variable_map(&state.env_store)?)
 }
 
 #[tauri::command]
-fn set_env_var(key: String, value: String, enabled: Option<bool>, state: State<'_, AppState>) -> Result<(), String> {
+fn import_curl(command: String) -> Result<ApiRequest, String> {
+    engine::curl::from_curl(&command)
+}
+
+#[tauri::command]
+fn get_env_vars(environment_id: Option<i64>, state: State<'_, AppState>) -> Result<Vec<EnvVar>, String> {
+    match environment_id {
+        Some(id) => state.env_store.get_vars(id),
+        None => state.env_store.get_all(),
+    }
+    .map_err(|e| format!("Failed to get env vars: {}", e))
+}
+
+#[tauri::command]
+fn set_env_var(key: String, value: String, enabled: Option<bool>, environment_id: Option<i64>, state: State<'_, AppState>) -> Result<(), String> {
     let en = enabled.unwrap_or(true);
-    state.env_store.set(&key, &value, en)
-        .map_err(|e| format!("Failed to set env var: {}", e))
+    match environment_id {
+        Some(id) => state.env_store.set_var(id, &key, &value, en),
+        None => state.env_store.set(&key, &value, en),
+    }
+    .map_err(|e| format!("Failed to set env var: {}", e))
+}
+
+#[tauri::command]
+fn get_global_vars(state: State<'_, AppState>) -> Result<Vec<EnvVar>, String> {
+    state.env_store.get_globals()
+        .map_err(|e| format!("Failed to get global vars: {}", e))
+}
+
+#[tauri::command]
+fn set_global_var(key: String, value: String, enabled: Option<bool>, state: State<'_, AppState>) -> Result<(), String> {
+    state.env_store.set_global(&key, &value, enabled.unwrap_or(true))
+        .map_err(|e| format!("Failed to set global var: {}", e))
+}
+
+#[tauri::command]
+fn delete_global_var(key: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.env_store.delete_global(&key)
+        .map_err(|e| format!("Failed to delete global var: {}", e))
+}
+
+#[tauri::command]
+fn list_environments(state: State<'_, AppState>) -> Result<Vec<Environment>, String> {
+    state.env_store.list_environments()
+        .map_err(|e| format!("Failed to list environments: {}", e))
+}
+
+#[tauri::command]
+fn create_environment(name: String, state: State<'_, AppState>) -> Result<Environment, String> {
+    state.env_store.create_environment(&name)
+        .map_err(|e| format!("Failed to create environment: {}", e))
+}
+
+#[tauri::command]
+fn rename_environment(id: i64, name: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.env_store.rename_environment(id, &name)
+        .map_err(|e| format!("Failed to rename environment: {}", e))
+}
+
+#[tauri::command]
+fn clone_environment(id: i64, name: String, state: State<'_, AppState>) -> Result<Environment, String> {
+    state.env_store.clone_environment(id, &name)
+        .map_err(|e| format!("Failed to clone environment: {}", e))
+}
+
+#[tauri::command]
+fn delete_environment(id: i64, state: State<'_, AppState>) -> Result<(), String> {
+    state.env_store.delete_environment(id)
+        .map_err(|e| format!("Failed to delete environment: {}", e))
+}
+
+#[tauri::command]
+fn set_active_environment(id: i64, state: State<'_, AppState>) -> Result<(), String> {
+    state.env_store.set_active_environment(id)
+        .map_err(|e| format!("Failed to set active environment: {}", e))
 }
 
 #[tauri::command]
@@ -80,11 +404,15 @@ async fn export_env_vars(app: AppHandle, state: State<'_, AppState>) -> Result<(
     use tauri_plugin_dialog::{DialogExt, FilePath};
     use std::fs;
 
+    let environment = state.env_store.active_environment().map_err(|e| format!("Failed to fetch environment: {}", e))?;
     let vars = state.env_store.get_all().map_err(|e| format!("Failed to fetch env vars: {}", e))?;
+    let globals = state.env_store.get_globals().map_err(|e| format!("Failed to fetch global vars: {}", e))?;
     let export = serde_json::json!({
         "version": "1.0",
         "exportedAt": chrono::Utc::now().to_rfc3339(),
+        "environment": environment.name,
         "env": vars,
+        "globals": globals,
     });
 
     let file_path = app.dialog()
@@ -106,9 +434,12 @@ async fn export_env_vars(app: AppHandle, state: State<'_, AppState>) -> Result<(
 }
 
 #[tauri::command]
-fn delete_env_var(key: String, state: State<'_, AppState>) -> Result<(), String> {
-    state.env_store.delete(&key)
-        .map_err(|e| format!("Failed to delete env var: {}", e))
+fn delete_env_var(key: String, environment_id: Option<i64>, state: State<'_, AppState>) -> Result<(), String> {
+    match environment_id {
+        Some(id) => state.env_store.delete_var(id, &key),
+        None => state.env_store.delete(&key),
+    }
+    .map_err(|e| format!("Failed to delete env var: {}", e))
 }
 
 #[tauri::command]
@@ -193,55 +524,318 @@ async fn save_collections(app: AppHandle, collections: serde_json::Value) -> Res
     Ok(())
 }
 
-/// Resolves environment variables in an ApiRequest
-fn resolve_request_variables(mut req: ApiRequest, env_store: &EnvStore) -> Result<ApiRequest, String> {
-    // Load all env vars into a HashMap
-    let env_vars = env_store.get_all()
-        .map_err(|e| format!("Failed to load env vars: {}", e))?;
+#[tauri::command]
+fn list_collections(state: State<'_, AppState>) -> Result<Vec<CollectionNode>, String> {
+    state.collection_store.list_collections()
+}
+
+#[tauri::command]
+fn get_collection_item(id: String, state: State<'_, AppState>) -> Result<CollectionNode, String> {
+    state.collection_store.get_node(&id)
+}
+
+#[tauri::command]
+fn create_collection(name: String, state: State<'_, AppState>) -> Result<CollectionNode, String> {
+    state.collection_store.create_collection(&name)
+}
+
+#[tauri::command]
+fn create_folder(parent_id: String, name: String, state: State<'_, AppState>) -> Result<CollectionNode, String> {
+    state.collection_store.create_folder(&parent_id, &name)
+}
+
+#[tauri::command]
+fn create_collection_request(parent_id: String, name: String, req: ApiRequest, state: State<'_, AppState>) -> Result<CollectionNode, String> {
+    state.collection_store.create_request(&parent_id, &name, req)
+}
+
+#[tauri::command]
+fn rename_collection_item(id: String, name: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.collection_store.rename_node(&id, &name)
+}
+
+#[tauri::command]
+fn update_collection_request(id: String, req: ApiRequest, state: State<'_, AppState>) -> Result<(), String> {
+    state.collection_store.update_request(&id, &req)
+}
+
+#[tauri::command]
+fn delete_collection_item(id: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.collection_store.delete_node(&id)
+}
+
+#[tauri::command]
+fn move_collection_item(id: String, parent_id: String, position: Option<usize>, state: State<'_, AppState>) -> Result<(), String> {
+    state.collection_store.move_node(&id, &parent_id, position)
+}
+
+#[tauri::command]
+fn reorder_collection_item(id: String, position: usize, state: State<'_, AppState>) -> Result<(), String> {
+    state.collection_store.reorder_node(&id, position)
+}
+
+#[tauri::command]
+fn duplicate_collection_item(id: String, state: State<'_, AppState>) -> Result<CollectionNode, String> {
+    state.collection_store.duplicate_node(&id)
+}
+
+#[tauri::command]
+async fn open_workspace_folder(app: AppHandle, state: State<'_, AppState>) -> Result<Option<CollectionNode>, String> {
+    use tauri_plugin_dialog::{DialogExt, FilePath};
     
-    let env_map: HashMap<String, String> = env_vars
-        .into_iter()
-        .map(|var| (var.key, var.value))
-        .collect();
+    // Open folder dialog (blocking)
+    let folder = app.dialog()
+        .file()
+        .set_title("Open Workspace Folder")
+        .blocking_pick_folder();
     
-    // Resolve URL
-    req.url = resolve_variables(&req.url, &env_map);
+    let root = match folder {
+        Some(FilePath::Path(p)) => p,
+        Some(FilePath::Url(_u)) => return Err("URL paths not supported".to_string()),
+        None => return Ok(None),
+    };
     
-    // Resolve params
-    for param in &mut req.params {
-        param.key = resolve_variables(&param.key, &env_map);
-        param.value = resolve_variables(&param.value, &env_map);
-    }
+    let tree = workspace::folder::load_workspace(&root)?;
     
-    // Resolve headers
-    for header in &mut req.headers {
-        header.key = resolve_variables(&header.key, &env_map);
-        header.value = resolve_variables(&header.value, &env_map);
-    }
+    // Refresh the frontend whenever files change on disk
+    let handle = app.clone();
+    let watcher = workspace::folder::watch_workspace(&root, move |result| match result {
+        Ok(tree) => {
+            if let Err(e) = handle.emit("workspace-changed", tree) {
+                eprintln!("Warning: Failed to emit workspace change: {}", e);
+            }
+        }
+        Err(e) => eprintln!("Warning: Failed to reload workspace: {}", e),
+    })?;
     
-    // Resolve body
-    if let Some(body) = &req.body {
-        req.body = Some(resolve_variables(body, &env_map));
-    }
+    *state.workspace.lock().unwrap() = Some(OpenWorkspace {
+        root,
+        _watcher: watcher,
+    });
     
-    // Resolve auth fields
-    if let Some(token) = &req.auth.token {
-        req.auth.token = Some(resolve_variables(token, &env_map));
+    Ok(Some(tree))
+}
+
+#[tauri::command]
+fn close_workspace(state: State<'_, AppState>) {
+    *state.workspace.lock().unwrap() = None;
+}
+
+#[tauri::command]
+fn get_workspace_tree(state: State<'_, AppState>) -> Result<Option<CollectionNode>, String> {
+    match state.workspace.lock().unwrap().as_ref() {
+        Some(ws) => workspace::folder::load_workspace(&ws.root).map(Some),
+        None => Ok(None),
     }
-    if let Some(api_key) = &req.auth.api_key {
-        req.auth.api_key = Some(resolve_variables(api_key, &env_map));
+}
+
+#[tauri::command]
+fn save_workspace_request(path: String, name: String, req: ApiRequest, state: State<'_, AppState>) -> Result<String, String> {
+    let root = workspace_root(&state)?;
+    workspace::folder::save_workspace_request(&root, &path, &name, req)
+}
+
+#[tauri::command]
+fn create_workspace_folder(path: String, state: State<'_, AppState>) -> Result<(), String> {
+    let root = workspace_root(&state)?;
+    workspace::folder::create_workspace_folder(&root, &path)
+}
+
+#[tauri::command]
+fn delete_workspace_item(path: String, state: State<'_, AppState>) -> Result<(), String> {
+    let root = workspace_root(&state)?;
+    workspace::folder::delete_workspace_item(&root, &path)
+}
+
+fn workspace_root(state: &AppState) -> Result<PathBuf, String> {
+    state.workspace.lock().unwrap()
+        .as_ref()
+        .map(|ws| ws.root.clone())
+        .ok_or_else(|| "No workspace folder is open".to_string())
+}
+
+#[tauri::command]
+async fn import_postman_collection(app: AppHandle, state: State<'_, AppState>) -> Result<Option<ImportOutcome<CollectionNode>>, String> {
+    let Some(content) = pick_text_file(&app, "Import Postman Collection", "JSON Files", &["json"])? else {
+        return Ok(None);
+    };
+    
+    let (tree, report) = workspace::postman::import_collection(&content)?;
+    let imported = state.collection_store.import_tree(&tree, None)?;
+    
+    Ok(Some(ImportOutcome { imported, report }))
+}
+
+#[tauri::command]
+async fn export_postman_collection(app: AppHandle, id: String, state: State<'_, AppState>) -> Result<Option<ConversionReport>, String> {
+    use tauri_plugin_dialog::{DialogExt, FilePath};
+    use std::fs;
+    
+    let collection = state.collection_store.get_node(&id)?;
+    let (export, report) = workspace::postman::export_collection(&collection);
+    
+    let file_path = app.dialog()
+        .file()
+        .set_title("Export Postman Collection")
+        .add_filter("JSON Files", &["json"])
+        .set_file_name(format!("{}.postman_collection.json", workspace::folder::slugify(&collection.name)))
+        .blocking_save_file();
+    
+    if let Some(path) = file_path {
+        let path_str = match path {
+            FilePath::Path(p) => p.to_str()
+                .ok_or_else(|| "Invalid file path".to_string())?.to_string(),
+            FilePath::Url(_u) => return Err("URL paths not supported".to_string()),
+        };
+        
+        let json = serde_json::to_string_pretty(&export)
+            .map_err(|e| format!("Failed to serialize collection: {}", e))?;
+        fs::write(&path_str, json)
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+        return Ok(Some(report));
     }
-    if let Some(api_value) = &req.auth.api_value {
-        req.auth.api_value = Some(resolve_variables(api_value, &env_map));
+    
+    Ok(None)
+}
+
+#[tauri::command]
+async fn import_postman_environment(app: AppHandle, state: State<'_, AppState>) -> Result<Option<ImportOutcome<Environment>>, String> {
+    let Some(content) = pick_text_file(&app, "Import Postman Environment", "JSON Files", &["json"])? else {
+        return Ok(None);
+    };
+    
+    let (name, vars, report) = workspace::postman::import_environment(&content)?;
+    
+    // Don't clash with an existing environment of the same name
+    let existing: Vec<String> = state.env_store.list_environments()
+        .map_err(|e| format!("Failed to list environments: {}", e))?
+        .into_iter()
+        .map(|env| env.name)
+        .collect();
+    let mut unique_name = name.clone();
+    let mut suffix = 2;
+    while existing.contains(&unique_name) {
+        unique_name = format!("{} ({})", name, suffix);
+        suffix += 1;
     }
-    if let Some(username) = &req.auth.username {
-        req.auth.username = Some(resolve_variables(username, &env_map));
+    
+    let environment = state.env_store.create_environment(&unique_name)
+        .map_err(|e| format!("Failed to create environment: {}", e))?;
+    for var in vars {
+        state.env_store.set_var(environment.id, &var.key, &var.value, var.enabled)
+            .map_err(|e| format!("Failed to set env var: {}", e))?;
     }
-    if let Some(password) = &req.auth.password {
-        req.auth.password = Some(resolve_variables(password, &env_map));
+    
+    Ok(Some(ImportOutcome { imported: environment, report }))
+}
+
+#[tauri::command]
+async fn import_openapi(app: AppHandle, state: State<'_, AppState>) -> Result<Option<ImportOutcome<CollectionNode>>, String> {
+    // Local files only; remote specs and external $refs are not fetched
+    let Some(content) = pick_text_file(&app, "Import OpenAPI / Swagger Spec", "OpenAPI Specs", &["json", "yaml", "yml"])? else {
+        return Ok(None);
+    };
+    
+    let (tree, report) = workspace::openapi::import_spec(&content)?;
+    let imported = state.collection_store.import_tree(&tree, None)?;
+    
+    Ok(Some(ImportOutcome { imported, report }))
+}
+
+#[tauri::command]
+async fn run_requests(
+    items: Vec<SavedRequest>,
+    options: Option<RunOptions>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RunReport, String> {
+    let variables = active_variable_map(&state.env_store)?;
+    let settings = state.settings_store.get_http_settings()?;
+    let options = options.unwrap_or_default();
+    
+    let report = runner::run_requests(items, variables, &settings, &state.client_pool, &options, |event| {
+        if let Err(e) = app.emit("runner-progress", event) {
+            eprintln!("Warning: Failed to emit runner progress: {}", e);
+        }
+    }).await;
+    
+    Ok(report)
+}
+
+#[tauri::command]
+async fn run_collection(
+    id: String,
+    options: Option<RunOptions>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RunReport, String> {
+    // Runs every request below the node, depth-first in display order
+    let items = state.collection_store.get_node(&id)?.flatten_requests();
+    run_requests(items, options, app, state).await
+}
+
+#[tauri::command]
+fn get_http_settings(state: State<'_, AppState>) -> Result<HttpSettings, String> {
+    state.settings_store.get_http_settings()
+}
+
+#[tauri::command]
+fn set_http_settings(settings: HttpSettings, state: State<'_, AppState>) -> Result<(), String> {
+    // Fail early on headers reqwest would reject at send time
+    engine::http::build_client(&settings)?;
+    engine::tls::validate(&settings)?;
+    state.settings_store.set_http_settings(&settings)
+}
+
+#[tauri::command]
+fn reset_http_settings(state: State<'_, AppState>) -> Result<HttpSettings, String> {
+    state.settings_store.reset_http_settings()
+}
+
+/// Closes all pooled connections; returns how many clients were dropped
+#[tauri::command]
+fn reset_connections(state: State<'_, AppState>) -> Result<usize, String> {
+    Ok(state.client_pool.reset())
+}
+
+/// Opens a file dialog and reads the chosen file, or None if cancelled
+fn pick_text_file(app: &AppHandle, title: &str, filter: &str, extensions: &[&str]) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::{DialogExt, FilePath};
+    
+    let file_path = app.dialog()
+        .file()
+        .set_title(title)
+        .add_filter(filter, extensions)
+        .blocking_pick_file();
+    
+    match file_path {
+        Some(FilePath::Path(p)) => std::fs::read_to_string(&p)
+            .map(Some)
+            .map_err(|e| format!("Failed to read file: {}", e)),
+        Some(FilePath::Url(_u)) => Err("URL paths not supported".to_string()),
+        None => Ok(None),
     }
+}
+
+/// Resolves environment variables in an ApiRequest
+fn resolve_request_variables(req: ApiRequest, env_store: &EnvStore) -> Result<ApiRequest, String> {
+    Ok(resolve_with_report(req, env_store)?.request)
+}
+
+/// Resolves against the active environment with the global scope as fallback,
+/// reporting any variables that have no (enabled) value
+fn resolve_with_report(req: ApiRequest, env_store: &EnvStore) -> Result<RequestResolution, String> {
+    resolve_request(req, &active_variable_map(env_store)?)
+}
+
+/// Enabled globals overlaid with the active environment
+fn active_variable_map(env_store: &EnvStore) -> Result<HashMap<String, String>, String> {
+    let global_vars = env_store.get_globals()
+        .map_err(|e| format!("Failed to load global vars: {}", e))?;
+    let env_vars = env_store.get_all()
+        .map_err(|e| format!("Failed to load env vars: {}", e))?;
     
-    Ok(req)
+    Ok(build_variable_map(global_vars, env_vars))
 }
 
 fn main() {
@@ -269,9 +863,38 @@ fn main() {
             let env_store = EnvStore::new(env_db_str)
                 .map_err(|e| format!("Failed to initialize env store: {}", e))?;
             
+            let collections_db_path = app_data_dir.join("collections.db");
+            let collections_db_str = collections_db_path.to_str()
+                .ok_or_else(|| "Invalid collections database path".to_string())?;
+            
+            let collection_store = CollectionStore::new(collections_db_str)
+                .map_err(|e| format!("Failed to initialize collection store: {}", e))?;
+            
+            let settings_db_path = app_data_dir.join("settings.db");
+            let settings_db_str = settings_db_path.to_str()
+                .ok_or_else(|| "Invalid settings database path".to_string())?;
+            
+            let settings_store = SettingsStore::new(settings_db_str)
+                .map_err(|e| format!("Failed to initialize settings store: {}", e))?;
+            
+            let schema_db_path = app_data_dir.join("graphql.db");
+            let schema_db_str = schema_db_path.to_str()
+                .ok_or_else(|| "Invalid GraphQL schema database path".to_string())?;
+            
+            let schema_store = SchemaStore::new(schema_db_str)
+                .map_err(|e| format!("Failed to initialize GraphQL schema store: {}", e))?;
+            
             let app_state = AppState {
                 store: Arc::new(store),
                 env_store: Arc::new(env_store),
+                collection_store: Arc::new(collection_store),
+                settings_store: Arc::new(settings_store),
+                schema_store: Arc::new(schema_store),
+                client_pool: Arc::new(ClientPool::new()),
+                ws_sessions: Arc::new(WsSessions::new()),
+                streams: Arc::new(Streams::new()),
+                workspace: Mutex::new(None),
+                last_response_body: Mutex::new(None),
             };
             
             app.manage(app_state);
@@ -283,12 +906,63 @@ fn main() {
             get_history,
             clear_history,
             export_curl,
+            import_curl,
+            preview_request_variables,
+            preview_variable_text,
             get_env_vars,
             set_env_var,
             delete_env_var,
+            get_global_vars,
+            set_global_var,
+            delete_global_var,
+            list_environments,
+            create_environment,
+            rename_environment,
+            clone_environment,
+            delete_environment,
+            set_active_environment,
             save_request,
             load_request,
             save_collections,
+            list_collections,
+            get_collection_item,
+            create_collection,
+            create_folder,
+            create_collection_request,
+            rename_collection_item,
+            update_collection_request,
+            delete_collection_item,
+            move_collection_item,
+            reorder_collection_item,
+            duplicate_collection_item,
+            open_workspace_folder,
+            close_workspace,
+            get_workspace_tree,
+            save_workspace_request,
+            create_workspace_folder,
+            delete_workspace_item,
+            import_postman_collection,
+            export_postman_collection,
+            import_postman_environment,
+            import_openapi,
+            run_requests,
+            run_collection,
+            get_http_settings,
+            set_http_settings,
+            reset_http_settings,
+            reset_connections,
+            save_response_body,
+            introspect_graphql_schema,
+            get_graphql_schema,
+            validate_graphql_query,
+            oauth2_get_token,
+            oauth2_token_status,
+            oauth2_clear_token,
+            ws_connect,
+            ws_send,
+            ws_close,
+            send_streaming_request,
+            cancel_stream,
             export_env_vars
         ])
         .run(tauri::generate_context!())
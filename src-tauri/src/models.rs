@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use crate::settings::HttpSettingsOverride;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct KeyValue {
     pub enabled: bool,
     pub key: String,
@@ -21,9 +22,75 @@ pub struct AuthPayload {
     pub api_location: Option<String>,
     pub username: Option<String>,
     pub password: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth2: Option<OAuth2Config>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub awsv4: Option<AwsV4Config>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Credentials and scope an "awsv4" auth signs requests with
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AwsV4Config {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// For temporary credentials; sent as X-Amz-Security-Token
+    #[serde(default)]
+    pub session_token: String,
+    pub region: String,
+    /// Signing name of the service, e.g. "execute-api" or "s3"
+    pub service: String,
+}
+
+/// How an "oauth2" auth gets its access token
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuth2Config {
+    #[serde(default)]
+    pub grant_type: OAuth2Grant,
+    pub token_url: String,
+    /// Authorization endpoint, for the authorization code grant
+    #[serde(default)]
+    pub auth_url: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: String,
+    #[serde(default)]
+    pub scope: String,
+    /// Resource owner credentials, for the password grant
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+    /// Port of the loopback redirect listener; 0 picks a free one
+    #[serde(default)]
+    pub redirect_port: u16,
+    #[serde(default)]
+    pub client_authentication: ClientAuthentication,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OAuth2Grant {
+    #[default]
+    ClientCredentials,
+    Password,
+    /// With PKCE, redirecting to a listener on 127.0.0.1
+    AuthorizationCode,
+}
+
+/// Where the client credentials go in token requests
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ClientAuthentication {
+    /// HTTP Basic auth header
+    #[default]
+    Basic,
+    /// `client_id` and `client_secret` form fields
+    Body,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ApiRequest {
     pub method: String,
     pub url: String,
@@ -31,17 +98,371 @@ pub struct ApiRequest {
     pub params: Vec<KeyValue>,
     #[serde(default)]
     pub headers: Vec<KeyValue>,
-    pub body: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_request_body")]
+    pub body: RequestBody,
     pub auth: AuthPayload,
+    #[serde(default)]
+    pub assertions: Vec<Assertion>,
+    #[serde(default)]
+    pub extractions: Vec<Extraction>,
+    /// Client settings for this request only, on top of the global ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<HttpSettingsOverride>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ApiRequest {
+    /// A GET request with nothing else set
+    pub fn get(url: impl Into<String>) -> Self {
+        ApiRequest { method: "GET".to_string(), url: url.into(), ..Default::default() }
+    }
+}
+
+/// What a request sends as its body
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum RequestBody {
+    #[default]
+    None,
+    /// Text sent as-is; `content_type` applies unless a Content-Type header is set
+    Raw {
+        content: String,
+        #[serde(rename = "contentType", default, skip_serializing_if = "Option::is_none")]
+        content_type: Option<String>,
+    },
+    Urlencoded { fields: Vec<KeyValue> },
+    Multipart { parts: Vec<FormPart> },
+    /// Contents of the file at `path`
+    Binary {
+        path: String,
+        #[serde(rename = "contentType", default, skip_serializing_if = "Option::is_none")]
+        content_type: Option<String>,
+    },
+    /// Sent as the standard JSON envelope; `variables` is JSON text so it can hold `{{VAR}}`s
+    #[serde(rename = "graphql")]
+    GraphQl {
+        query: String,
+        #[serde(default)]
+        variables: String,
+        #[serde(rename = "operationName", default, skip_serializing_if = "Option::is_none")]
+        operation_name: Option<String>,
+    },
+}
+
+impl RequestBody {
+    pub fn raw(content: impl Into<String>) -> Self {
+        RequestBody::Raw { content: content.into(), content_type: None }
+    }
+
+    /// The text of a raw body
+    pub fn as_raw(&self) -> Option<&str> {
+        match self {
+            RequestBody::Raw { content, .. } => Some(content),
+            _ => None,
+        }
+    }
+
+    /// True when there is nothing to send
+    pub fn is_empty(&self) -> bool {
+        match self {
+            RequestBody::None => true,
+            RequestBody::Raw { content, .. } => content.trim().is_empty(),
+            RequestBody::Urlencoded { fields } => !fields.iter().any(|f| f.enabled),
+            RequestBody::Multipart { parts } => !parts.iter().any(|p| p.enabled),
+            RequestBody::Binary { path, .. } => path.trim().is_empty(),
+            RequestBody::GraphQl { query, .. } => query.trim().is_empty(),
+        }
+    }
+}
+
+/// One field of a multipart/form-data body
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormPart {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub key: String,
+    #[serde(default)]
+    pub kind: FormPartKind,
+    /// The text to send, or the path of the file to upload
+    pub value: String,
+    /// Overrides the Content-Type guessed from the file name
+    #[serde(rename = "contentType", default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FormPartKind {
+    #[default]
+    Text,
+    File,
+}
+
+/// Requests saved before body modes existed store the body as a plain string
+fn deserialize_request_body<'de, D>(deserializer: D) -> Result<RequestBody, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Typed(RequestBody),
+        Legacy(Option<String>),
+    }
+
+    Ok(match Stored::deserialize(deserializer)? {
+        Stored::Typed(body) => body,
+        Stored::Legacy(Some(text)) if !text.is_empty() => RequestBody::raw(text),
+        Stored::Legacy(_) => RequestBody::None,
+    })
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ApiResponse {
     pub status: u16,
     #[serde(rename = "statusText")]
     pub status_text: String,
     pub time: u128,
     pub size: String,
+    #[serde(rename = "sizeBytes", default)]
+    pub size_bytes: u64,
     pub headers: HashMap<String, String>,
-    pub body: serde_json::Value,
+    #[serde(deserialize_with = "deserialize_response_body")]
+    pub body: ResponseBody,
+    #[serde(rename = "assertionResults", default)]
+    pub assertion_results: Vec<AssertionResult>,
+    #[serde(rename = "extractedValues", default)]
+    pub extracted_values: Vec<ExtractionResult>,
+    /// True if the send went over an existing pooled connection
+    #[serde(rename = "connectionReused", default)]
+    pub connection_reused: bool,
+    #[serde(default)]
+    pub timings: ResponseTimings,
+    /// What was negotiated with the server; None for plain http
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsInfo>,
+    /// Proxy the request went through, without credentials
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Problems found before sending that did not block it, e.g. GraphQL schema mismatches
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Body exactly as received, kept for saving to disk; never serialized
+    #[serde(skip)]
+    pub raw_body: Vec<u8>,
+}
+
+/// Response body decoded according to its Content-Type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ResponseBody {
+    Json { value: serde_json::Value },
+    /// Text decoded from `charset` (the declared one, or the one detected)
+    Text { text: String, charset: String },
+    Binary { base64: String, mime: String },
+}
+
+impl Default for ResponseBody {
+    fn default() -> Self {
+        ResponseBody::Text { text: String::new(), charset: "utf-8".to_string() }
+    }
+}
+
+impl ResponseBody {
+    pub fn json(&self) -> Option<&serde_json::Value> {
+        match self {
+            ResponseBody::Json { value } => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Body as text for matching; None for binary bodies
+    pub fn text(&self) -> Option<std::borrow::Cow<'_, str>> {
+        match self {
+            ResponseBody::Json { value } => Some(value.to_string().into()),
+            ResponseBody::Text { text, .. } => Some(text.as_str().into()),
+            ResponseBody::Binary { .. } => None,
+        }
+    }
+}
+
+/// History written before bodies were typed stores the bare JSON value
+fn deserialize_response_body<'de, D>(deserializer: D) -> Result<ResponseBody, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Typed(ResponseBody),
+        Legacy(serde_json::Value),
+    }
+
+    Ok(match Stored::deserialize(deserializer)? {
+        Stored::Typed(body) => body,
+        Stored::Legacy(serde_json::Value::String(text)) => ResponseBody::Text { text, charset: "utf-8".to_string() },
+        Stored::Legacy(value) => ResponseBody::Json { value },
+    })
+}
+
+/// Where the time of a send went, in milliseconds. Connection phases are
+/// None when a pooled connection was reused or the phase didn't happen
+/// (no DNS for IP addresses, no TLS for plain http).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResponseTimings {
+    pub dns: Option<f64>,
+    pub connect: Option<f64>,
+    pub tls: Option<f64>,
+    /// From the connection being ready (or the send, if reused) to the response headers
+    pub ttfb: f64,
+    pub download: f64,
+    pub total: f64,
+}
+
+/// Protocol, cipher suite and certificates of a TLS connection
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TlsInfo {
+    /// `TLSv1.2` or `TLSv1.3`
+    pub protocol: String,
+    pub cipher: String,
+    /// Chain presented by the server, leaf first
+    pub certificates: Vec<CertificateInfo>,
+    /// The chain was accepted without being checked
+    #[serde(rename = "verificationSkipped", default)]
+    pub verification_skipped: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    /// RFC 3339 timestamps
+    #[serde(rename = "notBefore")]
+    pub not_before: String,
+    #[serde(rename = "notAfter")]
+    pub not_after: String,
+    /// Hex, colon separated
+    pub serial: String,
+    /// SHA-256 fingerprint of the DER encoding, hex
+    pub sha256: String,
+}
+
+/// One message of a WebSocket session or streamed response, in the order it was sent or received
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    pub direction: Direction,
+    #[serde(flatten)]
+    pub frame: Frame,
+    /// Milliseconds since the connection opened
+    #[serde(rename = "elapsedMs")]
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Frame {
+    Text { text: String },
+    Binary { base64: String },
+    Close {
+        #[serde(default)]
+        code: Option<u16>,
+        #[serde(default)]
+        reason: String,
+    },
+    /// A server-sent event
+    Event {
+        event: String,
+        data: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        retry: Option<u64>,
+    },
+}
+
+fn default_enabled() -> bool { true }
+
+/// A check run against the response after each send
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Assertion {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub check: AssertionCheck,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AssertionCheck {
+    StatusEquals { value: u16 },
+    StatusInRange { min: u16, max: u16 },
+    HeaderPresent { name: String },
+    HeaderMatches { name: String, pattern: String },
+    JsonPathEquals { path: String, value: serde_json::Value },
+    JsonPathExists { path: String },
+    /// One of "string", "number", "boolean", "object", "array", "null"
+    JsonPathType { path: String, expected: String },
+    BodyContains { value: String },
+    BodyMatches { pattern: String },
+    ResponseTimeBelow { ms: u128 },
+    SizeBelow { bytes: u64 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssertionResult {
+    pub assertion: Assertion,
+    pub passed: bool,
+    pub message: String,
+}
+
+/// Captures a value from the response into a variable after each send
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Extraction {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Name of the variable to write, without braces
+    pub variable: String,
+    #[serde(default)]
+    pub scope: ExtractionScope,
+    #[serde(flatten)]
+    pub source: ExtractionSource,
+}
+
+/// Where a captured value is stored when sending a single request.
+/// Collection runs always keep captures in the run's own variable set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtractionScope {
+    #[default]
+    Environment,
+    Global,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "from", rename_all = "camelCase")]
+pub enum ExtractionSource {
+    JsonPath { path: String },
+    Header { name: String },
+    /// Captures `group` (0 = whole match) of the first match in the body
+    Regex {
+        pattern: String,
+        #[serde(default)]
+        group: usize,
+    },
+    Cookie { name: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionResult {
+    pub variable: String,
+    pub scope: ExtractionScope,
+    /// None when nothing matched
+    pub value: Option<String>,
+    pub error: Option<String>,
 }
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use crate::engine;
+use crate::engine::pool::ClientPool;
+use crate::env::resolve_request;
+use crate::settings::HttpSettings;
+use crate::workspace::models::SavedRequest;
+use super::models::{RunEvent, RunOptions, RunReport, RunResult, RunStatus};
+
+/// Executes requests one after another in the given order.
+/// Variables are resolved per request against `variables`, which is the run's
+/// own copy: values captured by extraction rules are added to it so later
+/// requests can use them. Every step is reported through `on_event` and the
+/// full report is returned at the end.
+pub async fn run_requests<F>(
+    items: Vec<SavedRequest>,
+    mut variables: HashMap<String, String>,
+    settings: &HttpSettings,
+    pool: &ClientPool,
+    options: &RunOptions,
+    mut on_event: F,
+) -> RunReport
+where
+    F: FnMut(RunEvent),
+{
+    let started_at = chrono::Utc::now().timestamp_millis();
+    let start = Instant::now();
+    let total = items.len();
+    on_event(RunEvent::Started { total });
+
+    let mut results = Vec::with_capacity(total);
+    let mut stop = false;
+
+    for (index, item) in items.into_iter().enumerate() {
+        if stop {
+            results.push(skipped(index, &item));
+            continue;
+        }
+        if index > 0 && options.delay_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(options.delay_ms)).await;
+        }
+
+        on_event(RunEvent::RequestStarted { index, name: item.name.clone() });
+        let result = run_one(index, item, &variables, settings, pool).await;
+        for extracted in &result.extracted_values {
+            if let Some(value) = &extracted.value {
+                variables.insert(extracted.variable.clone(), value.clone());
+            }
+        }
+        on_event(RunEvent::RequestFinished { result: result.clone() });
+
+        if options.stop_on_failure && result.status != RunStatus::Passed {
+            stop = true;
+        }
+        results.push(result);
+    }
+
+    let count = |status: RunStatus| results.iter().filter(|r| r.status == status).count();
+    let skipped = count(RunStatus::Skipped);
+    let report = RunReport {
+        started_at,
+        duration_ms: start.elapsed().as_millis(),
+        total,
+        passed: count(RunStatus::Passed),
+        failed: count(RunStatus::Failed),
+        errors: count(RunStatus::Error),
+        skipped,
+        stopped_early: skipped > 0,
+        results,
+    };
+    on_event(RunEvent::Finished { report: report.clone() });
+    report
+}
+
+async fn run_one(
+    index: usize,
+    item: SavedRequest,
+    variables: &HashMap<String, String>,
+    settings: &HttpSettings,
+    pool: &ClientPool,
+) -> RunResult {
+    let mut result = RunResult {
+        index,
+        name: item.name,
+        method: item.request.method.clone(),
+        url: item.request.url.clone(),
+        status: RunStatus::Error,
+        http_status: None,
+        status_text: None,
+        time: None,
+        size_bytes: None,
+        error: None,
+        assertion_results: Vec::new(),
+        extracted_values: Vec::new(),
+    };
+
+    let request = match resolve_request(item.request, variables) {
+        Ok(resolution) => resolution.request,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
+    };
+    result.url = request.url.clone();
+
+    let assertions = request.assertions.clone();
+    let extractions = request.extractions.clone();
+    match engine::http::execute_request(request, settings, pool).await {
+        Ok(response) => {
+            result.assertion_results = engine::assertions::evaluate(&assertions, &response);
+            result.extracted_values = engine::extract::extract(&extractions, &response);
+            // Without assertions to check, any response below 400 passes
+            let passed = if result.assertion_results.is_empty() {
+                response.status < 400
+            } else {
+                result.assertion_results.iter().all(|r| r.passed)
+            };
+            result.status = if passed {
+                RunStatus::Passed
+            } else {
+                RunStatus::Failed
+            };
+            result.http_status = Some(response.status);
+            result.status_text = Some(response.status_text);
+            result.time = Some(response.time);
+            result.size_bytes = Some(response.size_bytes);
+        }
+        Err(e) => result.error = Some(e),
+    }
+    result
+}
+
+fn skipped(index: usize, item: &SavedRequest) -> RunResult {
+    RunResult {
+        index,
+        name: item.name.clone(),
+        method: item.request.method.clone(),
+        url: item.request.url.clone(),
+        status: RunStatus::Skipped,
+        http_status: None,
+        status_text: None,
+        time: None,
+        size_bytes: None,
+        error: None,
+        assertion_results: Vec::new(),
+        extracted_values: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ApiRequest, Assertion, AssertionCheck, AuthPayload, Extraction, ExtractionScope, ExtractionSource};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Serves one connection per status line, in order
+    fn serve(responses: Vec<&'static str>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for status in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf);
+                let body = r#"{"ok":true}"#;
+                let reply = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(reply.as_bytes());
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    fn item(name: &str, url: &str, expect_status: u16) -> SavedRequest {
+        SavedRequest {
+            name: name.to_string(),
+            request: ApiRequest {
+                method: "GET".to_string(),
+                url: url.to_string(),
+                auth: AuthPayload { auth_type: "none".to_string(), ..Default::default() },
+                assertions: vec![Assertion {
+                    enabled: true,
+                    check: AssertionCheck::StatusEquals { value: expect_status },
+                }],
+                ..Default::default()
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_reports_each_request() {
+        let base = serve(vec!["200 OK", "404 Not Found"]);
+        let mut vars = HashMap::new();
+        vars.insert("BASE".to_string(), base.clone());
+
+        let mut events = Vec::new();
+        let report = run_requests(
+            vec![item("first", "{{BASE}}/a", 200), item("second", "{{BASE}}/b", 200)],
+            vars,
+            &HttpSettings::default(),
+            &ClientPool::new(),
+            &RunOptions::default(),
+            |event| events.push(event),
+        )
+        .await;
+
+        assert_eq!(report.total, 2);
+        assert_eq!(report.passed, 1);
+        assert_eq!(report.failed, 1);
+        assert!(!report.stopped_early);
+        assert_eq!(report.results[0].url, format!("{}/a", base));
+        assert_eq!(report.results[1].http_status, Some(404));
+        // started + 2 x (started, finished) + finished
+        assert_eq!(events.len(), 6);
+        assert!(matches!(events.last(), Some(RunEvent::Finished { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_error_status_fails_without_assertions() {
+        let base = serve(vec!["204 No Content", "500 Internal Server Error"]);
+        let mut vars = HashMap::new();
+        vars.insert("BASE".to_string(), base);
+
+        let mut items = vec![item("ok", "{{BASE}}/a", 200), item("broken", "{{BASE}}/b", 200)];
+        for item in &mut items {
+            item.request.assertions.clear();
+        }
+        let report = run_requests(items, vars, &HttpSettings::default(), &ClientPool::new(), &RunOptions::default(), |_| {}).await;
+
+        assert_eq!(report.results[0].status, RunStatus::Passed);
+        assert_eq!(report.results[1].status, RunStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_extracted_values_feed_later_requests() {
+        let base = serve(vec!["200 OK", "200 OK"]);
+        let mut vars = HashMap::new();
+        vars.insert("BASE".to_string(), base.clone());
+
+        let mut login = item("login", "{{BASE}}/login", 200);
+        login.request.extractions.push(Extraction {
+            enabled: true,
+            variable: "FLAG".to_string(),
+            scope: ExtractionScope::Environment,
+            source: ExtractionSource::JsonPath { path: "$.ok".to_string() },
+        });
+        let report = run_requests(
+            vec![login, item("next", "{{BASE}}/items/{{FLAG}}", 200)],
+            vars,
+            &HttpSettings::default(),
+            &ClientPool::new(),
+            &RunOptions::default(),
+            |_| {},
+        )
+        .await;
+
+        assert_eq!(report.results[0].extracted_values[0].value.as_deref(), Some("true"));
+        assert_eq!(report.results[1].url, format!("{}/items/true", base));
+    }
+
+    #[tokio::test]
+    async fn test_stop_on_failure_skips_rest() {
+        let mut vars = HashMap::new();
+        vars.insert("A".to_string(), "{{B}}".to_string());
+        vars.insert("B".to_string(), "{{A}}".to_string());
+
+        let options = RunOptions { stop_on_failure: true, delay_ms: 0 };
+        let report = run_requests(
+            vec![item("cycle", "http://{{A}}", 200), item("never", "http://127.0.0.1:9", 200)],
+            vars,
+            &HttpSettings::default(),
+            &ClientPool::new(),
+            &options,
+            |_| {},
+        )
+        .await;
+
+        assert!(report.stopped_early);
+        assert_eq!(report.errors, 1);
+        assert_eq!(report.skipped, 1);
+        assert!(report.results[0].error.as_deref().unwrap().contains("Circular"));
+        assert_eq!(report.results[1].status, RunStatus::Skipped);
+    }
+}
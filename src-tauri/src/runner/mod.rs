@@ -0,0 +1,6 @@
+pub mod models;
+pub mod executor;
+pub mod report;
+
+pub use models::{RunOptions, RunReport};
+pub use executor::run_requests;
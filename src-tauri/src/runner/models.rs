@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use crate::models::{AssertionResult, ExtractionResult};
+
+/// Options controlling a collection run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunOptions {
+    /// Stop after the first request that errors or fails an assertion
+    #[serde(rename = "stopOnFailure", default)]
+    pub stop_on_failure: bool,
+    /// Pause between consecutive requests, in milliseconds
+    #[serde(rename = "delayMs", default)]
+    pub delay_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    /// Every assertion held; a request without assertions passes on any status below 400
+    Passed,
+    /// An assertion failed, or a request without assertions got a 4xx/5xx
+    Failed,
+    Error,
+    Skipped,
+}
+
+/// Outcome of a single request within a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunResult {
+    pub index: usize,
+    pub name: String,
+    pub method: String,
+    /// URL after variable resolution
+    pub url: String,
+    pub status: RunStatus,
+    #[serde(rename = "httpStatus")]
+    pub http_status: Option<u16>,
+    #[serde(rename = "statusText")]
+    pub status_text: Option<String>,
+    /// Response time reported by the engine, in milliseconds
+    pub time: Option<u128>,
+    #[serde(rename = "sizeBytes")]
+    pub size_bytes: Option<u64>,
+    pub error: Option<String>,
+    #[serde(rename = "assertionResults")]
+    pub assertion_results: Vec<AssertionResult>,
+    #[serde(rename = "extractedValues", default)]
+    pub extracted_values: Vec<ExtractionResult>,
+}
+
+/// Summary returned once a run finishes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunReport {
+    /// Unix timestamp in milliseconds
+    #[serde(rename = "startedAt")]
+    pub started_at: i64,
+    #[serde(rename = "durationMs")]
+    pub duration_ms: u128,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub errors: usize,
+    pub skipped: usize,
+    /// True if the run ended early because of `stopOnFailure`
+    #[serde(rename = "stoppedEarly")]
+    pub stopped_early: bool,
+    pub results: Vec<RunResult>,
+}
+
+impl RunReport {
+    /// True when nothing errored or failed an assertion
+    pub fn success(&self) -> bool {
+        self.failed == 0 && self.errors == 0
+    }
+}
+
+/// Progress notifications emitted while a run is in flight
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RunEvent {
+    Started { total: usize },
+    RequestStarted { index: usize, name: String },
+    RequestFinished { result: RunResult },
+    Finished { report: RunReport },
+}
@@ -0,0 +1,178 @@
+use std::fmt::Write;
+use super::models::{RunReport, RunResult, RunStatus};
+
+/// Human-readable summary, one line per request plus failure details
+pub fn to_text(report: &RunReport) -> String {
+    let mut out = String::new();
+    for result in &report.results {
+        let label = match result.status {
+            RunStatus::Passed => "PASS",
+            RunStatus::Failed => "FAIL",
+            RunStatus::Error => "ERROR",
+            RunStatus::Skipped => "SKIP",
+        };
+        let _ = write!(out, "{:<5} {} {} {}", label, result.method, result.url, result.name);
+        if let (Some(status), Some(time)) = (result.http_status, result.time) {
+            let _ = write!(out, " [{} in {} ms]", status, time);
+        }
+        out.push('\n');
+        if let Some(error) = &result.error {
+            let _ = writeln!(out, "      {}", error);
+        }
+        for assertion in result.assertion_results.iter().filter(|a| !a.passed) {
+            let _ = writeln!(out, "      {}", assertion.message);
+        }
+    }
+    let _ = writeln!(
+        out,
+        "\n{} requests: {} passed, {} failed, {} errors, {} skipped ({} ms)",
+        report.total, report.passed, report.failed, report.errors, report.skipped, report.duration_ms
+    );
+    out
+}
+
+/// JUnit XML, one test case per request, for CI test reporters
+pub fn to_junit(report: &RunReport, suite_name: &str) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\">",
+        escape_xml(suite_name),
+        report.total,
+        report.failed,
+        report.errors,
+        report.skipped,
+        seconds(report.duration_ms)
+    );
+    let _ = writeln!(
+        out,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\">",
+        escape_xml(suite_name),
+        report.total,
+        report.failed,
+        report.errors,
+        report.skipped,
+        seconds(report.duration_ms)
+    );
+    for result in &report.results {
+        write_test_case(&mut out, result);
+    }
+    out.push_str("  </testsuite>\n</testsuites>\n");
+    out
+}
+
+fn write_test_case(out: &mut String, result: &RunResult) {
+    let _ = write!(
+        out,
+        "    <testcase name=\"{}\" classname=\"{} {}\" time=\"{}\"",
+        escape_xml(&result.name),
+        escape_xml(&result.method),
+        escape_xml(&result.url),
+        seconds(result.time.unwrap_or(0))
+    );
+    match result.status {
+        RunStatus::Passed => out.push_str("/>\n"),
+        RunStatus::Skipped => out.push_str(">\n      <skipped/>\n    </testcase>\n"),
+        RunStatus::Error => {
+            let message = result.error.as_deref().unwrap_or("Request failed");
+            let _ = write!(
+                out,
+                ">\n      <error message=\"{}\"/>\n    </testcase>\n",
+                escape_xml(message)
+            );
+        }
+        RunStatus::Failed => {
+            out.push_str(">\n");
+            for assertion in result.assertion_results.iter().filter(|a| !a.passed) {
+                let _ = writeln!(
+                    out,
+                    "      <failure message=\"{}\"/>",
+                    escape_xml(&assertion.message)
+                );
+            }
+            out.push_str("    </testcase>\n");
+        }
+    }
+}
+
+fn seconds(ms: u128) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Assertion, AssertionCheck, AssertionResult};
+
+    fn result(index: usize, status: RunStatus) -> RunResult {
+        RunResult {
+            index,
+            name: format!("req {}", index),
+            method: "GET".to_string(),
+            url: "http://localhost/items?a=1&b=2".to_string(),
+            status,
+            http_status: Some(200),
+            status_text: Some("OK".to_string()),
+            time: Some(1500),
+            size_bytes: Some(10),
+            error: None,
+            assertion_results: Vec::new(),
+            extracted_values: Vec::new(),
+        }
+    }
+
+    fn report() -> RunReport {
+        let mut failed = result(1, RunStatus::Failed);
+        failed.assertion_results.push(AssertionResult {
+            assertion: Assertion { enabled: true, check: AssertionCheck::StatusEquals { value: 201 } },
+            passed: false,
+            message: "Expected status 201, got 200".to_string(),
+        });
+        let mut errored = result(2, RunStatus::Error);
+        errored.error = Some("Request failed: <timeout>".to_string());
+        RunReport {
+            started_at: 0,
+            duration_ms: 2500,
+            total: 4,
+            passed: 1,
+            failed: 1,
+            errors: 1,
+            skipped: 1,
+            stopped_early: false,
+            results: vec![result(0, RunStatus::Passed), failed, errored, result(3, RunStatus::Skipped)],
+        }
+    }
+
+    #[test]
+    fn test_junit_output() {
+        let xml = to_junit(&report(), "smoke");
+        assert!(xml.contains("<testsuite name=\"smoke\" tests=\"4\" failures=\"1\" errors=\"1\" skipped=\"1\" time=\"2.500\">"));
+        assert!(xml.contains("classname=\"GET http://localhost/items?a=1&amp;b=2\" time=\"1.500\"/>"));
+        assert!(xml.contains("<failure message=\"Expected status 201, got 200\"/>"));
+        assert!(xml.contains("<error message=\"Request failed: &lt;timeout&gt;\"/>"));
+        assert!(xml.contains("<skipped/>"));
+    }
+
+    #[test]
+    fn test_text_output() {
+        let text = to_text(&report());
+        assert!(text.contains("FAIL  GET http://localhost/items?a=1&b=2 req 1 [200 in 1500 ms]"));
+        assert!(text.contains("      Expected status 201, got 200"));
+        assert!(text.ends_with("4 requests: 1 passed, 1 failed, 1 errors, 1 skipped (2500 ms)\n"));
+    }
+}
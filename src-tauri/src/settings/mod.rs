@@ -0,0 +1,7 @@
+pub mod models;
+pub mod store;
+
+pub use models::{
+    ClientIdentity, HttpSettings, HttpSettingsOverride, HttpVersion, ProxyMode, ProxySettings, TlsHostSettings, TlsVersion,
+};
+pub use store::SettingsStore;
@@ -0,0 +1,309 @@
+use serde::{Deserialize, Serialize};
+use crate::models::KeyValue;
+
+/// Which HTTP version the client should negotiate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HttpVersion {
+    /// HTTP/2 when the server offers it via ALPN, otherwise HTTP/1.1
+    #[default]
+    Auto,
+    Http1,
+    /// HTTP/2 without negotiation (prior knowledge)
+    Http2,
+}
+
+/// Client behaviour applied to every send unless a request overrides it
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpSettings {
+    /// Total request timeout in milliseconds, 0 disables it
+    #[serde(rename = "timeoutMs")]
+    pub timeout_ms: u64,
+    /// Connection timeout in milliseconds, 0 disables it
+    #[serde(rename = "connectTimeoutMs")]
+    pub connect_timeout_ms: u64,
+    #[serde(rename = "followRedirects")]
+    pub follow_redirects: bool,
+    #[serde(rename = "maxRedirects")]
+    pub max_redirects: usize,
+    /// Sent with every request; request headers with the same name win
+    #[serde(rename = "defaultHeaders")]
+    pub default_headers: Vec<KeyValue>,
+    #[serde(rename = "userAgent")]
+    pub user_agent: String,
+    #[serde(rename = "httpVersion")]
+    pub http_version: HttpVersion,
+    /// Advertise and transparently decode gzip, brotli and deflate bodies
+    pub decompress: bool,
+    /// TLS options per host; the first entry matching a request's host applies
+    pub tls: Vec<TlsHostSettings>,
+    pub proxy: ProxySettings,
+}
+
+/// Where sends get their proxy from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    /// Always connect directly
+    Off,
+    /// `http_proxy`, `https_proxy`, `all_proxy` and `no_proxy` from the environment
+    #[default]
+    System,
+    /// The proxy configured here
+    Manual,
+}
+
+/// Proxy used for sends; the URL and credentials only apply in manual mode
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxySettings {
+    pub mode: ProxyMode,
+    /// `http://`, `https://`, `socks5://` or `socks5h://` (hostnames resolved by the proxy)
+    pub url: String,
+    pub username: String,
+    pub password: String,
+    /// Comma separated hosts (covering their subdomains), IPs or CIDR ranges
+    /// reached directly; `*` for all
+    #[serde(rename = "noProxy")]
+    pub no_proxy: String,
+}
+
+/// Oldest TLS version a client will negotiate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TlsVersion {
+    #[default]
+    #[serde(rename = "1.2")]
+    Tls12,
+    #[serde(rename = "1.3")]
+    Tls13,
+}
+
+/// Certificate and key presented when a server asks for client authentication
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "format", rename_all = "lowercase")]
+pub enum ClientIdentity {
+    /// PEM certificate chain; the key may sit in the same file when `keyPath` is empty
+    Pem {
+        #[serde(rename = "certPath")]
+        cert_path: String,
+        #[serde(rename = "keyPath", default)]
+        key_path: String,
+    },
+    Pkcs12 {
+        path: String,
+        #[serde(default)]
+        password: String,
+    },
+}
+
+/// TLS options for the hosts matching `host`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsHostSettings {
+    /// `example.com`, `example.com:8443`, or `*.example.com` for any subdomain
+    pub host: String,
+    #[serde(rename = "clientIdentity", skip_serializing_if = "Option::is_none")]
+    pub client_identity: Option<ClientIdentity>,
+    /// PEM files with CA certificates trusted on top of the built-in roots
+    #[serde(rename = "caCertificates")]
+    pub ca_certificates: Vec<String>,
+    /// Accept any server certificate. Insecure, meant for local test servers.
+    #[serde(rename = "skipVerification")]
+    pub skip_verification: bool,
+    #[serde(rename = "minVersion")]
+    pub min_version: TlsVersion,
+}
+
+impl TlsHostSettings {
+    /// Whether the host pattern covers `host`. A pattern with a port only
+    /// matches that port; `port` is None when it isn't known (during the handshake).
+    pub fn matches(&self, host: &str, port: Option<u16>) -> bool {
+        let pattern = self.host.trim().to_ascii_lowercase();
+        let (pattern_host, pattern_port) = match pattern.rsplit_once(':') {
+            Some((h, p)) if !h.ends_with(':') => match p.parse::<u16>() {
+                Ok(p) => (h, Some(p)),
+                Err(_) => (pattern.as_str(), None),
+            },
+            _ => (pattern.as_str(), None),
+        };
+        if let (Some(expected), Some(actual)) = (pattern_port, port) {
+            if expected != actual {
+                return false;
+            }
+        }
+        let pattern_host = pattern_host.trim_start_matches('[').trim_end_matches(']');
+        let host = host.trim_start_matches('[').trim_end_matches(']').to_ascii_lowercase();
+        match pattern_host.strip_prefix("*.") {
+            Some(domain) => host.ends_with(&format!(".{}", domain)),
+            None => !pattern_host.is_empty() && host == pattern_host,
+        }
+    }
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 30_000,
+            connect_timeout_ms: 10_000,
+            follow_redirects: true,
+            max_redirects: 10,
+            default_headers: vec![
+                KeyValue {
+                    enabled: true,
+                    key: "Accept".to_string(),
+                    value: "application/json, text/plain, */*".to_string(),
+                },
+                KeyValue {
+                    enabled: true,
+                    key: "Accept-Language".to_string(),
+                    value: "en-US,en;q=0.9".to_string(),
+                },
+            ],
+            user_agent: format!("Prism/{}", env!("CARGO_PKG_VERSION")),
+            http_version: HttpVersion::Auto,
+            decompress: true,
+            tls: Vec::new(),
+            proxy: ProxySettings::default(),
+        }
+    }
+}
+
+/// Per-request overrides; unset fields fall back to the global settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpSettingsOverride {
+    #[serde(rename = "timeoutMs", default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(rename = "connectTimeoutMs", default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout_ms: Option<u64>,
+    #[serde(rename = "followRedirects", default, skip_serializing_if = "Option::is_none")]
+    pub follow_redirects: Option<bool>,
+    #[serde(rename = "maxRedirects", default, skip_serializing_if = "Option::is_none")]
+    pub max_redirects: Option<usize>,
+    /// Replaces the global default headers when set
+    #[serde(rename = "defaultHeaders", default, skip_serializing_if = "Option::is_none")]
+    pub default_headers: Option<Vec<KeyValue>>,
+    #[serde(rename = "userAgent", default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    #[serde(rename = "httpVersion", default, skip_serializing_if = "Option::is_none")]
+    pub http_version: Option<HttpVersion>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decompress: Option<bool>,
+    /// A different proxy for this request, or mode `off` to bypass it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxySettings>,
+}
+
+impl HttpSettings {
+    /// Settings for one send: these globals with the request's overrides applied
+    pub fn with_override(&self, overrides: Option<&HttpSettingsOverride>) -> HttpSettings {
+        let mut merged = self.clone();
+        let Some(o) = overrides else {
+            return merged;
+        };
+        if let Some(v) = o.timeout_ms {
+            merged.timeout_ms = v;
+        }
+        if let Some(v) = o.connect_timeout_ms {
+            merged.connect_timeout_ms = v;
+        }
+        if let Some(v) = o.follow_redirects {
+            merged.follow_redirects = v;
+        }
+        if let Some(v) = o.max_redirects {
+            merged.max_redirects = v;
+        }
+        if let Some(v) = &o.default_headers {
+            merged.default_headers = v.clone();
+        }
+        if let Some(v) = &o.user_agent {
+            merged.user_agent = v.clone();
+        }
+        if let Some(v) = o.http_version {
+            merged.http_version = v;
+        }
+        if let Some(v) = o.decompress {
+            merged.decompress = v;
+        }
+        if let Some(v) = &o.proxy {
+            merged.proxy = v.clone();
+        }
+        merged
+    }
+
+    /// These settings with `tls` narrowed to the entry for the URL's host, so
+    /// clients are pooled per TLS configuration rather than per settings list
+    pub fn for_url(&self, url: &url::Url) -> HttpSettings {
+        let host = url.host_str().unwrap_or_default();
+        let entry = self.tls.iter().find(|t| t.matches(host, url.port_or_known_default()));
+        HttpSettings {
+            tls: entry.cloned().into_iter().collect(),
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_override_merges_set_fields_only() {
+        let globals = HttpSettings::default();
+        let overrides = HttpSettingsOverride {
+            timeout_ms: Some(0),
+            follow_redirects: Some(false),
+            http_version: Some(HttpVersion::Http1),
+            proxy: Some(ProxySettings { mode: ProxyMode::Off, ..Default::default() }),
+            ..Default::default()
+        };
+        let merged = globals.with_override(Some(&overrides));
+        assert_eq!(merged.proxy.mode, ProxyMode::Off);
+        assert_eq!(merged.timeout_ms, 0);
+        assert!(!merged.follow_redirects);
+        assert_eq!(merged.http_version, HttpVersion::Http1);
+        assert_eq!(merged.max_redirects, globals.max_redirects);
+        assert_eq!(merged.user_agent, globals.user_agent);
+        assert_eq!(globals.with_override(None), globals);
+    }
+
+    #[test]
+    fn test_partial_settings_json_uses_defaults() {
+        let parsed: HttpSettings = serde_json::from_str(r#"{"timeoutMs": 5000, "httpVersion": "http2"}"#).unwrap();
+        assert_eq!(parsed.timeout_ms, 5000);
+        assert_eq!(parsed.http_version, HttpVersion::Http2);
+        assert!(parsed.decompress);
+        assert_eq!(parsed.default_headers.len(), 2);
+        assert!(parsed.tls.is_empty());
+        assert_eq!(parsed.proxy.mode, ProxyMode::System);
+    }
+
+    #[test]
+    fn test_tls_settings_match_by_host_and_port() {
+        let entry = |host: &str| TlsHostSettings { host: host.to_string(), ..Default::default() };
+        assert!(entry("API.example.com").matches("api.example.com", Some(443)));
+        assert!(!entry("api.example.com").matches("example.com", Some(443)));
+        assert!(entry("*.example.com").matches("a.b.example.com", None));
+        assert!(!entry("*.example.com").matches("example.com", None));
+        assert!(entry("localhost:8443").matches("localhost", Some(8443)));
+        assert!(!entry("localhost:8443").matches("localhost", Some(443)));
+        assert!(entry("localhost:8443").matches("localhost", None));
+        assert!(entry("[::1]:8443").matches("::1", Some(8443)));
+        assert!(!entry("").matches("", None));
+
+        let settings = HttpSettings {
+            tls: vec![entry("*.internal"), entry("localhost:8443")],
+            ..Default::default()
+        };
+        let url = |u: &str| url::Url::parse(u).unwrap();
+        assert_eq!(settings.for_url(&url("https://localhost:8443/x")).tls, vec![entry("localhost:8443")]);
+        assert_eq!(settings.for_url(&url("https://db.internal/")).tls, vec![entry("*.internal")]);
+        assert!(settings.for_url(&url("https://localhost/")).tls.is_empty());
+
+        let parsed: TlsHostSettings = serde_json::from_str(
+            r#"{"host": "localhost", "minVersion": "1.3", "clientIdentity": {"format": "pkcs12", "path": "/id.p12"}}"#,
+        ).unwrap();
+        assert_eq!(parsed.min_version, TlsVersion::Tls13);
+        assert_eq!(parsed.client_identity, Some(ClientIdentity::Pkcs12 { path: "/id.p12".to_string(), password: String::new() }));
+    }
+}
@@ -0,0 +1,99 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::Mutex;
+use super::models::HttpSettings;
+
+const HTTP_SETTINGS_KEY: &str = "http";
+
+/// App settings stored as JSON documents keyed by section
+pub struct SettingsStore {
+    conn: Mutex<Connection>,
+}
+
+impl SettingsStore {
+    pub fn new(db_path: &str) -> Result<Self, String> {
+        let conn = Connection::open(db_path)
+            .map_err(|e| format!("Failed to open settings database: {}", e))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value_json TEXT NOT NULL
+            )",
+            [],
+        )
+        .map_err(db_err)?;
+
+        Ok(SettingsStore {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Saved HTTP client settings, or the defaults if none were saved
+    pub fn get_http_settings(&self) -> Result<HttpSettings, String> {
+        let conn = self.conn.lock().unwrap();
+        let json: Option<String> = conn
+            .query_row(
+                "SELECT value_json FROM settings WHERE key = ?1",
+                params![HTTP_SETTINGS_KEY],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_err)?;
+
+        match json {
+            // Missing fields (e.g. from an older version) take their defaults
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| format!("Failed to parse HTTP settings: {}", e)),
+            None => Ok(HttpSettings::default()),
+        }
+    }
+
+    pub fn set_http_settings(&self, settings: &HttpSettings) -> Result<(), String> {
+        let json = serde_json::to_string(settings)
+            .map_err(|e| format!("Failed to serialize HTTP settings: {}", e))?;
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value_json) VALUES (?1, ?2)",
+            params![HTTP_SETTINGS_KEY, json],
+        )
+        .map_err(db_err)?;
+        Ok(())
+    }
+
+    /// Drops the saved HTTP settings so the defaults apply again
+    pub fn reset_http_settings(&self) -> Result<HttpSettings, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM settings WHERE key = ?1", params![HTTP_SETTINGS_KEY])
+            .map_err(db_err)?;
+        Ok(HttpSettings::default())
+    }
+}
+
+fn db_err(e: rusqlite::Error) -> String {
+    format!("Settings database error: {}", e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::models::HttpVersion;
+
+    #[test]
+    fn test_http_settings_round_trip() {
+        let store = SettingsStore::new(":memory:").unwrap();
+        assert_eq!(store.get_http_settings().unwrap(), HttpSettings::default());
+
+        let settings = HttpSettings {
+            timeout_ms: 1234,
+            follow_redirects: false,
+            http_version: HttpVersion::Http1,
+            user_agent: "ci-bot".to_string(),
+            ..Default::default()
+        };
+        store.set_http_settings(&settings).unwrap();
+        assert_eq!(store.get_http_settings().unwrap(), settings);
+
+        store.reset_http_settings().unwrap();
+        assert_eq!(store.get_http_settings().unwrap(), HttpSettings::default());
+    }
+}
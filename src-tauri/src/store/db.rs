@@ -20,7 +20,9 @@ impl Database {
                 time_ms INTEGER NOT NULL,
                 timestamp INTEGER NOT NULL,
                 request_json TEXT,
-                response_json TEXT
+                response_json TEXT,
+                dynamic_json TEXT,
+                transcript_json TEXT
             )",
             [],
         )?;
@@ -28,6 +30,8 @@ impl Database {
         // Migration: Add request_json column if it doesn't exist
         let _ = conn.execute("ALTER TABLE history ADD COLUMN request_json TEXT", []);
         let _ = conn.execute("ALTER TABLE history ADD COLUMN response_json TEXT", []);
+        let _ = conn.execute("ALTER TABLE history ADD COLUMN dynamic_json TEXT", []);
+        let _ = conn.execute("ALTER TABLE history ADD COLUMN transcript_json TEXT", []);
 
         // Create index on timestamp for faster queries
         conn.execute(
@@ -44,9 +48,11 @@ impl Database {
         let conn = self.conn.lock().unwrap();
         let request_json = serde_json::to_string(&entry.request).unwrap_or_default();
         let response_json = serde_json::to_string(&entry.response).unwrap_or_default();
+        let dynamic_json = serde_json::to_string(&entry.dynamic_values).unwrap_or_default();
+        let transcript_json = serde_json::to_string(&entry.transcript).unwrap_or_default();
         conn.execute(
-            "INSERT INTO history (id, method, url, status, time_ms, timestamp, request_json, response_json)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO history (id, method, url, status, time_ms, timestamp, request_json, response_json, dynamic_json, transcript_json)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             (
                 &entry.id,
                 &entry.method,
@@ -56,6 +62,8 @@ impl Database {
                 entry.timestamp,
                 &request_json,
                 &response_json,
+                &dynamic_json,
+                &transcript_json,
             ),
         )?;
         Ok(())
@@ -64,7 +72,7 @@ impl Database {
     pub fn get_all(&self) -> SqlResult<Vec<HistoryEntry>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, method, url, status, time_ms, timestamp, request_json, response_json 
+            "SELECT id, method, url, status, time_ms, timestamp, request_json, response_json, dynamic_json, transcript_json 
              FROM history 
              ORDER BY timestamp DESC 
              LIMIT 50"
@@ -73,16 +81,15 @@ impl Database {
         let entries = stmt.query_map([], |row| {
             let request_json: String = row.get(6).unwrap_or_default();
             let response_json: String = row.get(7).unwrap_or_default();
+            let dynamic_json: String = row.get(8).unwrap_or_default();
+            let transcript_json: String = row.get(9).unwrap_or_default();
             
             let request = serde_json::from_str(&request_json).unwrap_or_else(|_| {
                 // Fallback for old entries without request_json
                 crate::models::ApiRequest {
                     method: row.get(1).unwrap_or_else(|_| "GET".to_string()),
                     url: row.get(2).unwrap_or_else(|_| "".to_string()),
-                    params: Vec::new(),
-                    headers: Vec::new(),
-                    body: None,
-                    auth: crate::models::AuthPayload::default(),
+                    ..Default::default()
                 }
             });
 
@@ -93,8 +100,7 @@ impl Database {
                     status_text: "OK".to_string(),
                     time: row.get::<_, i64>(4).unwrap_or(0) as u128,
                     size: "0".to_string(),
-                    headers: std::collections::HashMap::new(),
-                    body: serde_json::Value::Null,
+                    ..Default::default()
                 }
             });
 
@@ -107,6 +113,8 @@ impl Database {
                 timestamp: row.get(5)?,
                 request,
                 response,
+                dynamic_values: serde_json::from_str(&dynamic_json).unwrap_or_default(),
+                transcript: serde_json::from_str(&transcript_json).unwrap_or_default(),
             })
         })?;
 
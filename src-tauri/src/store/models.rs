@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
-use crate::models::{ApiRequest, ApiResponse};
+use std::collections::BTreeMap;
+use crate::models::{ApiRequest, ApiResponse, TranscriptEntry};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
@@ -12,6 +13,12 @@ pub struct HistoryEntry {
     pub timestamp: i64,
     pub request: ApiRequest,
     pub response: ApiResponse,
+    /// Values generated for `{{$...}}` built-ins when this request was sent
+    #[serde(rename = "dynamicValues", default)]
+    pub dynamic_values: BTreeMap<String, String>,
+    /// Messages of a WebSocket session or streamed response; empty for plain requests
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transcript: Vec<TranscriptEntry>,
 }
 
 impl HistoryEntry {
@@ -28,6 +35,8 @@ impl HistoryEntry {
             timestamp,
             request,
             response,
+            dynamic_values: BTreeMap::new(),
+            transcript: Vec::new(),
         }
     }
 }
@@ -0,0 +1,567 @@
+use rusqlite::{Connection, OptionalExtension, Result as SqlResult};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use super::models::{CollectionNode, NodeKind};
+use crate::models::ApiRequest;
+
+/// SQLite-backed collection tree (collections > folders > requests)
+pub struct CollectionStore {
+    conn: Mutex<Connection>,
+}
+
+/// Flat row as stored in the `collection_nodes` table
+struct NodeRow {
+    id: String,
+    parent_id: Option<String>,
+    kind: NodeKind,
+    name: String,
+    position: i64,
+    request: Option<ApiRequest>,
+}
+
+impl CollectionStore {
+    pub fn new(db_path: &str) -> Result<Self, String> {
+        let conn = Connection::open(db_path)
+            .map_err(|e| format!("Failed to open collections database: {}", e))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS collection_nodes (
+                id TEXT PRIMARY KEY,
+                parent_id TEXT,
+                kind TEXT NOT NULL,
+                name TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                request_json TEXT,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(db_err)?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_collection_nodes_parent ON collection_nodes(parent_id, position)",
+            [],
+        )
+        .map_err(db_err)?;
+
+        Ok(CollectionStore {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// All collections with their full trees
+    pub fn list_collections(&self) -> Result<Vec<CollectionNode>, String> {
+        let conn = self.conn.lock().unwrap();
+        let rows = load_rows(&conn).map_err(db_err)?;
+        Ok(build_tree(rows, None))
+    }
+
+    /// A single node with its subtree
+    pub fn get_node(&self, id: &str) -> Result<CollectionNode, String> {
+        let conn = self.conn.lock().unwrap();
+        let rows = load_rows(&conn).map_err(db_err)?;
+        let parent_id = rows
+            .iter()
+            .find(|row| row.id == id)
+            .map(|row| row.parent_id.clone())
+            .ok_or_else(|| format!("Collection item not found: {}", id))?;
+
+        build_tree(rows, parent_id.as_deref())
+            .into_iter()
+            .find(|node| node.id == id)
+            .ok_or_else(|| format!("Collection item not found: {}", id))
+    }
+
+    pub fn create_collection(&self, name: &str) -> Result<CollectionNode, String> {
+        self.insert(None, NodeKind::Collection, name, None)
+    }
+
+    pub fn create_folder(&self, parent_id: &str, name: &str) -> Result<CollectionNode, String> {
+        self.insert(Some(parent_id), NodeKind::Folder, name, None)
+    }
+
+    pub fn create_request(&self, parent_id: &str, name: &str, request: ApiRequest) -> Result<CollectionNode, String> {
+        self.insert(Some(parent_id), NodeKind::Request, name, Some(request))
+    }
+
+    pub fn rename_node(&self, id: &str, name: &str) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        let updated = conn
+            .execute(
+                "UPDATE collection_nodes SET name = ?1, updated_at = ?2 WHERE id = ?3",
+                (name, now_millis(), id),
+            )
+            .map_err(db_err)?;
+        if updated == 0 {
+            return Err(format!("Collection item not found: {}", id));
+        }
+        Ok(())
+    }
+
+    pub fn update_request(&self, id: &str, request: &ApiRequest) -> Result<(), String> {
+        let request_json = serde_json::to_string(request)
+            .map_err(|e| format!("Failed to serialize request: {}", e))?;
+
+        let conn = self.conn.lock().unwrap();
+        let updated = conn
+            .execute(
+                "UPDATE collection_nodes SET request_json = ?1, updated_at = ?2 WHERE id = ?3 AND kind = 'request'",
+                (&request_json, now_millis(), id),
+            )
+            .map_err(db_err)?;
+        if updated == 0 {
+            return Err(format!("Request not found: {}", id));
+        }
+        Ok(())
+    }
+
+    /// Deletes a node and everything below it
+    pub fn delete_node(&self, id: &str) -> Result<(), String> {
+        let mut conn = self.conn.lock().unwrap();
+        let rows = load_rows(&conn).map_err(db_err)?;
+        let node = rows
+            .iter()
+            .find(|row| row.id == id)
+            .ok_or_else(|| format!("Collection item not found: {}", id))?;
+        let parent_id = node.parent_id.clone();
+
+        let mut ids = vec![id.to_string()];
+        collect_descendants(&rows, id, &mut ids);
+
+        let tx = conn.transaction().map_err(db_err)?;
+        for node_id in &ids {
+            tx.execute("DELETE FROM collection_nodes WHERE id = ?1", [node_id])
+                .map_err(db_err)?;
+        }
+        renumber(&tx, parent_id.as_deref(), None).map_err(db_err)?;
+        tx.commit().map_err(db_err)
+    }
+
+    /// Moves a folder or request under `parent_id` at `position` (appended if None)
+    pub fn move_node(&self, id: &str, parent_id: &str, position: Option<usize>) -> Result<(), String> {
+        let mut conn = self.conn.lock().unwrap();
+        let rows = load_rows(&conn).map_err(db_err)?;
+
+        let node = rows
+            .iter()
+            .find(|row| row.id == id)
+            .ok_or_else(|| format!("Collection item not found: {}", id))?;
+        if node.kind == NodeKind::Collection {
+            return Err("Collections cannot be moved into another item".to_string());
+        }
+        validate_parent(&rows, parent_id)?;
+
+        let mut descendants = vec![id.to_string()];
+        collect_descendants(&rows, id, &mut descendants);
+        if descendants.iter().any(|d| d == parent_id) {
+            return Err("Cannot move an item into itself or one of its children".to_string());
+        }
+
+        let old_parent = node.parent_id.clone();
+
+        let tx = conn.transaction().map_err(db_err)?;
+        tx.execute(
+            "UPDATE collection_nodes SET parent_id = ?1, updated_at = ?2 WHERE id = ?3",
+            (parent_id, now_millis(), id),
+        )
+        .map_err(db_err)?;
+        if old_parent.as_deref() != Some(parent_id) {
+            renumber(&tx, old_parent.as_deref(), None).map_err(db_err)?;
+        }
+        renumber(&tx, Some(parent_id), Some((id, position))).map_err(db_err)?;
+        tx.commit().map_err(db_err)
+    }
+
+    /// Reorders a node among its siblings
+    pub fn reorder_node(&self, id: &str, position: usize) -> Result<(), String> {
+        let mut conn = self.conn.lock().unwrap();
+        let parent_id: Option<String> = conn
+            .query_row("SELECT parent_id FROM collection_nodes WHERE id = ?1", [id], |row| row.get(0))
+            .optional()
+            .map_err(db_err)?
+            .ok_or_else(|| format!("Collection item not found: {}", id))?;
+
+        let tx = conn.transaction().map_err(db_err)?;
+        renumber(&tx, parent_id.as_deref(), Some((id, Some(position)))).map_err(db_err)?;
+        tx.commit().map_err(db_err)
+    }
+
+    /// Deep-copies a node (and its subtree) right after the original
+    pub fn duplicate_node(&self, id: &str) -> Result<CollectionNode, String> {
+        let new_id = {
+            let mut conn = self.conn.lock().unwrap();
+            let rows = load_rows(&conn).map_err(db_err)?;
+            let node = rows
+                .iter()
+                .find(|row| row.id == id)
+                .ok_or_else(|| format!("Collection item not found: {}", id))?;
+            let parent_id = node.parent_id.clone();
+            let position = node.position;
+
+            let children_of = index_children(&rows);
+            let by_id: HashMap<&str, &NodeRow> = rows.iter().map(|row| (row.id.as_str(), row)).collect();
+
+            let tx = conn.transaction().map_err(db_err)?;
+            let new_id = copy_subtree(&tx, &by_id, &children_of, id, parent_id.as_deref(), true)
+                .map_err(db_err)?;
+            // Place the copy right after the original
+            renumber(&tx, parent_id.as_deref(), Some((&new_id, Some(position as usize + 1))))
+                .map_err(db_err)?;
+            tx.commit().map_err(db_err)?;
+            new_id
+        };
+
+        self.get_node(&new_id)
+    }
+
+    /// Persists an imported tree (ids are ignored and regenerated).
+    /// Top-level collections are added as new collections; anything else goes under `parent_id`.
+    pub fn import_tree(&self, tree: &CollectionNode, parent_id: Option<&str>) -> Result<CollectionNode, String> {
+        if tree.kind == NodeKind::Collection && parent_id.is_some() {
+            return Err("Collections can only be imported at the top level".to_string());
+        }
+        if tree.kind != NodeKind::Collection && parent_id.is_none() {
+            return Err("Folders and requests must be imported into a collection".to_string());
+        }
+
+        let new_id = {
+            let mut conn = self.conn.lock().unwrap();
+            if let Some(parent) = parent_id {
+                validate_parent(&load_rows(&conn).map_err(db_err)?, parent)?;
+            }
+
+            let tx = conn.transaction().map_err(db_err)?;
+            let new_id = insert_subtree(&tx, tree, parent_id).map_err(db_err)?;
+            renumber(&tx, parent_id, Some((&new_id, None))).map_err(db_err)?;
+            tx.commit().map_err(db_err)?;
+            new_id
+        };
+
+        self.get_node(&new_id)
+    }
+
+    fn insert(
+        &self,
+        parent_id: Option<&str>,
+        kind: NodeKind,
+        name: &str,
+        request: Option<ApiRequest>,
+    ) -> Result<CollectionNode, String> {
+        let request_json = match &request {
+            Some(req) => Some(
+                serde_json::to_string(req).map_err(|e| format!("Failed to serialize request: {}", e))?,
+            ),
+            None => None,
+        };
+
+        let conn = self.conn.lock().unwrap();
+        if let Some(parent) = parent_id {
+            validate_parent(&load_rows(&conn).map_err(db_err)?, parent)?;
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let position: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM collection_nodes WHERE parent_id IS ?1",
+                [parent_id],
+                |row| row.get(0),
+            )
+            .map_err(db_err)?;
+
+        conn.execute(
+            "INSERT INTO collection_nodes (id, parent_id, kind, name, position, request_json, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            (&id, parent_id, kind.as_str(), name, position, &request_json, now_millis()),
+        )
+        .map_err(db_err)?;
+
+        Ok(CollectionNode {
+            id,
+            parent_id: parent_id.map(|p| p.to_string()),
+            kind,
+            name: name.to_string(),
+            position,
+            request,
+            children: Vec::new(),
+        })
+    }
+}
+
+fn db_err(e: rusqlite::Error) -> String {
+    format!("Collections database error: {}", e)
+}
+
+fn now_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn load_rows(conn: &Connection) -> SqlResult<Vec<NodeRow>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, parent_id, kind, name, position, request_json
+         FROM collection_nodes
+         ORDER BY position, name"
+    )?;
+
+    let rows = stmt.query_map([], |row| {
+        let kind: String = row.get(2)?;
+        let request_json: Option<String> = row.get(5)?;
+        Ok(NodeRow {
+            id: row.get(0)?,
+            parent_id: row.get(1)?,
+            kind: NodeKind::parse(&kind).unwrap_or(NodeKind::Request),
+            name: row.get(3)?,
+            position: row.get(4)?,
+            request: request_json
+                .map(|json| serde_json::from_str(&json))
+                .transpose()
+                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(e)))?,
+        })
+    })?;
+
+    let mut result = Vec::new();
+    for row in rows {
+        result.push(row?);
+    }
+    Ok(result)
+}
+
+fn index_children(rows: &[NodeRow]) -> HashMap<Option<&str>, Vec<&NodeRow>> {
+    let mut children: HashMap<Option<&str>, Vec<&NodeRow>> = HashMap::new();
+    for row in rows {
+        children.entry(row.parent_id.as_deref()).or_default().push(row);
+    }
+    children
+}
+
+/// Builds the subtrees whose parent is `root`
+fn build_tree(rows: Vec<NodeRow>, root: Option<&str>) -> Vec<CollectionNode> {
+    let children = index_children(&rows);
+
+    fn build(children: &HashMap<Option<&str>, Vec<&NodeRow>>, parent: Option<&str>) -> Vec<CollectionNode> {
+        children
+            .get(&parent)
+            .map(|rows| {
+                rows.iter()
+                    .map(|row| CollectionNode {
+                        id: row.id.clone(),
+                        parent_id: row.parent_id.clone(),
+                        kind: row.kind,
+                        name: row.name.clone(),
+                        position: row.position,
+                        request: row.request.clone(),
+                        children: build(children, Some(&row.id)),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    build(&children, root)
+}
+
+fn collect_descendants(rows: &[NodeRow], id: &str, out: &mut Vec<String>) {
+    for row in rows.iter().filter(|row| row.parent_id.as_deref() == Some(id)) {
+        out.push(row.id.clone());
+        collect_descendants(rows, &row.id, out);
+    }
+}
+
+fn validate_parent(rows: &[NodeRow], parent_id: &str) -> Result<(), String> {
+    let parent = rows
+        .iter()
+        .find(|row| row.id == parent_id)
+        .ok_or_else(|| format!("Parent not found: {}", parent_id))?;
+    if !parent.kind.is_container() {
+        return Err("Items can only be added to a collection or folder".to_string());
+    }
+    Ok(())
+}
+
+/// Rewrites sibling positions as 0..n, optionally placing `target` at a given index
+fn renumber(conn: &Connection, parent_id: Option<&str>, target: Option<(&str, Option<usize>)>) -> SqlResult<()> {
+    let mut ids: Vec<String> = {
+        let mut stmt = conn.prepare(
+            "SELECT id FROM collection_nodes WHERE parent_id IS ?1 ORDER BY position, name"
+        )?;
+        let rows = stmt.query_map([parent_id], |row| row.get(0))?;
+        rows.collect::<SqlResult<_>>()?
+    };
+
+    if let Some((target_id, position)) = target {
+        ids.retain(|id| id != target_id);
+        let index = position.unwrap_or(ids.len()).min(ids.len());
+        ids.insert(index, target_id.to_string());
+    }
+
+    for (index, id) in ids.iter().enumerate() {
+        conn.execute(
+            "UPDATE collection_nodes SET position = ?1 WHERE id = ?2",
+            (index as i64, id),
+        )?;
+    }
+    Ok(())
+}
+
+fn insert_subtree(conn: &Connection, node: &CollectionNode, parent_id: Option<&str>) -> SqlResult<String> {
+    let new_id = uuid::Uuid::new_v4().to_string();
+    let request_json = node.request.as_ref().and_then(|req| serde_json::to_string(req).ok());
+
+    conn.execute(
+        "INSERT INTO collection_nodes (id, parent_id, kind, name, position, request_json, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        (&new_id, parent_id, node.kind.as_str(), &node.name, node.position, &request_json, now_millis()),
+    )?;
+
+    for (index, child) in node.children.iter().enumerate() {
+        let child_id = insert_subtree(conn, child, Some(&new_id))?;
+        conn.execute(
+            "UPDATE collection_nodes SET position = ?1 WHERE id = ?2",
+            (index as i64, &child_id),
+        )?;
+    }
+    Ok(new_id)
+}
+
+fn copy_subtree(
+    conn: &Connection,
+    by_id: &HashMap<&str, &NodeRow>,
+    children_of: &HashMap<Option<&str>, Vec<&NodeRow>>,
+    id: &str,
+    parent_id: Option<&str>,
+    is_root: bool,
+) -> SqlResult<String> {
+    let row = by_id[id];
+    let new_id = uuid::Uuid::new_v4().to_string();
+    let name = if is_root { format!("{} Copy", row.name) } else { row.name.clone() };
+    let request_json = row.request.as_ref().and_then(|req| serde_json::to_string(req).ok());
+
+    conn.execute(
+        "INSERT INTO collection_nodes (id, parent_id, kind, name, position, request_json, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        (&new_id, parent_id, row.kind.as_str(), &name, row.position, &request_json, now_millis()),
+    )?;
+
+    if let Some(children) = children_of.get(&Some(id)) {
+        for child in children {
+            copy_subtree(conn, by_id, children_of, &child.id, Some(&new_id), false)?;
+        }
+    }
+    Ok(new_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_and_list_tree() {
+        let store = CollectionStore::new(":memory:").unwrap();
+        let api = store.create_collection("API").unwrap();
+        let users = store.create_folder(&api.id, "Users").unwrap();
+        store.create_request(&users.id, "List users", ApiRequest::get("https://example.com/users")).unwrap();
+        store.create_request(&api.id, "Health", ApiRequest::get("https://example.com/health")).unwrap();
+
+        let collections = store.list_collections().unwrap();
+        assert_eq!(collections.len(), 1);
+        assert_eq!(collections[0].children.len(), 2);
+        assert_eq!(collections[0].children[0].name, "Users");
+        assert_eq!(collections[0].children[0].children[0].name, "List users");
+
+        let names: Vec<String> = collections[0].flatten_requests().into_iter().map(|r| r.name).collect();
+        assert_eq!(names, vec!["List users", "Health"]);
+    }
+
+    #[test]
+    fn test_requests_cannot_have_children() {
+        let store = CollectionStore::new(":memory:").unwrap();
+        let api = store.create_collection("API").unwrap();
+        let req = store.create_request(&api.id, "Health", ApiRequest::get("https://example.com")).unwrap();
+        assert!(store.create_folder(&req.id, "Nope").is_err());
+    }
+
+    #[test]
+    fn test_move_and_reorder() {
+        let store = CollectionStore::new(":memory:").unwrap();
+        let api = store.create_collection("API").unwrap();
+        let a = store.create_folder(&api.id, "A").unwrap();
+        let b = store.create_folder(&api.id, "B").unwrap();
+        let r1 = store.create_request(&a.id, "One", ApiRequest::get("https://example.com/1")).unwrap();
+        let r2 = store.create_request(&a.id, "Two", ApiRequest::get("https://example.com/2")).unwrap();
+
+        store.move_node(&r2.id, &b.id, None).unwrap();
+        store.move_node(&r1.id, &b.id, Some(0)).unwrap();
+        let folder_b = store.get_node(&b.id).unwrap();
+        let names: Vec<&str> = folder_b.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["One", "Two"]);
+        assert!(store.get_node(&a.id).unwrap().children.is_empty());
+
+        store.reorder_node(&b.id, 0).unwrap();
+        assert_eq!(store.list_collections().unwrap()[0].children[0].name, "B");
+
+        // No moving a folder into its own subtree
+        let inner = store.create_folder(&b.id, "Inner").unwrap();
+        assert!(store.move_node(&b.id, &inner.id, None).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_and_delete() {
+        let store = CollectionStore::new(":memory:").unwrap();
+        let api = store.create_collection("API").unwrap();
+        let folder = store.create_folder(&api.id, "Users").unwrap();
+        store.create_folder(&api.id, "Other").unwrap();
+        store.create_request(&folder.id, "List", ApiRequest::get("https://example.com/users")).unwrap();
+
+        let copy = store.duplicate_node(&folder.id).unwrap();
+        assert_eq!(copy.name, "Users Copy");
+        assert_eq!(copy.position, 1);
+        assert_eq!(copy.children.len(), 1);
+        assert_ne!(copy.children[0].id, folder.id);
+
+        store.delete_node(&folder.id).unwrap();
+        let tree = store.get_node(&api.id).unwrap();
+        let names: Vec<&str> = tree.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Users Copy", "Other"]);
+        assert_eq!(tree.children[0].position, 0);
+    }
+
+    #[test]
+    fn test_unreadable_request_is_an_error() {
+        let store = CollectionStore::new(":memory:").unwrap();
+        let api = store.create_collection("API").unwrap();
+        let req = store.create_request(&api.id, "Health", ApiRequest::get("https://example.com")).unwrap();
+        store.conn.lock().unwrap()
+            .execute("UPDATE collection_nodes SET request_json = '{' WHERE id = ?1", [&req.id])
+            .unwrap();
+        assert!(store.list_collections().is_err());
+    }
+
+    #[test]
+    fn test_import_tree() {
+        let store = CollectionStore::new(":memory:").unwrap();
+        store.create_collection("Existing").unwrap();
+
+        let tree = CollectionNode {
+            id: String::new(),
+            parent_id: None,
+            kind: NodeKind::Collection,
+            name: "Imported".to_string(),
+            position: 0,
+            request: None,
+            children: vec![CollectionNode {
+                id: String::new(),
+                parent_id: None,
+                kind: NodeKind::Request,
+                name: "Health".to_string(),
+                position: 0,
+                request: Some(ApiRequest::get("https://example.com/health")),
+                children: vec![],
+            }],
+        };
+
+        let imported = store.import_tree(&tree, None).unwrap();
+        assert_eq!(imported.position, 1);
+        assert_eq!(imported.children.len(), 1);
+        assert_eq!(imported.children[0].parent_id.as_deref(), Some(imported.id.as_str()));
+        assert!(store.import_tree(&tree.children[0], None).is_err());
+    }
+}
@@ -0,0 +1,289 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use super::io::to_canonical_json;
+use super::models::{CollectionNode, NodeKind, SavedRequest};
+use crate::models::ApiRequest;
+
+/// Id of the workspace root node; other nodes use their '/'-separated relative path
+pub const ROOT_ID: &str = ".";
+
+/// Reads a workspace directory as a collection tree:
+/// subfolders become folders and `*.json` files become requests.
+pub fn load_workspace(root: &Path) -> Result<CollectionNode, String> {
+    if !root.is_dir() {
+        return Err(format!("Workspace folder not found: {}", root.display()));
+    }
+
+    let name = root
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("Workspace")
+        .to_string();
+
+    Ok(CollectionNode {
+        id: ROOT_ID.to_string(),
+        parent_id: None,
+        kind: NodeKind::Collection,
+        name,
+        position: 0,
+        request: None,
+        children: load_dir(root, root, ROOT_ID)?,
+    })
+}
+
+fn load_dir(root: &Path, dir: &Path, parent_id: &str) -> Result<Vec<CollectionNode>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read folder {}: {}", dir.display(), e))?;
+
+    let mut folders = Vec::new();
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+
+        // Skip hidden entries such as .git
+        if file_name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            folders.push((file_name, path));
+        } else if file_name.ends_with(".json") {
+            files.push((file_name, path));
+        }
+    }
+
+    // Folders first, then requests, each sorted by name so the tree is stable
+    folders.sort();
+    files.sort();
+
+    let mut nodes = Vec::new();
+    for (file_name, path) in folders {
+        let id = relative_id(root, &path);
+        let children = load_dir(root, &path, &id)?;
+        nodes.push(CollectionNode {
+            id,
+            parent_id: Some(parent_id.to_string()),
+            kind: NodeKind::Folder,
+            name: file_name,
+            position: nodes.len() as i64,
+            request: None,
+            children,
+        });
+    }
+
+    for (file_name, path) in files {
+        let saved = match read_saved_request(&path) {
+            Ok(saved) => saved,
+            Err(e) => {
+                // Not every JSON file in a repo is a request; skip it
+                eprintln!("Warning: Skipping {}: {}", file_name, e);
+                continue;
+            }
+        };
+        nodes.push(CollectionNode {
+            id: relative_id(root, &path),
+            parent_id: Some(parent_id.to_string()),
+            kind: NodeKind::Request,
+            name: saved.name,
+            position: nodes.len() as i64,
+            request: Some(saved.request),
+            children: Vec::new(),
+        });
+    }
+
+    Ok(nodes)
+}
+
+fn read_saved_request(path: &Path) -> Result<SavedRequest, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse JSON: {}", e))
+}
+
+fn relative_id(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Joins a relative id onto the workspace root, rejecting paths that escape it
+pub fn resolve_in_workspace(root: &Path, relative: &str) -> Result<PathBuf, String> {
+    if relative.is_empty() || relative == ROOT_ID {
+        return Ok(root.to_path_buf());
+    }
+
+    let relative_path = Path::new(relative);
+    if relative_path
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return Err(format!("Invalid workspace path: {}", relative));
+    }
+    Ok(root.join(relative_path))
+}
+
+/// Writes a request into the workspace. `path` is either a `.json` file or a
+/// folder, in which case the file name is derived from the request name.
+/// Returns the relative id of the written file.
+pub fn save_workspace_request(root: &Path, path: &str, name: &str, request: ApiRequest) -> Result<String, String> {
+    let relative = if path.ends_with(".json") {
+        path.to_string()
+    } else {
+        let file_name = format!("{}.json", slugify(name));
+        if path.is_empty() || path == ROOT_ID {
+            file_name
+        } else {
+            format!("{}/{}", path.trim_end_matches('/'), file_name)
+        }
+    };
+
+    let file_path = resolve_in_workspace(root, &relative)?;
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create folder: {}", e))?;
+    }
+
+    let saved = SavedRequest {
+        name: name.to_string(),
+        request,
+    };
+    fs::write(&file_path, to_canonical_json(&saved)?)
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+
+    Ok(relative)
+}
+
+pub fn create_workspace_folder(root: &Path, path: &str) -> Result<(), String> {
+    let dir = resolve_in_workspace(root, path)?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create folder: {}", e))
+}
+
+pub fn delete_workspace_item(root: &Path, path: &str) -> Result<(), String> {
+    let target = resolve_in_workspace(root, path)?;
+    if target == root {
+        return Err("Cannot delete the workspace root".to_string());
+    }
+    if target.is_dir() {
+        fs::remove_dir_all(&target)
+    } else {
+        fs::remove_file(&target)
+    }
+    .map_err(|e| format!("Failed to delete {}: {}", path, e))
+}
+
+/// File name for a request, e.g. "Get User #1" -> "get-user-1"
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for ch in name.chars() {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-').to_string();
+    if slug.is_empty() { "request".to_string() } else { slug }
+}
+
+/// Watches the workspace and calls `on_change` with the reloaded tree whenever
+/// a folder or request file changes (e.g. after a `git pull`).
+/// The watcher stops when the returned handle is dropped.
+pub fn watch_workspace<F>(root: &Path, on_change: F) -> Result<RecommendedWatcher, String>
+where
+    F: Fn(Result<CollectionNode, String>) + Send + 'static,
+{
+    let watch_root = root.to_path_buf();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        if event.kind.is_access() {
+            return;
+        }
+
+        // Ignore noise from hidden folders (.git) and non-request files
+        let relevant = event.paths.iter().any(|path| {
+            let rel = path.strip_prefix(&watch_root).unwrap_or(path);
+            let hidden = rel
+                .components()
+                .any(|c| c.as_os_str().to_string_lossy().starts_with('.'));
+            !hidden && (path.extension().map(|e| e == "json").unwrap_or(false) || path.extension().is_none())
+        });
+        if relevant {
+            on_change(load_workspace(&watch_root));
+        }
+    })
+    .map_err(|e| format!("Failed to create file watcher: {}", e))?;
+
+    watcher
+        .watch(root, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch workspace: {}", e))?;
+
+    Ok(watcher)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_workspace(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("prism-ws-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_directory_maps_to_tree() {
+        let root = temp_workspace("tree");
+        save_workspace_request(&root, "users", "List Users", ApiRequest::get("https://example.com/users")).unwrap();
+        save_workspace_request(&root, ".", "Health", ApiRequest::get("https://example.com/health")).unwrap();
+        fs::write(root.join("package.json"), "{\"name\": \"not-a-request\"}").unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+
+        let tree = load_workspace(&root).unwrap();
+        assert_eq!(tree.children.len(), 2);
+        assert_eq!(tree.children[0].kind, NodeKind::Folder);
+        assert_eq!(tree.children[0].id, "users");
+        assert_eq!(tree.children[0].children[0].id, "users/list-users.json");
+        assert_eq!(tree.children[0].children[0].name, "List Users");
+        assert_eq!(tree.children[1].name, "Health");
+
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_output_is_deterministic() {
+        let root = temp_workspace("stable");
+        let path = save_workspace_request(&root, "a.json", "A", ApiRequest::get("https://example.com")).unwrap();
+        let first = fs::read_to_string(root.join(&path)).unwrap();
+        save_workspace_request(&root, "a.json", "A", ApiRequest::get("https://example.com")).unwrap();
+        let second = fs::read_to_string(root.join(&path)).unwrap();
+
+        assert_eq!(first, second);
+        assert!(first.ends_with("}\n"));
+        // Keys are sorted
+        assert!(first.find("\"name\"").unwrap() < first.find("\"request\"").unwrap());
+        assert!(first.find("\"auth\"").unwrap() < first.find("\"method\"").unwrap());
+
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_paths_cannot_escape_root() {
+        let root = temp_workspace("escape");
+        assert!(resolve_in_workspace(&root, "../outside.json").is_err());
+        assert!(resolve_in_workspace(&root, "/etc/passwd").is_err());
+        assert!(delete_workspace_item(&root, ".").is_err());
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Get User #1"), "get-user-1");
+        assert_eq!(slugify("  "), "request");
+    }
+}
@@ -1,5 +1,6 @@
 use std::fs;
 use std::path::Path;
+use serde::Serialize;
 use super::models::SavedRequest;
 use crate::models::ApiRequest;
 
@@ -8,8 +9,7 @@ pub fn save_request_to_file(request: ApiRequest, path: &str) -> Result<(), Strin
     let saved_request = SavedRequest::from_request(request);
     
     // Serialize to pretty JSON
-    let json = serde_json::to_string_pretty(&saved_request)
-        .map_err(|e| format!("Failed to serialize request: {}", e))?;
+    let json = to_canonical_json(&saved_request)?;
     
     // Write to file with UTF-8 encoding
     fs::write(path, json)
@@ -18,6 +18,18 @@ pub fn save_request_to_file(request: ApiRequest, path: &str) -> Result<(), Strin
     Ok(())
 }
 
+/// Pretty JSON with sorted keys and a trailing newline, so re-saving an
+/// unchanged request produces an identical file
+pub fn to_canonical_json<T: Serialize>(value: &T) -> Result<String, String> {
+    // serde_json::Value keeps object keys in sorted order
+    let value = serde_json::to_value(value)
+        .map_err(|e| format!("Failed to serialize request: {}", e))?;
+    let mut json = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize request: {}", e))?;
+    json.push('\n');
+    Ok(json)
+}
+
 /// Load a request from a JSON file
 pub fn load_request_from_file(path: &str) -> Result<ApiRequest, String> {
     // Check if file exists
@@ -39,7 +51,6 @@ pub fn load_request_from_file(path: &str) -> Result<ApiRequest, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{AuthPayload};
     use std::fs;
 
     #[test]
@@ -50,10 +61,7 @@ mod tests {
         let request = ApiRequest {
             method: "GET".to_string(),
             url: "https://api.example.com/users".to_string(),
-            params: vec![],
-            headers: vec![],
-            body: None,
-            auth: AuthPayload::default(),
+            ..Default::default()
         };
         
         // Save
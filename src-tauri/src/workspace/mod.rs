@@ -1,4 +1,9 @@
 pub mod models;
 pub mod io;
+pub mod collections;
+pub mod folder;
+pub mod postman;
+pub mod openapi;
 
 pub use io::{save_request_to_file, load_request_from_file};
+pub use collections::CollectionStore;
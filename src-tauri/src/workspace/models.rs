@@ -16,6 +16,99 @@ impl SavedRequest {
     }
 }
 
+/// Kind of node in a collection tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    Collection,
+    Folder,
+    Request,
+}
+
+impl NodeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeKind::Collection => "collection",
+            NodeKind::Folder => "folder",
+            NodeKind::Request => "request",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "collection" => Some(NodeKind::Collection),
+            "folder" => Some(NodeKind::Folder),
+            "request" => Some(NodeKind::Request),
+            _ => None,
+        }
+    }
+
+    /// Collections and folders can hold children
+    pub fn is_container(&self) -> bool {
+        !matches!(self, NodeKind::Request)
+    }
+}
+
+/// A collection, folder or request, with its children in display order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionNode {
+    pub id: String,
+    #[serde(rename = "parentId")]
+    pub parent_id: Option<String>,
+    pub kind: NodeKind,
+    pub name: String,
+    pub position: i64,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub request: Option<ApiRequest>,
+    #[serde(default)]
+    pub children: Vec<CollectionNode>,
+}
+
+impl CollectionNode {
+    /// All requests below this node, depth-first in display order
+    pub fn flatten_requests(&self) -> Vec<SavedRequest> {
+        let mut result = Vec::new();
+        self.collect_requests(&mut result);
+        result
+    }
+
+    fn collect_requests(&self, out: &mut Vec<SavedRequest>) {
+        if let Some(request) = &self.request {
+            out.push(SavedRequest {
+                name: self.name.clone(),
+                request: request.clone(),
+            });
+        }
+        for child in &self.children {
+            child.collect_requests(out);
+        }
+    }
+}
+
+/// Notes about anything an import or export could not convert
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConversionReport {
+    pub warnings: Vec<String>,
+}
+
+impl ConversionReport {
+    pub fn warn(&mut self, context: &str, message: impl Into<String>) {
+        let message = message.into();
+        if context.is_empty() {
+            self.warnings.push(message);
+        } else {
+            self.warnings.push(format!("{}: {}", context, message));
+        }
+    }
+}
+
+/// Result of an import, together with its conversion report
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportOutcome<T> {
+    pub imported: T,
+    pub report: ConversionReport,
+}
+
 /// Extract a readable endpoint from a URL for naming
 fn extract_endpoint(url: &str) -> String {
     if let Ok(parsed) = url::Url::parse(url) {
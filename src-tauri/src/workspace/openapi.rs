@@ -0,0 +1,740 @@
+use serde_json::{json, Map, Value};
+use super::models::{CollectionNode, ConversionReport, NodeKind};
+use crate::models::{ApiRequest, AuthPayload, FormPart, FormPartKind, KeyValue, RequestBody};
+
+const METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// Maximum schema nesting followed when synthesizing example bodies
+const MAX_SCHEMA_DEPTH: usize = 8;
+
+/// Converts an OpenAPI 3 or Swagger 2 spec (JSON or YAML) into a collection tree,
+/// with one request per operation and one folder per tag.
+/// Node ids are left empty; they are assigned when the tree is persisted.
+pub fn import_spec(content: &str) -> Result<(CollectionNode, ConversionReport), String> {
+    let spec: Value = match serde_json::from_str(content) {
+        Ok(value) => value,
+        Err(_) => serde_yaml::from_str(content)
+            .map_err(|e| format!("Failed to parse spec as JSON or YAML: {}", e))?,
+    };
+
+    let is_swagger2 = match (spec.get("openapi"), spec.get("swagger")) {
+        (Some(Value::String(v)), _) if v.starts_with('3') => false,
+        (_, Some(Value::String(v))) if v.starts_with('2') => true,
+        _ => return Err("Not an OpenAPI 3 or Swagger 2 document".to_string()),
+    };
+
+    let mut report = ConversionReport::default();
+    let importer = Importer {
+        spec: &spec,
+        is_swagger2,
+    };
+
+    let title = spec
+        .pointer("/info/title")
+        .and_then(Value::as_str)
+        .unwrap_or("OpenAPI Import")
+        .to_string();
+    let base_url = importer.base_url(&mut report);
+
+    // Folder order follows the spec's top-level tag list, then first use
+    let mut folders: Vec<(String, Vec<CollectionNode>)> = spec
+        .get("tags")
+        .and_then(Value::as_array)
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|t| t.get("name").and_then(Value::as_str))
+                .map(|name| (name.to_string(), Vec::new()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut untagged = Vec::new();
+
+    let paths = spec.get("paths").and_then(Value::as_object).cloned().unwrap_or_default();
+    for (path, path_item) in &paths {
+        let path_item = importer.deref(path_item, &mut report);
+        for method in METHODS {
+            let Some(operation) = path_item.get(*method) else { continue };
+            let context = format!("{} {}", method.to_uppercase(), path);
+            let request = importer.convert_operation(&base_url, path, method, &path_item, operation, &mut report);
+
+            let name = operation
+                .get("summary")
+                .or_else(|| operation.get("operationId"))
+                .and_then(Value::as_str)
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| context.clone());
+            let node = leaf(name, request);
+
+            match operation.pointer("/tags/0").and_then(Value::as_str) {
+                Some(tag) => match folders.iter_mut().find(|(name, _)| name == tag) {
+                    Some((_, children)) => children.push(node),
+                    None => folders.push((tag.to_string(), vec![node])),
+                },
+                None => untagged.push(node),
+            }
+        }
+    }
+
+    let mut children: Vec<CollectionNode> = folders
+        .into_iter()
+        .filter(|(_, children)| !children.is_empty())
+        .map(|(name, children)| container(NodeKind::Folder, name, children))
+        .collect();
+    children.extend(untagged);
+    for (index, child) in children.iter_mut().enumerate() {
+        child.position = index as i64;
+    }
+
+    Ok((container(NodeKind::Collection, title, children), report))
+}
+
+fn leaf(name: String, request: ApiRequest) -> CollectionNode {
+    CollectionNode {
+        id: String::new(),
+        parent_id: None,
+        kind: NodeKind::Request,
+        name,
+        position: 0,
+        request: Some(request),
+        children: Vec::new(),
+    }
+}
+
+fn container(kind: NodeKind, name: String, mut children: Vec<CollectionNode>) -> CollectionNode {
+    for (index, child) in children.iter_mut().enumerate() {
+        child.position = index as i64;
+    }
+    CollectionNode {
+        id: String::new(),
+        parent_id: None,
+        kind,
+        name,
+        position: 0,
+        request: None,
+        children,
+    }
+}
+
+struct Importer<'a> {
+    spec: &'a Value,
+    is_swagger2: bool,
+}
+
+impl<'a> Importer<'a> {
+    fn base_url(&self, report: &mut ConversionReport) -> String {
+        let url = if self.is_swagger2 {
+            self.spec.get("host").and_then(Value::as_str).map(|host| {
+                let scheme = self
+                    .spec
+                    .pointer("/schemes/0")
+                    .and_then(Value::as_str)
+                    .unwrap_or("https");
+                let base_path = self.spec.get("basePath").and_then(Value::as_str).unwrap_or("");
+                format!("{}://{}{}", scheme, host, base_path)
+            })
+        } else {
+            self.spec.pointer("/servers/0").map(|server| {
+                let mut url = server.get("url").and_then(Value::as_str).unwrap_or("").to_string();
+                // Substitute server variables with their defaults
+                if let Some(vars) = server.get("variables").and_then(Value::as_object) {
+                    for (name, var) in vars {
+                        let default = var.get("default").map(value_to_string).unwrap_or_default();
+                        url = url.replace(&format!("{{{}}}", name), &default);
+                    }
+                }
+                url
+            })
+        };
+
+        match url {
+            Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+                url.trim_end_matches('/').to_string()
+            }
+            Some(relative) => {
+                report.warn("", "Server URL is relative; set {{baseUrl}} in your environment");
+                format!("{{{{baseUrl}}}}{}", relative.trim_end_matches('/'))
+            }
+            None => {
+                report.warn("", "No server URL in spec; set {{baseUrl}} in your environment");
+                "{{baseUrl}}".to_string()
+            }
+        }
+    }
+
+    fn convert_operation(
+        &self,
+        base_url: &str,
+        path: &str,
+        method: &str,
+        path_item: &Value,
+        operation: &Value,
+        report: &mut ConversionReport,
+    ) -> ApiRequest {
+        let context = &format!("{} {}", method.to_uppercase(), path);
+        let mut params = Vec::new();
+        let mut headers = Vec::new();
+        let mut body = RequestBody::None;
+
+        // Operation-level parameters override path-level ones with the same name and location
+        let mut parameters: Vec<Value> = Vec::new();
+        for source in [path_item.get("parameters"), operation.get("parameters")] {
+            for param in source.and_then(Value::as_array).into_iter().flatten() {
+                let param = self.deref(param, report);
+                let key = (param.get("name").cloned(), param.get("in").cloned());
+                parameters.retain(|p| (p.get("name").cloned(), p.get("in").cloned()) != key);
+                parameters.push(param);
+            }
+        }
+
+        let mut form_parts: Vec<FormPart> = Vec::new();
+        for param in &parameters {
+            let name = param.get("name").and_then(Value::as_str).unwrap_or("").to_string();
+            let required = param.get("required").and_then(Value::as_bool).unwrap_or(false);
+            let location = param.get("in").and_then(Value::as_str).unwrap_or("");
+            match location {
+                "query" => params.push(KeyValue {
+                    enabled: false,
+                    key: name,
+                    value: self.param_example(param),
+                }),
+                "header" => headers.push(KeyValue {
+                    enabled: required,
+                    key: name,
+                    value: self.param_example(param),
+                }),
+                "path" => {}
+                "body" => {
+                    // Swagger 2 body parameter
+                    if let Some(schema) = param.get("schema") {
+                        body = RequestBody::raw(pretty(&self.synthesize(schema, 0)));
+                        add_header(&mut headers, "Content-Type", &self.swagger2_content_type(operation));
+                    }
+                }
+                "formData" => {
+                    // Swagger 2 form field; `type: file` is an upload
+                    let is_file = param.get("type").and_then(Value::as_str) == Some("file");
+                    form_parts.push(FormPart {
+                        enabled: required || is_file,
+                        key: name,
+                        kind: if is_file { FormPartKind::File } else { FormPartKind::Text },
+                        value: if is_file { String::new() } else { self.param_example(param) },
+                        content_type: None,
+                    });
+                }
+                other => report.warn(context, format!("{} parameter '{}' was not converted", other, name)),
+            }
+        }
+
+        if !form_parts.is_empty() {
+            let multipart = form_parts.iter().any(|p| p.kind == FormPartKind::File)
+                || self.swagger2_content_type(operation) == "multipart/form-data";
+            body = if multipart {
+                RequestBody::Multipart { parts: form_parts }
+            } else {
+                RequestBody::Urlencoded {
+                    fields: form_parts
+                        .into_iter()
+                        .map(|p| KeyValue { enabled: p.enabled, key: p.key, value: p.value })
+                        .collect(),
+                }
+            };
+        }
+
+        if !self.is_swagger2 {
+            if let Some(request_body) = operation.get("requestBody") {
+                let request_body = self.deref(request_body, report);
+                body = self.convert_request_body(&request_body, &mut headers, context, report);
+            }
+        }
+
+        // {id} -> {{id}}
+        let url = format!("{}{}", base_url, path.replace('{', "{{").replace('}', "}}"));
+
+        ApiRequest {
+            method: method.to_uppercase(),
+            url,
+            params,
+            headers,
+            body,
+            auth: self.convert_security(operation, context, report),
+            ..Default::default()
+        }
+    }
+
+    fn convert_request_body(&self, request_body: &Value, headers: &mut Vec<KeyValue>, context: &str, report: &mut ConversionReport) -> RequestBody {
+        let Some(content) = request_body.get("content").and_then(Value::as_object) else {
+            return RequestBody::None;
+        };
+
+        let pick = content
+            .iter()
+            .find(|(mime, _)| mime.contains("json"))
+            .or_else(|| content.iter().find(|(mime, _)| *mime == "application/x-www-form-urlencoded"))
+            .or_else(|| content.iter().find(|(mime, _)| *mime == "multipart/form-data"))
+            .or_else(|| content.iter().find(|(mime, _)| mime.starts_with("text/")));
+
+        let Some((mime, media)) = pick else {
+            let types: Vec<&str> = content.keys().map(String::as_str).collect();
+            report.warn(context, format!("request body ({}) was not converted", types.join(", ")));
+            return RequestBody::None;
+        };
+
+        let example = media
+            .get("example")
+            .cloned()
+            .or_else(|| {
+                media.get("examples")
+                    .and_then(Value::as_object)
+                    .and_then(|examples| examples.values().next())
+                    .map(|example| self.deref(example, report))
+                    .and_then(|example| example.get("value").cloned())
+            })
+            .or_else(|| media.get("schema").map(|schema| self.synthesize(schema, 0)))
+            .unwrap_or(Value::Null);
+
+        // Form bodies get their Content-Type (and multipart boundary) when sent
+        let fields = example.as_object().cloned().unwrap_or_default();
+        match mime.as_str() {
+            "application/x-www-form-urlencoded" => RequestBody::Urlencoded {
+                fields: fields
+                    .iter()
+                    .map(|(k, v)| KeyValue {
+                        enabled: true,
+                        key: k.clone(),
+                        value: value_to_string(v),
+                    })
+                    .collect(),
+            },
+            "multipart/form-data" => {
+                let properties = media.get("schema").map(|schema| self.deref(schema, report));
+                RequestBody::Multipart {
+                    parts: fields
+                        .iter()
+                        .map(|(k, v)| {
+                            // `format: binary` properties are file uploads; the path is left to fill in
+                            let is_file = properties
+                                .as_ref()
+                                .and_then(|schema| schema.pointer(&format!("/properties/{}/format", k)))
+                                .and_then(Value::as_str)
+                                == Some("binary");
+                            FormPart {
+                                enabled: true,
+                                key: k.clone(),
+                                kind: if is_file { FormPartKind::File } else { FormPartKind::Text },
+                                value: if is_file { String::new() } else { value_to_string(v) },
+                                content_type: None,
+                            }
+                        })
+                        .collect(),
+                }
+            }
+            _ => {
+                add_header(headers, "Content-Type", mime);
+                RequestBody::raw(if mime.contains("json") { pretty(&example) } else { value_to_string(&example) })
+            }
+        }
+    }
+
+    fn swagger2_content_type(&self, operation: &Value) -> String {
+        operation
+            .pointer("/consumes/0")
+            .or_else(|| self.spec.pointer("/consumes/0"))
+            .and_then(Value::as_str)
+            .unwrap_or("application/json")
+            .to_string()
+    }
+
+    /// Maps the first applicable security requirement onto an AuthPayload
+    fn convert_security(&self, operation: &Value, context: &str, report: &mut ConversionReport) -> AuthPayload {
+        let none = AuthPayload {
+            auth_type: "none".to_string(),
+            ..Default::default()
+        };
+
+        let requirements = operation
+            .get("security")
+            .or_else(|| self.spec.get("security"))
+            .and_then(Value::as_array);
+        let Some(scheme_name) = requirements
+            .and_then(|reqs| reqs.first())
+            .and_then(Value::as_object)
+            .and_then(|req| req.keys().next())
+        else {
+            return none;
+        };
+
+        let schemes_path = if self.is_swagger2 { "/securityDefinitions" } else { "/components/securitySchemes" };
+        let Some(scheme) = self.spec.pointer(schemes_path).and_then(|s| s.get(scheme_name)) else {
+            report.warn(context, format!("security scheme '{}' is not defined", scheme_name));
+            return none;
+        };
+        let scheme = self.deref(scheme, report);
+
+        let scheme_type = scheme.get("type").and_then(Value::as_str).unwrap_or("");
+        let http_scheme = scheme.get("scheme").and_then(Value::as_str).unwrap_or("").to_lowercase();
+
+        match (scheme_type, http_scheme.as_str()) {
+            ("http", "bearer") => AuthPayload {
+                auth_type: "bearer".to_string(),
+                token: Some("{{TOKEN}}".to_string()),
+                ..Default::default()
+            },
+            ("http", "basic") | ("basic", _) => AuthPayload {
+                auth_type: "basic".to_string(),
+                username: Some("{{USERNAME}}".to_string()),
+                password: Some("{{PASSWORD}}".to_string()),
+                ..Default::default()
+            },
+            ("apiKey", _) => {
+                let location = scheme.get("in").and_then(Value::as_str).unwrap_or("header");
+                if location == "cookie" {
+                    report.warn(context, format!("cookie API key '{}' was not converted", scheme_name));
+                    return none;
+                }
+                AuthPayload {
+                    auth_type: "apikey".to_string(),
+                    api_key: scheme.get("name").and_then(Value::as_str).map(|s| s.to_string()),
+                    api_value: Some("{{API_KEY}}".to_string()),
+                    api_location: Some(location.to_string()),
+                    ..Default::default()
+                }
+            }
+            ("oauth2", _) | ("openIdConnect", _) => {
+                report.warn(context, format!(
+                    "{} scheme '{}' mapped to a bearer token; set {{{{ACCESS_TOKEN}}}}",
+                    scheme_type, scheme_name
+                ));
+                AuthPayload {
+                    auth_type: "bearer".to_string(),
+                    token: Some("{{ACCESS_TOKEN}}".to_string()),
+                    ..Default::default()
+                }
+            }
+            _ => {
+                report.warn(context, format!("security scheme '{}' ({}) was not converted", scheme_name, scheme_type));
+                none
+            }
+        }
+    }
+
+    fn param_example(&self, param: &Value) -> String {
+        let example = param
+            .get("example")
+            .or_else(|| param.get("default"))
+            .or_else(|| param.pointer("/schema/example"))
+            .or_else(|| param.pointer("/schema/default"))
+            .or_else(|| param.pointer("/schema/enum/0"))
+            .or_else(|| param.pointer("/enum/0"));
+        example.map(value_to_string).unwrap_or_default()
+    }
+
+    /// Builds an example value from a JSON schema
+    fn synthesize(&self, schema: &Value, depth: usize) -> Value {
+        if depth > MAX_SCHEMA_DEPTH {
+            return Value::Null;
+        }
+        let schema = match self.lookup_ref(schema) {
+            Some(resolved) => resolved,
+            None if schema.get("$ref").is_some() => return Value::Null,
+            None => schema,
+        };
+
+        if let Some(example) = schema.get("example").or_else(|| schema.get("default")) {
+            return example.clone();
+        }
+        if let Some(first) = schema.pointer("/enum/0") {
+            return first.clone();
+        }
+        if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+            let mut merged = Map::new();
+            for part in all_of {
+                if let Value::Object(fields) = self.synthesize(part, depth + 1) {
+                    merged.extend(fields);
+                }
+            }
+            return Value::Object(merged);
+        }
+        if let Some(first) = schema
+            .pointer("/oneOf/0")
+            .or_else(|| schema.pointer("/anyOf/0"))
+        {
+            return self.synthesize(first, depth + 1);
+        }
+
+        let schema_type = match schema.get("type") {
+            Some(Value::String(t)) => t.as_str(),
+            // OpenAPI 3.1 allows a list of types
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).find(|t| *t != "null").unwrap_or("null"),
+            _ if schema.get("properties").is_some() => "object",
+            _ => "",
+        };
+
+        match schema_type {
+            "object" => {
+                let mut object = Map::new();
+                if let Some(props) = schema.get("properties").and_then(Value::as_object) {
+                    for (name, prop) in props {
+                        object.insert(name.clone(), self.synthesize(prop, depth + 1));
+                    }
+                }
+                Value::Object(object)
+            }
+            "array" => match schema.get("items") {
+                Some(items) => json!([self.synthesize(items, depth + 1)]),
+                None => json!([]),
+            },
+            "integer" => json!(0),
+            "number" => json!(0.0),
+            "boolean" => json!(true),
+            "string" => json!(match schema.get("format").and_then(Value::as_str) {
+                Some("date-time") => "2024-01-01T00:00:00Z",
+                Some("date") => "2024-01-01",
+                Some("email") => "user@example.com",
+                Some("uuid") => "00000000-0000-0000-0000-000000000000",
+                Some("uri") | Some("url") => "https://example.com",
+                _ => "string",
+            }),
+            _ => Value::Null,
+        }
+    }
+
+    /// Follows a local `#/...` reference; None if the value has no usable `$ref`
+    fn lookup_ref(&self, value: &'a Value) -> Option<&'a Value> {
+        let reference = value.get("$ref")?.as_str()?;
+        let pointer = reference.strip_prefix('#')?;
+        self.spec.pointer(pointer)
+    }
+
+    fn deref(&self, value: &Value, report: &mut ConversionReport) -> Value {
+        match value.get("$ref").and_then(Value::as_str) {
+            Some(reference) => match reference.strip_prefix('#').and_then(|p| self.spec.pointer(p)) {
+                Some(target) => target.clone(),
+                None => {
+                    report.warn("", format!("Reference '{}' could not be resolved (only local references are supported)", reference));
+                    Value::Null
+                }
+            },
+            None => value.clone(),
+        }
+    }
+}
+
+fn add_header(headers: &mut Vec<KeyValue>, key: &str, value: &str) {
+    if !headers.iter().any(|h| h.key.eq_ignore_ascii_case(key)) {
+        headers.push(KeyValue {
+            enabled: true,
+            key: key.to_string(),
+            value: value.to_string(),
+        });
+    }
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPENAPI_YAML: &str = r##"
+openapi: 3.0.3
+info:
+  title: Pets
+servers:
+  - url: https://{env}.pets.local/v1
+    variables:
+      env:
+        default: api
+tags:
+  - name: pets
+security:
+  - bearerAuth: []
+components:
+  securitySchemes:
+    bearerAuth:
+      type: http
+      scheme: bearer
+    keyAuth:
+      type: apiKey
+      in: query
+      name: api_key
+  schemas:
+    Pet:
+      type: object
+      properties:
+        id:
+          type: integer
+        name:
+          type: string
+          example: Rex
+        tags:
+          type: array
+          items:
+            type: string
+        owner:
+          $ref: '#/components/schemas/Owner'
+    Owner:
+      type: object
+      properties:
+        email:
+          type: string
+          format: email
+paths:
+  /pets/{petId}:
+    parameters:
+      - name: petId
+        in: path
+        required: true
+        schema:
+          type: integer
+    get:
+      tags: [pets]
+      summary: Get a pet
+      parameters:
+        - name: fields
+          in: query
+          schema:
+            type: string
+            default: all
+        - name: X-Request-Id
+          in: header
+          required: true
+          schema:
+            type: string
+    put:
+      tags: [pets]
+      operationId: updatePet
+      security:
+        - keyAuth: []
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: '#/components/schemas/Pet'
+  /health:
+    get:
+      security: []
+"##;
+
+    #[test]
+    fn test_import_openapi3_yaml() {
+        let (collection, report) = import_spec(OPENAPI_YAML).unwrap();
+        assert_eq!(collection.name, "Pets");
+        assert_eq!(collection.children.len(), 2);
+
+        let pets = &collection.children[0];
+        assert_eq!(pets.kind, NodeKind::Folder);
+        assert_eq!(pets.name, "pets");
+
+        let get = &pets.children[0];
+        assert_eq!(get.name, "Get a pet");
+        let get_req = get.request.as_ref().unwrap();
+        assert_eq!(get_req.url, "https://api.pets.local/v1/pets/{{petId}}");
+        assert_eq!(get_req.params.len(), 1);
+        assert!(!get_req.params[0].enabled);
+        assert_eq!(get_req.params[0].value, "all");
+        assert!(get_req.headers[0].enabled);
+        assert_eq!(get_req.auth.auth_type, "bearer");
+
+        let put = pets.children[1].request.as_ref().unwrap();
+        assert_eq!(pets.children[1].name, "updatePet");
+        assert_eq!(put.auth.auth_type, "apikey");
+        assert_eq!(put.auth.api_location.as_deref(), Some("query"));
+        let body: Value = serde_json::from_str(put.body.as_raw().unwrap()).unwrap();
+        assert_eq!(body["name"], "Rex");
+        assert_eq!(body["tags"], json!(["string"]));
+        assert_eq!(body["owner"]["email"], "user@example.com");
+
+        let health = collection.children[1].request.as_ref().unwrap();
+        assert_eq!(health.auth.auth_type, "none");
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    }
+
+    #[test]
+    fn test_import_swagger2_json() {
+        let spec = r##"{
+            "swagger": "2.0",
+            "info": { "title": "Legacy" },
+            "host": "legacy.local",
+            "basePath": "/api",
+            "schemes": ["http"],
+            "securityDefinitions": { "basic": { "type": "basic" } },
+            "paths": {
+                "/users": {
+                    "post": {
+                        "security": [{ "basic": [] }],
+                        "parameters": [
+                            { "name": "user", "in": "body", "schema": { "type": "object", "properties": { "age": { "type": "integer" } } } }
+                        ]
+                    }
+                }
+            }
+        }"##;
+
+        let (collection, _) = import_spec(spec).unwrap();
+        let req = collection.children[0].request.as_ref().unwrap();
+        assert_eq!(req.method, "POST");
+        assert_eq!(req.url, "http://legacy.local/api/users");
+        assert_eq!(req.auth.auth_type, "basic");
+        assert_eq!(req.headers[0].value, "application/json");
+        let body: Value = serde_json::from_str(req.body.as_raw().unwrap()).unwrap();
+        assert_eq!(body["age"], 0);
+    }
+
+    #[test]
+    fn test_form_bodies() {
+        let spec = r##"{
+            "openapi": "3.0.0",
+            "info": { "title": "Files" },
+            "servers": [{ "url": "https://files.local" }],
+            "paths": {
+                "/upload": { "post": { "requestBody": { "content": { "multipart/form-data": {
+                    "schema": { "type": "object", "properties": {
+                        "title": { "type": "string", "example": "Q3" },
+                        "file": { "type": "string", "format": "binary" }
+                    } }
+                } } } } }
+            }
+        }"##;
+
+        let (collection, _) = import_spec(spec).unwrap();
+        let req = collection.children[0].request.as_ref().unwrap();
+        let RequestBody::Multipart { parts } = &req.body else {
+            panic!("expected a multipart body");
+        };
+        let file = parts.iter().find(|p| p.key == "file").unwrap();
+        assert_eq!(file.kind, FormPartKind::File);
+        assert_eq!(parts.iter().find(|p| p.key == "title").unwrap().value, "Q3");
+        // Sent with its own boundary, so no fixed Content-Type header
+        assert!(req.headers.is_empty());
+    }
+
+    #[test]
+    fn test_recursive_schema_terminates() {
+        let spec = r##"{
+            "openapi": "3.0.0",
+            "info": { "title": "Tree" },
+            "components": { "schemas": { "Node": { "type": "object", "properties": { "child": { "$ref": "#/components/schemas/Node" } } } } },
+            "paths": { "/nodes": { "post": { "requestBody": { "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Node" } } } } } } }
+        }"##;
+
+        let (collection, report) = import_spec(spec).unwrap();
+        assert!(!collection.children[0].request.as_ref().unwrap().body.is_empty());
+        assert!(report.warnings.iter().any(|w| w.contains("baseUrl")));
+    }
+
+    #[test]
+    fn test_rejects_unknown_documents() {
+        assert!(import_spec("name: not a spec").is_err());
+        assert!(import_spec("{{{").is_err());
+    }
+}
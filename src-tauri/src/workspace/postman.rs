@@ -0,0 +1,942 @@
+use serde_json::{json, Map, Value};
+use super::models::{CollectionNode, ConversionReport, NodeKind};
+use crate::engine::curl::split_url_params;
+use crate::env::EnvVar;
+use crate::env::dynamic::BUILTINS;
+use crate::models::{
+    ApiRequest, AuthPayload, AwsV4Config, ClientAuthentication, FormPart, FormPartKind, KeyValue, OAuth2Config, OAuth2Grant, RequestBody,
+};
+
+const SCHEMA_V21: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// Converts a Postman v2.0 or v2.1 collection into a collection tree.
+/// Node ids are left empty; they are assigned when the tree is persisted.
+pub fn import_collection(content: &str) -> Result<(CollectionNode, ConversionReport), String> {
+    let root: Value = serde_json::from_str(content)
+        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+    let info = root.get("info").ok_or_else(|| "Not a Postman collection: missing 'info'".to_string())?;
+    let schema = info.get("schema").and_then(Value::as_str).unwrap_or("");
+    if !schema.contains("v2.1") && !schema.contains("v2.0") {
+        return Err(format!("Unsupported Postman schema: {}", schema));
+    }
+
+    let mut report = ConversionReport::default();
+    let name = str_field(info, "name").unwrap_or_else(|| "Postman Collection".to_string());
+
+    if let Some(vars) = root.get("variable").and_then(Value::as_array) {
+        let keys: Vec<&str> = vars.iter().filter_map(|v| v.get("key").and_then(Value::as_str)).collect();
+        if !keys.is_empty() {
+            report.warn("", format!(
+                "Collection variables were not imported, add them to an environment: {}",
+                keys.join(", ")
+            ));
+        }
+    }
+    if has_scripts(&root) {
+        report.warn(&name, "collection scripts were not converted");
+    }
+
+    let auth = convert_auth(root.get("auth"), &AuthPayload::default(), &name, &mut report);
+    let children = convert_items(root.get("item"), &auth, &name, &mut report);
+
+    Ok((node(NodeKind::Collection, name, None, children), report))
+}
+
+/// Converts a collection tree into a Postman v2.1 collection, with a report
+/// of what Postman has no place for
+pub fn export_collection(collection: &CollectionNode) -> (Value, ConversionReport) {
+    let mut report = ConversionReport::default();
+    let items: Vec<Value> = collection.children.iter().map(|child| export_item(child, &collection.name, &mut report)).collect();
+    let export = json!({
+        "info": {
+            "name": collection.name,
+            "schema": SCHEMA_V21,
+        },
+        "item": items,
+    });
+    (export, report)
+}
+
+/// Reads a Postman environment file into a name and its variables
+pub fn import_environment(content: &str) -> Result<(String, Vec<EnvVar>, ConversionReport), String> {
+    let root: Value = serde_json::from_str(content)
+        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+    let values = root
+        .get("values")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "Not a Postman environment: missing 'values'".to_string())?;
+
+    let mut report = ConversionReport::default();
+    let name = str_field(&root, "name").unwrap_or_else(|| "Postman Environment".to_string());
+
+    let mut vars = Vec::new();
+    for value in values {
+        let Some(key) = str_field(value, "key") else {
+            report.warn(&name, "skipped a variable without a key");
+            continue;
+        };
+        if value.get("type").and_then(Value::as_str) == Some("secret") {
+            report.warn(&name, format!("'{}' was a secret in Postman and is stored as plain text", key));
+        }
+        vars.push(EnvVar {
+            key,
+            value: value_to_string(value.get("value")),
+            enabled: value.get("enabled").and_then(Value::as_bool).unwrap_or(true),
+        });
+    }
+
+    Ok((name, vars, report))
+}
+
+fn node(kind: NodeKind, name: String, request: Option<ApiRequest>, children: Vec<CollectionNode>) -> CollectionNode {
+    CollectionNode {
+        id: String::new(),
+        parent_id: None,
+        kind,
+        name,
+        position: 0,
+        request,
+        children,
+    }
+}
+
+fn convert_items(items: Option<&Value>, inherited_auth: &AuthPayload, path: &str, report: &mut ConversionReport) -> Vec<CollectionNode> {
+    let Some(items) = items.and_then(Value::as_array) else {
+        return Vec::new();
+    };
+
+    let mut nodes = Vec::new();
+    for item in items {
+        let name = str_field(item, "name").unwrap_or_else(|| "Untitled".to_string());
+        let context = format!("{} / {}", path, name);
+
+        if has_scripts(item) {
+            report.warn(&context, "scripts were not converted");
+        }
+
+        if item.get("item").is_some() {
+            // Folder, possibly overriding auth for its children
+            let auth = convert_auth(item.get("auth"), inherited_auth, &context, report);
+            let children = convert_items(item.get("item"), &auth, &context, report);
+            nodes.push(node(NodeKind::Folder, name, None, children));
+        } else if let Some(request) = item.get("request") {
+            let request = convert_request(request, inherited_auth, &context, report);
+            nodes.push(node(NodeKind::Request, name, Some(request), Vec::new()));
+        } else {
+            report.warn(&context, "item is neither a folder nor a request, skipped");
+        }
+    }
+
+    for (index, node) in nodes.iter_mut().enumerate() {
+        node.position = index as i64;
+    }
+    nodes
+}
+
+fn convert_request(request: &Value, inherited_auth: &AuthPayload, context: &str, report: &mut ConversionReport) -> ApiRequest {
+    // A request may be just a URL string
+    if let Some(url) = request.as_str() {
+        let (url, params) = split_url_params(url);
+        return ApiRequest {
+            method: "GET".to_string(),
+            url,
+            params,
+            auth: inherited_auth.clone(),
+            ..Default::default()
+        };
+    }
+
+    let method = str_field(request, "method").unwrap_or_else(|| "GET".to_string()).to_uppercase();
+    let (url, params) = convert_url(request.get("url"));
+    let mut headers = key_values(request.get("header"));
+    let body = convert_body(request.get("body"), &mut headers, context, report);
+    let auth = convert_auth(request.get("auth"), inherited_auth, context, report);
+
+    let req = ApiRequest {
+        method,
+        url,
+        params,
+        headers,
+        body,
+        auth,
+        ..Default::default()
+    };
+    check_dynamic_variables(&req, context, report);
+    req
+}
+
+fn convert_url(url: Option<&Value>) -> (String, Vec<KeyValue>) {
+    match url {
+        Some(Value::String(raw)) => split_url_params(raw),
+        Some(Value::Object(obj)) => {
+            let raw = obj.get("raw").and_then(Value::as_str).map(|s| s.to_string());
+            let base = match raw {
+                Some(raw) => split_url_params(&raw).0,
+                None => rebuild_url(obj),
+            };
+            // The query array carries the disabled flags, so prefer it over the raw string
+            let params = match obj.get("query") {
+                Some(query) => key_values(Some(query)),
+                None => obj.get("raw").and_then(Value::as_str).map(|raw| split_url_params(raw).1).unwrap_or_default(),
+            };
+            (base, params)
+        }
+        _ => (String::new(), Vec::new()),
+    }
+}
+
+fn rebuild_url(obj: &Map<String, Value>) -> String {
+    let join = |value: Option<&Value>, sep: &str| match value {
+        Some(Value::Array(parts)) => parts.iter().map(|p| value_to_string(Some(p))).collect::<Vec<_>>().join(sep),
+        Some(other) => value_to_string(Some(other)),
+        None => String::new(),
+    };
+
+    let mut url = String::new();
+    if let Some(protocol) = obj.get("protocol").and_then(Value::as_str) {
+        url.push_str(protocol);
+        url.push_str("://");
+    }
+    url.push_str(&join(obj.get("host"), "."));
+    if let Some(port) = obj.get("port").and_then(Value::as_str) {
+        url.push(':');
+        url.push_str(port);
+    }
+    let path = join(obj.get("path"), "/");
+    if !path.is_empty() {
+        url.push('/');
+        url.push_str(&path);
+    }
+    url
+}
+
+fn convert_body(body: Option<&Value>, headers: &mut Vec<KeyValue>, context: &str, report: &mut ConversionReport) -> RequestBody {
+    let Some(body) = body.filter(|b| !b.is_null()) else {
+        return RequestBody::None;
+    };
+    let mode = body.get("mode").and_then(Value::as_str).unwrap_or("raw");
+
+    match mode {
+        "raw" => {
+            let Some(raw) = str_field(body, "raw") else {
+                return RequestBody::None;
+            };
+            let language = body.pointer("/options/raw/language").and_then(Value::as_str);
+            if language == Some("json") {
+                add_content_type(headers, "application/json");
+            }
+            RequestBody::raw(raw)
+        }
+        "urlencoded" => RequestBody::Urlencoded { fields: key_values(body.get("urlencoded")) },
+        "formdata" => RequestBody::Multipart {
+            parts: body
+                .get("formdata")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|row| {
+                    let is_file = row.get("type").and_then(Value::as_str) == Some("file");
+                    // A file part's src is a path, or a list of them when several files are picked
+                    let value = if is_file {
+                        match row.get("src") {
+                            Some(Value::Array(paths)) => value_to_string(paths.first()),
+                            src => value_to_string(src),
+                        }
+                    } else {
+                        value_to_string(row.get("value"))
+                    };
+                    Some(FormPart {
+                        enabled: !row.get("disabled").and_then(Value::as_bool).unwrap_or(false),
+                        key: str_field(row, "key")?,
+                        kind: if is_file { FormPartKind::File } else { FormPartKind::Text },
+                        value,
+                        content_type: str_field(row, "contentType"),
+                    })
+                })
+                .collect(),
+        },
+        "file" => RequestBody::Binary {
+            path: body.pointer("/file/src").and_then(Value::as_str).unwrap_or("").to_string(),
+            content_type: None,
+        },
+        "graphql" => RequestBody::GraphQl {
+            query: body.pointer("/graphql/query").and_then(Value::as_str).unwrap_or("").to_string(),
+            variables: body.pointer("/graphql/variables").and_then(Value::as_str).unwrap_or("").to_string(),
+            operation_name: None,
+        },
+        other => {
+            report.warn(context, format!("'{}' body was not converted", other));
+            RequestBody::None
+        }
+    }
+}
+
+fn add_content_type(headers: &mut Vec<KeyValue>, value: &str) {
+    if !headers.iter().any(|h| h.key.eq_ignore_ascii_case("content-type")) {
+        headers.push(KeyValue {
+            enabled: true,
+            key: "Content-Type".to_string(),
+            value: value.to_string(),
+        });
+    }
+}
+
+/// Maps a Postman auth block; a missing block inherits from the parent
+fn convert_auth(auth: Option<&Value>, inherited: &AuthPayload, context: &str, report: &mut ConversionReport) -> AuthPayload {
+    let Some(auth) = auth.filter(|a| !a.is_null()) else {
+        return inherited.clone();
+    };
+    let auth_type = auth.get("type").and_then(Value::as_str).unwrap_or("noauth");
+    // v2.1 lists parameters as [{key, value}]; v2.0 uses a plain object
+    let param = |key: &str| -> Option<String> {
+        match auth.get(auth_type)? {
+            Value::Array(params) => params
+                .iter()
+                .find(|p| p.get("key").and_then(Value::as_str) == Some(key))
+                .map(|p| value_to_string(p.get("value"))),
+            Value::Object(params) => params.get(key).map(|v| value_to_string(Some(v))),
+            _ => None,
+        }
+    };
+
+    match auth_type {
+        "bearer" => AuthPayload {
+            auth_type: "bearer".to_string(),
+            token: param("token"),
+            ..Default::default()
+        },
+        "apikey" => AuthPayload {
+            auth_type: "apikey".to_string(),
+            api_key: param("key"),
+            api_value: param("value"),
+            api_location: Some(param("in").unwrap_or_else(|| "header".to_string())),
+            ..Default::default()
+        },
+        "basic" => AuthPayload {
+            auth_type: "basic".to_string(),
+            username: param("username"),
+            password: param("password"),
+            ..Default::default()
+        },
+        "digest" => AuthPayload {
+            auth_type: "digest".to_string(),
+            username: param("username"),
+            password: param("password"),
+            ..Default::default()
+        },
+        "oauth2" => {
+            let grant_type = match param("grant_type").as_deref() {
+                Some("password_credentials") => OAuth2Grant::Password,
+                Some("authorization_code" | "authorization_code_with_pkce") => OAuth2Grant::AuthorizationCode,
+                Some("client_credentials") | None => OAuth2Grant::ClientCredentials,
+                Some(other) => {
+                    report.warn(context, format!("OAuth 2.0 grant '{}' is not supported, using client credentials", other));
+                    OAuth2Grant::ClientCredentials
+                }
+            };
+            AuthPayload {
+                auth_type: "oauth2".to_string(),
+                oauth2: Some(OAuth2Config {
+                    grant_type,
+                    token_url: param("accessTokenUrl").unwrap_or_default(),
+                    auth_url: param("authUrl").unwrap_or_default(),
+                    client_id: param("clientId").unwrap_or_default(),
+                    client_secret: param("clientSecret").unwrap_or_default(),
+                    scope: param("scope").unwrap_or_default(),
+                    username: param("username").unwrap_or_default(),
+                    password: param("password").unwrap_or_default(),
+                    client_authentication: match param("client_authentication").as_deref() {
+                        Some("body") => ClientAuthentication::Body,
+                        _ => ClientAuthentication::Basic,
+                    },
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }
+        }
+        "awsv4" => AuthPayload {
+            auth_type: "awsv4".to_string(),
+            awsv4: Some(AwsV4Config {
+                access_key_id: param("accessKey").unwrap_or_default(),
+                secret_access_key: param("secretKey").unwrap_or_default(),
+                session_token: param("sessionToken").unwrap_or_default(),
+                region: param("region").unwrap_or_default(),
+                service: param("service").unwrap_or_default(),
+            }),
+            ..Default::default()
+        },
+        "noauth" => AuthPayload {
+            auth_type: "none".to_string(),
+            ..Default::default()
+        },
+        "inherit" => inherited.clone(),
+        other => {
+            report.warn(context, format!("'{}' auth is not supported, request will be sent without auth", other));
+            AuthPayload {
+                auth_type: "none".to_string(),
+                ..Default::default()
+            }
+        }
+    }
+}
+
+fn check_dynamic_variables(req: &ApiRequest, context: &str, report: &mut ConversionReport) {
+    let mut fields = vec![req.url.as_str()];
+    fields.extend(req.params.iter().flat_map(|p| [p.key.as_str(), p.value.as_str()]));
+    fields.extend(req.headers.iter().flat_map(|h| [h.key.as_str(), h.value.as_str()]));
+    match &req.body {
+        RequestBody::Raw { content, .. } => fields.push(content),
+        RequestBody::Urlencoded { fields: rows } => {
+            fields.extend(rows.iter().flat_map(|f| [f.key.as_str(), f.value.as_str()]));
+        }
+        RequestBody::Multipart { parts } => fields.extend(parts.iter().map(|p| p.value.as_str())),
+        RequestBody::GraphQl { query, variables, .. } => fields.extend([query.as_str(), variables.as_str()]),
+        RequestBody::None | RequestBody::Binary { .. } => {}
+    }
+
+    let mut unsupported: Vec<String> = Vec::new();
+    for field in fields {
+        for part in field.split("{{$").skip(1) {
+            if let Some(end) = part.find("}}") {
+                let name = format!("${}", &part[..end]);
+                if !BUILTINS.contains(&name.as_str()) && !unsupported.contains(&name) {
+                    unsupported.push(name);
+                }
+            }
+        }
+    }
+    if !unsupported.is_empty() {
+        report.warn(context, format!("unsupported dynamic variables: {}", unsupported.join(", ")));
+    }
+}
+
+fn has_scripts(item: &Value) -> bool {
+    item.get("event")
+        .and_then(Value::as_array)
+        .map(|events| !events.is_empty())
+        .unwrap_or(false)
+}
+
+fn key_values(value: Option<&Value>) -> Vec<KeyValue> {
+    value
+        .and_then(Value::as_array)
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| {
+                    Some(KeyValue {
+                        enabled: !row.get("disabled").and_then(Value::as_bool).unwrap_or(false),
+                        key: str_field(row, "key")?,
+                        value: value_to_string(row.get("value")),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(|s| s.to_string())
+}
+
+fn value_to_string(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Null) | None => String::new(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn export_item(node: &CollectionNode, path: &str, report: &mut ConversionReport) -> Value {
+    let context = format!("{} / {}", path, node.name);
+    match &node.request {
+        Some(request) if node.kind == NodeKind::Request => json!({
+            "name": node.name,
+            "request": export_request(request, &context, report),
+        }),
+        _ => json!({
+            "name": node.name,
+            "item": node.children.iter().map(|child| export_item(child, &context, report)).collect::<Vec<_>>(),
+        }),
+    }
+}
+
+fn export_request(req: &ApiRequest, context: &str, report: &mut ConversionReport) -> Value {
+    if !req.assertions.is_empty() {
+        report.warn(context, "assertions were not exported");
+    }
+    if !req.extractions.is_empty() {
+        report.warn(context, "extraction rules were not exported");
+    }
+    if req.settings.is_some() {
+        report.warn(context, "request settings were not exported");
+    }
+
+    let export_rows = |rows: &[KeyValue]| -> Vec<Value> {
+        rows.iter()
+            .map(|row| {
+                let mut value = json!({ "key": row.key, "value": row.value });
+                if !row.enabled {
+                    value["disabled"] = json!(true);
+                }
+                value
+            })
+            .collect()
+    };
+
+    // Postman's raw URL only carries enabled params
+    let enabled: Vec<String> = req.params
+        .iter()
+        .filter(|p| p.enabled && !p.key.trim().is_empty())
+        .map(|p| format!("{}={}", p.key, p.value))
+        .collect();
+    let raw = if enabled.is_empty() {
+        req.url.clone()
+    } else {
+        format!("{}?{}", req.url, enabled.join("&"))
+    };
+
+    let mut request = json!({
+        "method": req.method.to_uppercase(),
+        "header": export_rows(&req.headers),
+        "url": {
+            "raw": raw,
+            "query": export_rows(&req.params),
+        },
+        "auth": export_auth(&req.auth, context, report),
+    });
+
+    match &req.body {
+        RequestBody::Raw { content, content_type } if !content.trim().is_empty() => {
+            if let Some(content_type) = content_type {
+                report.warn(context, format!("body content type '{}' was not exported, add it as a Content-Type header", content_type));
+            }
+            let trimmed = content.trim();
+            let is_json = (trimmed.starts_with('{') && trimmed.ends_with('}'))
+                || (trimmed.starts_with('[') && trimmed.ends_with(']'));
+            let mut body_value = json!({ "mode": "raw", "raw": content });
+            if is_json {
+                body_value["options"] = json!({ "raw": { "language": "json" } });
+            }
+            request["body"] = body_value;
+        }
+        RequestBody::Urlencoded { fields } => {
+            request["body"] = json!({ "mode": "urlencoded", "urlencoded": export_rows(fields) });
+        }
+        RequestBody::Multipart { parts } => {
+            let rows: Vec<Value> = parts
+                .iter()
+                .map(|part| {
+                    let mut row = match part.kind {
+                        FormPartKind::Text => json!({ "key": part.key, "value": part.value, "type": "text" }),
+                        FormPartKind::File => json!({ "key": part.key, "src": part.value, "type": "file" }),
+                    };
+                    if let Some(content_type) = &part.content_type {
+                        row["contentType"] = json!(content_type);
+                    }
+                    if !part.enabled {
+                        row["disabled"] = json!(true);
+                    }
+                    row
+                })
+                .collect();
+            request["body"] = json!({ "mode": "formdata", "formdata": rows });
+        }
+        RequestBody::Binary { path, content_type } => {
+            if let Some(content_type) = content_type {
+                report.warn(context, format!("body content type '{}' was not exported, add it as a Content-Type header", content_type));
+            }
+            request["body"] = json!({ "mode": "file", "file": { "src": path } });
+        }
+        RequestBody::GraphQl { query, variables, operation_name } => {
+            if operation_name.is_some() {
+                report.warn(context, "GraphQL operation name was not exported");
+            }
+            request["body"] = json!({ "mode": "graphql", "graphql": { "query": query, "variables": variables } });
+        }
+        _ => {}
+    }
+
+    request
+}
+
+fn export_auth(auth: &AuthPayload, context: &str, report: &mut ConversionReport) -> Value {
+    let param = |key: &str, value: &Option<String>| {
+        json!({ "key": key, "value": value.clone().unwrap_or_default(), "type": "string" })
+    };
+
+    match auth.auth_type.as_str() {
+        "bearer" => json!({
+            "type": "bearer",
+            "bearer": [param("token", &auth.token)],
+        }),
+        "apikey" => json!({
+            "type": "apikey",
+            "apikey": [
+                param("key", &auth.api_key),
+                param("value", &auth.api_value),
+                param("in", &Some(auth.api_location.clone().unwrap_or_else(|| "header".to_string()))),
+            ],
+        }),
+        "basic" => json!({
+            "type": "basic",
+            "basic": [param("username", &auth.username), param("password", &auth.password)],
+        }),
+        "digest" => {
+            report.warn(context, "digest auth was exported with only the username and password, Postman takes the rest from the server's challenge");
+            json!({
+                "type": "digest",
+                "digest": [param("username", &auth.username), param("password", &auth.password)],
+            })
+        }
+        "oauth2" => {
+            report.warn(context, "OAuth 2.0 was exported without its token or redirect port, get a new token in Postman");
+            let config = auth.oauth2.clone().unwrap_or_default();
+            let grant_type = match config.grant_type {
+                OAuth2Grant::ClientCredentials => "client_credentials",
+                OAuth2Grant::Password => "password_credentials",
+                OAuth2Grant::AuthorizationCode => "authorization_code_with_pkce",
+            };
+            let client_authentication = match config.client_authentication {
+                ClientAuthentication::Basic => "header",
+                ClientAuthentication::Body => "body",
+            };
+            let field = |key: &str, value: String| param(key, &Some(value));
+            json!({
+                "type": "oauth2",
+                "oauth2": [
+                    field("grant_type", grant_type.to_string()),
+                    field("accessTokenUrl", config.token_url),
+                    field("authUrl", config.auth_url),
+                    field("clientId", config.client_id),
+                    field("clientSecret", config.client_secret),
+                    field("scope", config.scope),
+                    field("username", config.username),
+                    field("password", config.password),
+                    field("client_authentication", client_authentication.to_string()),
+                ],
+            })
+        }
+        "awsv4" => {
+            report.warn(context, "AWS Signature was exported as settings only, Postman signs the request itself when sending");
+            let config = auth.awsv4.clone().unwrap_or_default();
+            let field = |key: &str, value: String| param(key, &Some(value));
+            json!({
+                "type": "awsv4",
+                "awsv4": [
+                    field("accessKey", config.access_key_id),
+                    field("secretKey", config.secret_access_key),
+                    field("sessionToken", config.session_token),
+                    field("region", config.region),
+                    field("service", config.service),
+                ],
+            })
+        }
+        _ => json!({ "type": "noauth" }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLLECTION: &str = r#"{
+        "info": {
+            "name": "Shop",
+            "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+        },
+        "auth": {
+            "type": "bearer",
+            "bearer": [{ "key": "token", "value": "{{TOKEN}}", "type": "string" }]
+        },
+        "variable": [{ "key": "BASE", "value": "https://shop.local" }],
+        "item": [
+            {
+                "name": "Orders",
+                "item": [
+                    {
+                        "name": "List orders",
+                        "request": {
+                            "method": "GET",
+                            "header": [
+                                { "key": "Accept", "value": "application/json" },
+                                { "key": "X-Debug", "value": "1", "disabled": true }
+                            ],
+                            "url": {
+                                "raw": "{{BASE}}/orders?page=1&limit=20",
+                                "host": ["{{BASE}}"],
+                                "path": ["orders"],
+                                "query": [
+                                    { "key": "page", "value": "1" },
+                                    { "key": "limit", "value": "20", "disabled": true }
+                                ]
+                            }
+                        }
+                    },
+                    {
+                        "name": "Create order",
+                        "event": [{ "listen": "test", "script": { "exec": ["pm.test()"] } }],
+                        "request": {
+                            "method": "POST",
+                            "auth": {
+                                "type": "apikey",
+                                "apikey": [
+                                    { "key": "key", "value": "X-Api-Key" },
+                                    { "key": "value", "value": "abc" },
+                                    { "key": "in", "value": "header" }
+                                ]
+                            },
+                            "url": "{{BASE}}/orders",
+                            "body": {
+                                "mode": "raw",
+                                "raw": "{\"id\": \"{{$randomUUID}}\"}",
+                                "options": { "raw": { "language": "json" } }
+                            }
+                        }
+                    }
+                ]
+            },
+            {
+                "name": "Login",
+                "request": {
+                    "method": "POST",
+                    "auth": {
+                        "type": "basic",
+                        "basic": [
+                            { "key": "username", "value": "admin" },
+                            { "key": "password", "value": "secret" }
+                        ]
+                    },
+                    "url": "{{BASE}}/login",
+                    "body": {
+                        "mode": "urlencoded",
+                        "urlencoded": [{ "key": "remember", "value": "yes please" }]
+                    }
+                }
+            },
+            {
+                "name": "Upload",
+                "request": {
+                    "method": "POST",
+                    "url": "{{BASE}}/upload",
+                    "body": {
+                        "mode": "formdata",
+                        "formdata": [
+                            { "key": "note", "value": "{{$randomEmail}}", "type": "text" },
+                            { "key": "doc", "src": "/tmp/report.pdf", "type": "file", "contentType": "application/pdf" }
+                        ]
+                    }
+                }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_import_collection() {
+        let (collection, report) = import_collection(COLLECTION).unwrap();
+        assert_eq!(collection.name, "Shop");
+        assert_eq!(collection.children.len(), 3);
+
+        let orders = &collection.children[0];
+        assert_eq!(orders.kind, NodeKind::Folder);
+
+        let list = orders.children[0].request.as_ref().unwrap();
+        assert_eq!(list.url, "{{BASE}}/orders");
+        assert_eq!(list.params.len(), 2);
+        assert!(!list.params[1].enabled);
+        assert!(!list.headers[1].enabled);
+        // Inherited from the collection
+        assert_eq!(list.auth.auth_type, "bearer");
+        assert_eq!(list.auth.token.as_deref(), Some("{{TOKEN}}"));
+
+        let create = orders.children[1].request.as_ref().unwrap();
+        assert_eq!(create.auth.auth_type, "apikey");
+        assert_eq!(create.auth.api_key.as_deref(), Some("X-Api-Key"));
+        assert!(create.headers.iter().any(|h| h.value == "application/json"));
+
+        let login = collection.children[1].request.as_ref().unwrap();
+        assert_eq!(login.auth.auth_type, "basic");
+        assert_eq!(
+            login.body,
+            RequestBody::Urlencoded {
+                fields: vec![KeyValue { enabled: true, key: "remember".to_string(), value: "yes please".to_string() }]
+            }
+        );
+
+        let upload = collection.children[2].request.as_ref().unwrap();
+        let RequestBody::Multipart { parts } = &upload.body else {
+            panic!("expected a multipart body");
+        };
+        assert_eq!(parts[1].kind, FormPartKind::File);
+        assert_eq!(parts[1].value, "/tmp/report.pdf");
+        assert_eq!(parts[1].content_type.as_deref(), Some("application/pdf"));
+
+        let warnings = report.warnings.join("\n");
+        assert!(warnings.contains("BASE"));
+        assert!(warnings.contains("Create order: scripts were not converted"));
+        assert!(warnings.contains("$randomUUID"));
+        assert!(!warnings.contains("$randomEmail"));
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let (collection, _) = import_collection(COLLECTION).unwrap();
+        let (exported, _) = export_collection(&collection);
+        assert_eq!(exported["info"]["schema"], SCHEMA_V21);
+
+        let (reimported, _) = import_collection(&exported.to_string()).unwrap();
+        let original = collection.children[0].children[0].request.as_ref().unwrap();
+        let again = reimported.children[0].children[0].request.as_ref().unwrap();
+        assert_eq!(again.url, original.url);
+        assert_eq!(again.params.len(), original.params.len());
+        assert_eq!(again.params[1].enabled, original.params[1].enabled);
+        assert_eq!(again.auth.auth_type, "bearer");
+
+        for index in [1, 2] {
+            let original = collection.children[index].request.as_ref().unwrap();
+            let again = reimported.children[index].request.as_ref().unwrap();
+            assert_eq!(again.body, original.body);
+        }
+    }
+
+    #[test]
+    fn test_export_reports_dropped_data() {
+        let mut request = ApiRequest::get("https://api.local/items");
+        request.body = RequestBody::Raw { content: "<a/>".to_string(), content_type: Some("application/xml".to_string()) };
+        request.assertions.push(crate::models::Assertion {
+            enabled: true,
+            check: crate::models::AssertionCheck::StatusEquals { value: 200 },
+        });
+        request.settings = Some(Default::default());
+        let folder = node(NodeKind::Folder, "Items".to_string(), None, vec![
+            node(NodeKind::Request, "Create".to_string(), Some(request), Vec::new()),
+        ]);
+        let collection = node(NodeKind::Collection, "Api".to_string(), None, vec![folder]);
+
+        let (_, report) = export_collection(&collection);
+        assert_eq!(report.warnings, vec![
+            "Api / Items / Create: assertions were not exported",
+            "Api / Items / Create: request settings were not exported",
+            "Api / Items / Create: body content type 'application/xml' was not exported, add it as a Content-Type header",
+        ]);
+    }
+
+    #[test]
+    fn test_oauth2_round_trip() {
+        let content = r#"{
+            "info": { "name": "Api", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json" },
+            "auth": {
+                "type": "oauth2",
+                "oauth2": [
+                    { "key": "grant_type", "value": "password_credentials" },
+                    { "key": "accessTokenUrl", "value": "https://auth.local/token" },
+                    { "key": "clientId", "value": "{{CLIENT_ID}}" },
+                    { "key": "username", "value": "jane" },
+                    { "key": "client_authentication", "value": "body" }
+                ]
+            },
+            "item": [{ "name": "Me", "request": { "method": "GET", "url": "https://api.local/me" } }]
+        }"#;
+        let (collection, _) = import_collection(content).unwrap();
+        let auth = &collection.children[0].request.as_ref().unwrap().auth;
+        assert_eq!(auth.auth_type, "oauth2");
+        let config = auth.oauth2.as_ref().unwrap();
+        assert_eq!(config.grant_type, OAuth2Grant::Password);
+        assert_eq!(config.token_url, "https://auth.local/token");
+        assert_eq!(config.client_id, "{{CLIENT_ID}}");
+        assert_eq!(config.client_authentication, ClientAuthentication::Body);
+
+        let (exported, report) = export_collection(&collection);
+        assert!(report.warnings[0].contains("Api / Me: OAuth 2.0 was exported without its token"));
+        let (reimported, _) = import_collection(&exported.to_string()).unwrap();
+        assert_eq!(reimported.children[0].request.as_ref().unwrap().auth.oauth2.as_ref(), Some(config));
+    }
+
+    #[test]
+    fn test_awsv4_round_trip() {
+        let content = r#"{
+            "info": { "name": "Api", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json" },
+            "item": [{
+                "name": "Bucket",
+                "request": {
+                    "method": "GET",
+                    "url": "http://localhost:9000/bucket",
+                    "auth": {
+                        "type": "awsv4",
+                        "awsv4": [
+                            { "key": "accessKey", "value": "{{AWS_KEY}}" },
+                            { "key": "secretKey", "value": "{{AWS_SECRET}}" },
+                            { "key": "region", "value": "us-east-1" },
+                            { "key": "service", "value": "s3" }
+                        ]
+                    }
+                }
+            }]
+        }"#;
+        let (collection, report) = import_collection(content).unwrap();
+        assert!(report.warnings.is_empty());
+        let auth = &collection.children[0].request.as_ref().unwrap().auth;
+        let config = auth.awsv4.as_ref().unwrap();
+        assert_eq!((config.access_key_id.as_str(), config.service.as_str()), ("{{AWS_KEY}}", "s3"));
+
+        let (reimported, _) = import_collection(&export_collection(&collection).0.to_string()).unwrap();
+        assert_eq!(reimported.children[0].request.as_ref().unwrap().auth.awsv4.as_ref(), Some(config));
+    }
+
+    #[test]
+    fn test_import_environment() {
+        let content = r#"{
+            "name": "Staging",
+            "values": [
+                { "key": "BASE", "value": "https://staging.local", "enabled": true },
+                { "key": "TOKEN", "value": "t0k3n", "type": "secret", "enabled": false }
+            ],
+            "_postman_variable_scope": "environment"
+        }"#;
+
+        let (name, vars, report) = import_environment(content).unwrap();
+        assert_eq!(name, "Staging");
+        assert_eq!(vars.len(), 2);
+        assert!(!vars[1].enabled);
+        assert_eq!(report.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_import_v20_auth_objects() {
+        let content = r#"{
+            "info": {
+                "name": "Legacy",
+                "schema": "https://schema.getpostman.com/json/collection/v2.0.0/collection.json"
+            },
+            "auth": { "type": "bearer", "bearer": { "token": "{{TOKEN}}" } },
+            "item": [
+                {
+                    "name": "Login",
+                    "request": {
+                        "method": "POST",
+                        "auth": { "type": "basic", "basic": { "username": "admin", "password": "secret" } },
+                        "url": "https://legacy.local/login"
+                    }
+                },
+                { "name": "Me", "request": { "method": "GET", "url": "https://legacy.local/me" } }
+            ]
+        }"#;
+
+        let (collection, _) = import_collection(content).unwrap();
+        let login = collection.children[0].request.as_ref().unwrap();
+        assert_eq!(login.auth.auth_type, "basic");
+        assert_eq!(login.auth.username.as_deref(), Some("admin"));
+        assert_eq!(login.auth.password.as_deref(), Some("secret"));
+
+        let me = collection.children[1].request.as_ref().unwrap();
+        assert_eq!(me.auth.auth_type, "bearer");
+        assert_eq!(me.auth.token.as_deref(), Some("{{TOKEN}}"));
+    }
+
+    #[test]
+    fn test_rejects_non_postman_json() {
+        assert!(import_collection(r#"{"openapi": "3.0.0"}"#).is_err());
+        assert!(import_environment(r#"{"name": "x"}"#).is_err());
+    }
+}
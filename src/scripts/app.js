@@ -7,6 +7,8 @@ document.addEventListener('DOMContentLoaded', async () => {
         initTabs();
         initAuthForm();
         initResponseControls();
+        initWebSocketPanel();
+        initStreaming();
     } catch (e) {
         console.error('Module initialization failed:', e);
     }
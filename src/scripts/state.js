@@ -17,9 +17,17 @@ const state = {
         }
     },
     response: null,
+    // The response whose raw body the backend holds for saving (the last one sent)
+    storedBodyResponse: null,
     history: [],
     envVars: [],
-    collections: []
+    collections: [],
+    // Schema cached for the current GraphQL endpoint, if any
+    graphqlSchema: null,
+    // Id of the open WebSocket session, if any
+    wsSessionId: null,
+    // Id of the response being streamed, if any
+    streamId: null
 };
 
 if (typeof window.getInvoke === 'undefined') {
@@ -112,22 +120,29 @@ function escapeHtml(text) {
 }
 
 // Validation and Env Resolution Helpers
-function getUnresolvedVars(text) {
-    if (!text || typeof text !== 'string') return [];
-    const matches = text.match(/{{([^}]+)}}/g);
-    if (!matches) return [];
-    
-    const unresolved = [];
-    const envKeys = state.envVars.map(v => v.key);
-    
-    matches.forEach(match => {
-        const key = match.slice(2, -2);
-        if (!envKeys.includes(key)) {
-            unresolved.push(key);
-        }
-    });
-    
-    return unresolved;
+
+/** Variables in `text` the backend can't resolve against the active environment */
+async function getUnresolvedVars(text) {
+    if (!text || typeof text !== 'string' || !text.includes('{{')) return [];
+    const invoke = window.getInvoke ? window.getInvoke() : null;
+    if (!invoke) return [];
+    try {
+        const resolved = await invoke('preview_variable_text', { input: text });
+        return resolved.unresolved;
+    } catch (e) {
+        // A circular reference can't be resolved either
+        return [String(e)];
+    }
+}
+
+/** Marks `input` while `value` has variables the backend can't resolve */
+async function highlightUnresolved(input, value) {
+    input.dataset.preview = value;
+    const unresolved = await getUnresolvedVars(value);
+    // Skip answers for a value that has since been edited
+    if (input.dataset.preview === value) {
+        input.classList.toggle('unresolved-var', unresolved.length > 0);
+    }
 }
 
 function validateJson(text) {
@@ -50,9 +50,16 @@ function renderAuthFields(authType) {
                 <label>Password</label>
                 <input type="password" id="basicPassword" placeholder="Enter password" value="${escapeHtml(state.request.auth.password)}" />
             </div>
-        `
+        `,
+
+        oauth2: oauth2Template(),
+
+        awsv4: awsv4Template()
     };
 
+    // Same credentials; the backend answers the server's challenge
+    templates.digest = templates.basic;
+
     authFields.innerHTML = templates[authType] || templates.none;
 
     // Add event listeners for auth inputs
@@ -76,7 +83,7 @@ function renderAuthFields(authType) {
         document.getElementById('apiLocation')?.addEventListener('change', (e) => {
             updateAuth({ apiLocation: e.target.value });
         });
-    } else if (authType === 'basic') {
+    } else if (authType === 'basic' || authType === 'digest') {
         document.getElementById('basicUsername')?.addEventListener('input', (e) => {
             const val = e.target.value;
             updateAuth({ username: val });
@@ -87,13 +94,195 @@ function renderAuthFields(authType) {
             updateAuth({ password: val });
             highlightUnresolved(e.target, val);
         });
+    } else if (authType === 'oauth2') {
+        initOAuth2Fields();
+    } else if (authType === 'awsv4') {
+        updateAwsV4({});
+        document.querySelectorAll('[data-awsv4]').forEach(input => {
+            input.addEventListener('input', (e) => {
+                const val = e.target.value;
+                updateAwsV4({ [e.target.dataset.awsv4]: val });
+                highlightUnresolved(e.target, val);
+            });
+        });
+    }
+}
+
+/* AWS credentials live in state.request.auth.awsv4; the backend signs the
+ * final request, so there is nothing to fetch up front. */
+const AWSV4_FIELDS = [
+    { key: 'accessKeyId', label: 'Access Key', placeholder: 'e.g., {{AWS_ACCESS_KEY_ID}}' },
+    { key: 'secretAccessKey', label: 'Secret Key', placeholder: 'e.g., {{AWS_SECRET_ACCESS_KEY}}', secret: true },
+    { key: 'sessionToken', label: 'Session Token', placeholder: 'Only for temporary credentials', secret: true },
+    { key: 'region', label: 'Region', placeholder: 'e.g., us-east-1' },
+    { key: 'service', label: 'Service Name', placeholder: 'e.g., execute-api or s3' }
+];
+
+function awsv4Config() {
+    const defaults = Object.fromEntries(AWSV4_FIELDS.map(f => [f.key, '']));
+    return { ...defaults, ...(state.request.auth.awsv4 || {}) };
+}
+
+function updateAwsV4(updates) {
+    updateAuth({ awsv4: { ...awsv4Config(), ...updates } });
+}
+
+function awsv4Template() {
+    const config = awsv4Config();
+    return AWSV4_FIELDS.map(f => `
+        <div class="auth-field">
+            <label>${f.label}</label>
+            <input type="${f.secret ? 'password' : 'text'}" data-awsv4="${f.key}" placeholder="${f.placeholder}" value="${escapeHtml(config[f.key])}" />
+        </div>
+    `).join('');
+}
+
+/* OAuth 2.0 settings live in state.request.auth.oauth2; the backend fetches,
+ * caches and refreshes the token, so the form only shows its status. */
+const OAUTH2_DEFAULTS = {
+    grantType: 'clientCredentials',
+    tokenUrl: '',
+    authUrl: '',
+    clientId: '',
+    clientSecret: '',
+    scope: '',
+    username: '',
+    password: '',
+    redirectPort: 0,
+    clientAuthentication: 'basic'
+};
+
+const OAUTH2_TEXT_FIELDS = [
+    { key: 'tokenUrl', label: 'Token URL', placeholder: 'https://auth.example.com/oauth/token' },
+    { key: 'authUrl', label: 'Authorization URL', placeholder: 'https://auth.example.com/authorize', grants: ['authorizationCode'] },
+    { key: 'clientId', label: 'Client ID', placeholder: 'Enter client ID' },
+    { key: 'clientSecret', label: 'Client Secret', placeholder: 'Optional for public clients', secret: true },
+    { key: 'scope', label: 'Scope', placeholder: 'e.g., read write' },
+    { key: 'username', label: 'Username', placeholder: 'Resource owner username', grants: ['password'] },
+    { key: 'password', label: 'Password', placeholder: 'Resource owner password', secret: true, grants: ['password'] },
+    { key: 'redirectPort', label: 'Redirect Port', placeholder: '0 picks a free port', grants: ['authorizationCode'] }
+];
+
+function oauth2Config() {
+    return { ...OAUTH2_DEFAULTS, ...(state.request.auth.oauth2 || {}) };
+}
+
+function oauth2Template() {
+    const config = oauth2Config();
+    const option = (value, label, current) =>
+        `<option value="${value}" ${current === value ? 'selected' : ''}>${label}</option>`;
+    const fields = OAUTH2_TEXT_FIELDS
+        .filter(f => !f.grants || f.grants.includes(config.grantType))
+        .map(f => `
+            <div class="auth-field">
+                <label>${f.label}</label>
+                <input type="${f.secret ? 'password' : 'text'}" data-oauth2="${f.key}" placeholder="${f.placeholder}" value="${escapeHtml(String(config[f.key] ?? ''))}" />
+            </div>
+        `).join('');
+
+    return `
+        <div class="auth-field">
+            <label>Grant Type</label>
+            <select id="oauth2Grant">
+                ${option('clientCredentials', 'Client Credentials', config.grantType)}
+                ${option('password', 'Password Credentials', config.grantType)}
+                ${option('authorizationCode', 'Authorization Code (PKCE)', config.grantType)}
+            </select>
+        </div>
+        ${fields}
+        <div class="auth-field">
+            <label>Client Authentication</label>
+            <select id="oauth2ClientAuth">
+                ${option('basic', 'Basic Auth header', config.clientAuthentication)}
+                ${option('body', 'Credentials in body', config.clientAuthentication)}
+            </select>
+        </div>
+        <div class="oauth2-actions">
+            <button class="btn-secondary" id="oauth2TokenBtn">Get New Access Token</button>
+            <button class="btn-secondary" id="oauth2ClearBtn">Clear Token</button>
+        </div>
+        <div class="oauth2-status" id="oauth2Status"></div>
+    `;
+}
+
+function updateOAuth2(updates) {
+    updateAuth({ oauth2: { ...oauth2Config(), ...updates } });
+}
+
+function initOAuth2Fields() {
+    updateOAuth2({});
+    document.getElementById('oauth2Grant')?.addEventListener('change', (e) => {
+        updateOAuth2({ grantType: e.target.value });
+        renderAuthFields('oauth2');
+    });
+    document.getElementById('oauth2ClientAuth')?.addEventListener('change', (e) => {
+        updateOAuth2({ clientAuthentication: e.target.value });
+    });
+    document.querySelectorAll('[data-oauth2]').forEach(input => {
+        input.addEventListener('input', (e) => {
+            const key = e.target.dataset.oauth2;
+            const val = e.target.value;
+            updateOAuth2({ [key]: key === 'redirectPort' ? (parseInt(val, 10) || 0) : val });
+            highlightUnresolved(e.target, val);
+        });
+    });
+    document.getElementById('oauth2TokenBtn')?.addEventListener('click', fetchOAuth2Token);
+    document.getElementById('oauth2ClearBtn')?.addEventListener('click', clearOAuth2Token);
+    refreshOAuth2Status();
+}
+
+function setOAuth2Status(message, isError = false) {
+    const status = document.getElementById('oauth2Status');
+    if (!status) return;
+    status.textContent = message;
+    status.classList.toggle('error', isError);
+}
+
+function describeToken(token) {
+    if (!token) return 'No access token yet';
+    if (!token.expiresAt) return `${token.tokenType} token cached`;
+    const seconds = Math.round((token.expiresAt - Date.now()) / 1000);
+    return seconds > 0
+        ? `${token.tokenType} token cached, expires in ${seconds}s`
+        : `${token.tokenType} token expired, it will be refreshed on the next send`;
+}
+
+async function refreshOAuth2Status() {
+    const invoke = window.getInvoke ? window.getInvoke() : null;
+    if (!invoke) return;
+    try {
+        const token = await invoke('oauth2_token_status', { req: requestForBackend() });
+        setOAuth2Status(describeToken(token));
+    } catch (e) {
+        setOAuth2Status(String(e), true);
     }
 }
 
-function highlightUnresolved(input, value) {
-    if (getUnresolvedVars(value).length > 0) {
-        input.classList.add('unresolved-var');
-    } else {
-        input.classList.remove('unresolved-var');
+async function fetchOAuth2Token() {
+    const invoke = window.getInvoke ? window.getInvoke() : null;
+    if (!invoke) return;
+    const btn = document.getElementById('oauth2TokenBtn');
+    btn.disabled = true;
+    setOAuth2Status(oauth2Config().grantType === 'authorizationCode'
+        ? 'Waiting for sign-in in your browser...'
+        : 'Requesting token...');
+    try {
+        setOAuth2Status(describeToken(await invoke('oauth2_get_token', { req: requestForBackend() })));
+    } catch (e) {
+        setOAuth2Status(String(e), true);
+    } finally {
+        btn.disabled = false;
     }
 }
+
+async function clearOAuth2Token() {
+    const invoke = window.getInvoke ? window.getInvoke() : null;
+    if (!invoke) return;
+    try {
+        await invoke('oauth2_clear_token', { req: requestForBackend() });
+        refreshOAuth2Status();
+    } catch (e) {
+        setOAuth2Status(String(e), true);
+    }
+}
+
@@ -24,12 +24,7 @@ function initRequestBar() {
             const val = e.target.value;
             updateRequest({ url: val });
             
-            // Highlight unresolved
-            if (getUnresolvedVars(val).length > 0) {
-                urlInput.classList.add('unresolved-var');
-            } else {
-                urlInput.classList.remove('unresolved-var');
-            }
+            highlightUnresolved(urlInput, val);
         });
 
         if (bodyEditor) {
@@ -37,12 +32,7 @@ function initRequestBar() {
                 const val = e.target.value;
                 updateRequest({ body: val });
                 
-                // Highlight unresolved
-                if (getUnresolvedVars(val).length > 0) {
-                    bodyEditor.classList.add('unresolved-var');
-                } else {
-                    bodyEditor.classList.remove('unresolved-var');
-                }
+                highlightUnresolved(bodyEditor, val);
             });
         }
 
@@ -76,29 +66,61 @@ function initRequestBar() {
             });
         }
 
+        const addFormDataBtn = document.getElementById('addFormDataBtn');
+        if (addFormDataBtn) {
+            addFormDataBtn.addEventListener('click', () => {
+                if (!state.request.formData) state.request.formData = [];
+                state.request.formData.push({ key: '', value: '', enabled: true, kind: 'text' });
+                renderFormData();
+            });
+        }
+
         // Body type switching
         const bodyTypeRadios = document.querySelectorAll('input[name="bodyType"]');
         bodyTypeRadios.forEach(radio => {
             radio.addEventListener('change', (e) => {
-                const rawBody = document.getElementById('rawBody');
-                const formDataBody = document.getElementById('formDataBody');
-                if (e.target.value === 'raw') {
-                    rawBody.style.display = 'block';
-                    formDataBody.style.display = 'none';
-                } else {
-                    rawBody.style.display = 'none';
-                    formDataBody.style.display = 'block';
-                    renderFormData();
-                }
+                const panels = { raw: 'rawBody', 'form-data': 'formDataBody', graphql: 'graphqlBody' };
+                Object.entries(panels).forEach(([type, id]) => {
+                    const panel = document.getElementById(id);
+                    if (panel) panel.style.display = e.target.value === type ? 'block' : 'none';
+                });
+                if (e.target.value === 'form-data') renderFormData();
+                if (e.target.value === 'graphql') loadGraphqlSchema();
             });
         });
 
+        // GraphQL: validate as you type against the cached schema
+        const graphqlQuery = document.getElementById('graphqlQuery');
+        if (graphqlQuery) {
+            let timer = null;
+            graphqlQuery.addEventListener('input', () => {
+                clearTimeout(timer);
+                timer = setTimeout(validateGraphqlQuery, 400);
+            });
+        }
+        const fetchSchemaBtn = document.getElementById('fetchSchemaBtn');
+        if (fetchSchemaBtn) fetchSchemaBtn.addEventListener('click', fetchGraphqlSchema);
+
         // File upload
         const uploadFileBtn = document.getElementById('uploadFileBtn');
         const fileInput = document.getElementById('fileInput');
         if (uploadFileBtn && fileInput) {
-            uploadFileBtn.addEventListener('click', () => {
-                fileInput.click();
+            uploadFileBtn.addEventListener('click', async () => {
+                // The native dialog gives real paths, which the backend reads when sending
+                const dialog = window.getDialog ? window.getDialog() : null;
+                if (!dialog?.open) {
+                    fileInput.click();
+                    return;
+                }
+                const picked = await dialog.open({ multiple: true });
+                if (!picked) return;
+                if (!state.request.formData) state.request.formData = [];
+                (Array.isArray(picked) ? picked : [picked]).forEach(path => {
+                    const name = path.split(/[\\/]/).pop();
+                    state.request.formData.push({ key: name, value: path, enabled: true, kind: 'file' });
+                });
+                state.request.formMode = 'multipart';
+                renderFormData();
             });
             fileInput.addEventListener('change', handleFileSelect);
         }
@@ -131,6 +153,15 @@ async function handleSendRequest() {
         return;
     }
 
+    if (isWebSocketUrl(state.request.url)) {
+        await handleWebSocketConnect();
+        return;
+    }
+    if (shouldStream()) {
+        await handleStreamingSend();
+        return;
+    }
+
     // --- HARDENING: Body Validation ---
     const method = state.request.method.toUpperCase();
     if (method === 'GET' || method === 'DELETE') {
@@ -140,7 +171,7 @@ async function handleSendRequest() {
         const contentTypeHeader = state.request.headers.find(h => 
             h.enabled && h.key.toLowerCase() === 'content-type'
         );
-        if (contentTypeHeader && contentTypeHeader.value.includes('application/json')) {
+        if (contentTypeHeader && contentTypeHeader.value.includes('application/json') && typeof state.request.body === 'string') {
             if (!validateJson(state.request.body)) {
                 alert('Invalid JSON body');
                 return;
@@ -149,31 +180,16 @@ async function handleSendRequest() {
     }
 
     // --- HARDENING: Env Resolution Check ---
-    const unresolved = [];
-    unresolved.push(...getUnresolvedVars(state.request.url));
-    state.request.params.filter(p => p.enabled).forEach(p => {
-        unresolved.push(...getUnresolvedVars(p.key));
-        unresolved.push(...getUnresolvedVars(p.value));
-    });
-    state.request.headers.filter(h => h.enabled).forEach(h => {
-        unresolved.push(...getUnresolvedVars(h.key));
-        unresolved.push(...getUnresolvedVars(h.value));
-    });
-    unresolved.push(...getUnresolvedVars(state.request.body));
-    
-    // Auth vars
-    if (state.request.auth.type !== 'none') {
-        unresolved.push(...getUnresolvedVars(state.request.auth.token));
-        unresolved.push(...getUnresolvedVars(state.request.auth.apiKey));
-        unresolved.push(...getUnresolvedVars(state.request.auth.apiValue));
-        unresolved.push(...getUnresolvedVars(state.request.auth.username));
-        unresolved.push(...getUnresolvedVars(state.request.auth.password));
-    }
-
-    if (unresolved.length > 0) {
-        const uniqueUnresolved = [...new Set(unresolved)];
-        console.warn('Unresolved variables:', uniqueUnresolved);
-        // We don't block, but we could highlight UI here.
+    // The backend resolves the whole request the way a send would, body and auth included
+    try {
+        const invoke = window.getInvoke ? window.getInvoke() : null;
+        const preview = invoke ? await invoke('preview_request_variables', { req: requestForBackend() }) : null;
+        if (preview && preview.unresolved.length > 0) {
+            console.warn('Unresolved variables:', preview.unresolved);
+            // We don't block, but we could highlight UI here.
+        }
+    } catch (e) {
+        console.warn('Variable resolution failed:', e);
     }
 
     // --- HARDENING: Auth validation ---
@@ -185,6 +201,19 @@ async function handleSendRequest() {
         alert('Username and password are required for Basic auth');
         return;
     }
+    if (state.request.auth.type === 'digest' && !state.request.auth.username) {
+        alert('Username is required for Digest auth');
+        return;
+    }
+    if (state.request.auth.type === 'oauth2' && !(state.request.auth.oauth2?.tokenUrl && state.request.auth.oauth2?.clientId)) {
+        alert('Token URL and client ID are required for OAuth 2.0');
+        return;
+    }
+    const awsv4 = state.request.auth.awsv4;
+    if (state.request.auth.type === 'awsv4' && !(awsv4?.accessKeyId && awsv4?.secretAccessKey && awsv4?.region && awsv4?.service)) {
+        alert('Access key, secret key, region and service are required for AWS Signature');
+        return;
+    }
 
     // Show loading state
     sendBtn.disabled = true;
@@ -201,13 +230,14 @@ async function handleSendRequest() {
         }
 
         // Deep clone the request to avoid proxy/reference issues
-        const requestPayload = JSON.parse(JSON.stringify(state.request));
+        const requestPayload = requestForBackend();
         
         const response = await invoke('send_request', { 
             req: requestPayload
         });
         
         setResponse(response);
+        state.storedBodyResponse = response;
         
         // Reload history
         await loadHistory();
@@ -241,7 +271,7 @@ async function handleExportCurl() {
         }
         
         const curlCommand = await invoke('export_curl', { 
-            req: structuredClone(state.request)
+            req: requestForBackend()
         });
         
         // Copy to clipboard
@@ -274,7 +304,7 @@ async function handleSaveRequest() {
         if (!invoke) {
             throw new Error('Tauri invoke not found');
         }
-        await invoke('save_request', { req: structuredClone(state.request) });
+        await invoke('save_request', { req: requestForBackend() });
         
         // Show success feedback
         const btn = document.getElementById('saveRequestBtn');
@@ -309,7 +339,7 @@ async function handleLoadRequest() {
         // Update UI elements
         document.getElementById('methodSelect').value = loadedRequest.method;
         document.getElementById('urlInput').value = loadedRequest.url;
-        document.querySelector('.body-editor').value = loadedRequest.body || '';
+        loadBodyIntoEditor(loadedRequest.body);
         
         // Update params, headers, body, auth
         renderParams();
@@ -354,9 +384,6 @@ function renderKVList(container, items, type) {
     }
 
     container.innerHTML = items.map((item, index) => {
-        const unresolvedKey = getUnresolvedVars(item.key).length > 0;
-        const unresolvedValue = getUnresolvedVars(item.value).length > 0;
-        
         let displayKey = item.key;
         if (type === 'headers' && item.key) {
             // Normalize casing for display: Camel-Case-Headers
@@ -368,10 +395,8 @@ function renderKVList(container, items, type) {
         return `
             <div class="kv-row" data-index="${index}">
                 <input type="checkbox" ${item.enabled ? 'checked' : ''} class="kv-enabled">
-                <input type="text" placeholder="key" value="${escapeHtml(displayKey)}" 
-                       class="kv-key ${unresolvedKey ? 'unresolved-var' : ''}">
-                <input type="text" placeholder="value" value="${escapeHtml(item.value)}" 
-                       class="kv-value ${unresolvedValue ? 'unresolved-var' : ''}">
+                <input type="text" placeholder="key" value="${escapeHtml(displayKey)}" class="kv-key">
+                <input type="text" placeholder="value" value="${escapeHtml(item.value)}" class="kv-value">
                 <button class="btn-icon-small btn-delete-kv">×</button>
             </div>
         `;
@@ -384,6 +409,8 @@ function renderKVList(container, items, type) {
         const keyInput = row.querySelector('.kv-key');
         const valueInput = row.querySelector('.kv-value');
         const deleteBtn = row.querySelector('.btn-delete-kv');
+        highlightUnresolved(keyInput, items[index].key);
+        highlightUnresolved(valueInput, items[index].value);
 
         enabledInput.addEventListener('change', (e) => {
             items[index].enabled = e.target.checked;
@@ -392,13 +419,7 @@ function renderKVList(container, items, type) {
         keyInput.addEventListener('input', (e) => {
             const val = e.target.value;
             items[index].key = val;
-            
-            // Highlight unresolved
-            if (getUnresolvedVars(val).length > 0) {
-                keyInput.classList.add('unresolved-var');
-            } else {
-                keyInput.classList.remove('unresolved-var');
-            }
+            highlightUnresolved(keyInput, val);
 
             // Warning for Content-Type override
             if (type === 'headers' && val.toLowerCase() === 'content-type') {
@@ -413,13 +434,7 @@ function renderKVList(container, items, type) {
         valueInput.addEventListener('input', (e) => {
             const val = e.target.value;
             items[index].value = val;
-            
-            // Highlight unresolved
-            if (getUnresolvedVars(val).length > 0) {
-                valueInput.classList.add('unresolved-var');
-            } else {
-                valueInput.classList.remove('unresolved-var');
-            }
+            highlightUnresolved(valueInput, val);
         });
 
         deleteBtn.addEventListener('click', () => {
@@ -430,6 +445,125 @@ function renderKVList(container, items, type) {
     });
 }
 
+/*
+ * Request bodies are typed on the backend:
+ * { mode: 'none' | 'raw' | 'urlencoded' | 'multipart' | 'binary' | 'graphql', ... }.
+ * The body tab edits raw text, form fields or a GraphQL query; a plain string body is accepted as raw.
+ */
+function loadBodyIntoEditor(body) {
+    const bodyEditor = document.querySelector('.body-editor');
+    const isForm = body && (body.mode === 'urlencoded' || body.mode === 'multipart');
+    const isGraphql = body && body.mode === 'graphql';
+
+    state.request.formMode = isForm ? body.mode : 'multipart';
+    state.request.formData = isForm ? (body.fields || body.parts).map(field => ({ kind: 'text', ...field })) : [];
+    const text = typeof body === 'string' ? body : (body?.content || '');
+    if (bodyEditor) bodyEditor.value = text;
+    // Keep the editor text as the raw body; only binary bodies stay typed
+    state.request.body = body && body.mode === 'binary' ? body : text;
+    document.getElementById('graphqlQuery').value = isGraphql ? body.query : '';
+    document.getElementById('graphqlVariables').value = isGraphql ? (body.variables || '') : '';
+    document.getElementById('graphqlOperation').value = isGraphql ? (body.operationName || '') : '';
+
+    const type = isGraphql ? 'graphql' : (isForm ? 'form-data' : 'raw');
+    const radio = document.querySelector(`input[name="bodyType"][value="${type}"]`);
+    if (radio) {
+        radio.checked = true;
+        radio.dispatchEvent(new Event('change'));
+    }
+}
+
+/** Copy of the request with the body in the shape the backend expects */
+function requestForBackend() {
+    const req = JSON.parse(JSON.stringify(state.request));
+    const bodyType = document.querySelector('input[name="bodyType"]:checked')?.value;
+
+    if (bodyType === 'form-data') {
+        const fields = req.formData || [];
+        req.body = req.formMode === 'urlencoded'
+            ? { mode: 'urlencoded', fields: fields.map(({ key, value, enabled }) => ({ key, value, enabled })) }
+            : { mode: 'multipart', parts: fields };
+    } else if (bodyType === 'graphql') {
+        const operationName = document.getElementById('graphqlOperation').value.trim();
+        req.body = {
+            mode: 'graphql',
+            query: document.getElementById('graphqlQuery').value,
+            variables: document.getElementById('graphqlVariables').value,
+            ...(operationName ? { operationName } : {})
+        };
+    }
+    delete req.formData;
+    delete req.formMode;
+    delete req.files;
+    return req;
+}
+
+/* GraphQL schemas are cached per endpoint by the backend; state.graphqlSchema
+ * holds the one for the current URL and feeds field suggestions. */
+async function fetchGraphqlSchema() {
+    const invoke = window.getInvoke ? window.getInvoke() : null;
+    if (!invoke) return;
+    try {
+        const cached = await invoke('introspect_graphql_schema', { req: requestForBackend() });
+        state.graphqlSchema = cached.schema;
+        setGraphqlStatus(`Schema cached (${cached.schema.types.length} types)`);
+        validateGraphqlQuery();
+    } catch (e) {
+        setGraphqlStatus(String(e), true);
+    }
+}
+
+async function loadGraphqlSchema() {
+    const invoke = window.getInvoke ? window.getInvoke() : null;
+    if (!invoke || !state.request.url.trim()) return;
+    try {
+        const cached = await invoke('get_graphql_schema', { url: state.request.url });
+        state.graphqlSchema = cached ? cached.schema : null;
+        setGraphqlStatus(cached
+            ? `Using schema fetched ${new Date(cached.fetchedAt).toLocaleString()}`
+            : 'No schema cached for this endpoint; only syntax is checked');
+    } catch (e) {
+        setGraphqlStatus(String(e), true);
+    }
+}
+
+async function validateGraphqlQuery() {
+    const invoke = window.getInvoke ? window.getInvoke() : null;
+    const query = document.getElementById('graphqlQuery')?.value || '';
+    if (!invoke || !query.trim()) return;
+    try {
+        const errors = await invoke('validate_graphql_query', { query, url: state.request.url });
+        if (errors.length > 0) {
+            setGraphqlStatus(errors.join('\n'), true);
+        } else {
+            const hints = graphqlFieldHints(query);
+            setGraphqlStatus(hints.length > 0 ? `Fields: ${hints.join(', ')}` : 'Query is valid');
+        }
+    } catch (e) {
+        setGraphqlStatus(String(e), true);
+    }
+}
+
+/** Field names of the schema starting with the word being typed */
+function graphqlFieldHints(query) {
+    const word = (query.match(/[A-Za-z_][A-Za-z0-9_]*$/) || [''])[0];
+    if (!state.graphqlSchema || !word) return [];
+    const names = new Set();
+    state.graphqlSchema.types
+        .filter(type => !type.name.startsWith('__'))
+        .forEach(type => (type.fields || []).forEach(field => {
+            if (field.name.startsWith(word) && field.name !== word) names.add(field.name);
+        }));
+    return [...names].slice(0, 10);
+}
+
+function setGraphqlStatus(message, isError = false) {
+    const status = document.getElementById('graphqlStatus');
+    if (!status) return;
+    status.textContent = message;
+    status.classList.toggle('error', isError);
+}
+
 function renderFormData() {
     const container = document.getElementById('formDataList');
     if (!container) return;
@@ -479,7 +613,7 @@ async function handleSaveToCollection() {
     if (index >= 0) {
         const name = prompt('Request name:');
         if (name) {
-            state.collections[index].requests.push({ ...structuredClone(state.request), name });
+            state.collections[index].requests.push({ ...requestForBackend(), name });
             saveCollections();
             renderCollections();
 
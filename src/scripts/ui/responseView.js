@@ -16,27 +16,41 @@ function renderResponse(response) {
     // Meta info
     responseTime.textContent = `${response.time}ms`;
     responseSize.textContent = `${response.size} KB`;
+    renderTlsInfo(response.tls);
+    setBodySavable(response === state.storedBodyResponse);
+
+    // Problems that did not block the send, e.g. fields missing from the cached GraphQL schema
+    const warnings = document.getElementById('responseWarnings');
+    if (warnings) {
+        warnings.style.display = response.warnings && response.warnings.length ? '' : 'none';
+        warnings.title = (response.warnings || []).join('\n');
+    }
+
+    const proxyItem = document.getElementById('responseProxyItem');
+    if (proxyItem) {
+        proxyItem.style.display = response.proxy ? '' : 'none';
+        document.getElementById('responseProxy').textContent = response.proxy || '';
+    }
 
     // Detect content type
     const contentType = getContentType(response.headers);
-    const isJson = contentType && contentType.includes('application/json');
     const isXml = contentType && (contentType.includes('application/xml') || contentType.includes('text/xml'));
     const isHtml = contentType && contentType.includes('text/html');
 
-    // Body
-    if (response.body) {
-        if (isPrettyPrint) {
-            if (isJson && typeof response.body === 'object') {
-                responseBody.innerHTML = formatJSON(response.body);
-            } else if (isXml) {
-                responseBody.textContent = formatXML(response.body);
-            } else if (isHtml) {
-                responseBody.innerHTML = response.body;
-            } else {
-                responseBody.textContent = response.body;
-            }
+    // Body is typed: { kind: 'json', value } | { kind: 'text', text, charset } | { kind: 'binary', base64, mime }
+    const body = response.body || {};
+    if (body.kind === 'binary') {
+        const bytes = Math.floor(body.base64.length * 3 / 4);
+        responseBody.textContent = `(Binary ${body.mime}, ~${bytes} bytes — use Save to write it to a file)`;
+    } else if (body.kind === 'json') {
+        responseBody.innerHTML = isPrettyPrint ? formatJSON(body.value) : escapeHtml(JSON.stringify(body.value));
+    } else if (body.text) {
+        if (isPrettyPrint && isXml) {
+            responseBody.textContent = formatXML(body.text);
+        } else if (isPrettyPrint && isHtml) {
+            responseBody.innerHTML = body.text;
         } else {
-            responseBody.textContent = typeof response.body === 'string' ? response.body : JSON.stringify(response.body);
+            responseBody.textContent = body.text;
         }
     } else {
         responseBody.textContent = '(No content)';
@@ -49,6 +63,34 @@ function renderResponse(response) {
     }
 }
 
+// Only the last response sent has its raw bytes kept by the backend, so
+// responses restored from history or streamed can't be saved
+function setBodySavable(savable) {
+    const saveBodyBtn = document.getElementById('saveBodyBtn');
+    if (!saveBodyBtn) return;
+    saveBodyBtn.disabled = !savable;
+    saveBodyBtn.title = savable
+        ? 'Save the raw response body to a file'
+        : 'Only the body of the last response sent can be saved';
+}
+
+// Negotiated protocol and cipher; the certificate chain shows on hover
+function renderTlsInfo(tls) {
+    const item = document.getElementById('responseTlsItem');
+    const value = document.getElementById('responseTls');
+    const warning = document.getElementById('responseTlsWarning');
+    if (!item || !value || !warning) return;
+
+    item.style.display = tls ? '' : 'none';
+    warning.style.display = tls && tls.verificationSkipped ? '' : 'none';
+    if (!tls) return;
+
+    value.textContent = `${tls.protocol} ${tls.cipher}`;
+    item.title = (tls.certificates || []).map((cert, i) =>
+        `${i}: ${cert.subject}\n   issued by ${cert.issuer}\n   valid ${cert.notBefore} to ${cert.notAfter}\n   SHA-256 ${cert.sha256}`
+    ).join('\n');
+}
+
 function getContentType(headers) {
     if (!headers) return null;
     const contentType = Object.keys(headers).find(key => key.toLowerCase() === 'content-type');
@@ -64,6 +106,7 @@ function formatXML(xml) {
 function initResponseControls() {
     const prettyPrintBtn = document.getElementById('prettyPrintBtn');
     const rawViewBtn = document.getElementById('rawViewBtn');
+    const saveBodyBtn = document.getElementById('saveBodyBtn');
 
     if (prettyPrintBtn) {
         prettyPrintBtn.addEventListener('click', () => {
@@ -78,6 +121,18 @@ function initResponseControls() {
             if (state.response) renderResponse(state.response);
         });
     }
+
+    if (saveBodyBtn) {
+        saveBodyBtn.addEventListener('click', async () => {
+            const invoke = window.getInvoke ? window.getInvoke() : null;
+            if (!invoke || !state.response || state.response !== state.storedBodyResponse) return;
+            try {
+                await invoke('save_response_body');
+            } catch (error) {
+                alert('Failed to save response: ' + error);
+            }
+        });
+    }
 }
 
 function formatJSON(obj) {
@@ -714,7 +714,7 @@ function loadCollectionRequest(req) {
     // Update UI
     document.getElementById('methodSelect').value = req.method;
     document.getElementById('urlInput').value = req.url;
-    document.querySelector('.body-editor').value = req.body || '';
+    loadBodyIntoEditor(req.body);
     renderParams();
     renderHeaders();
     renderAuth();
@@ -789,12 +789,14 @@ function loadHistoryItem(item) {
     
     // Ensure all required fields exist (merge with defaults)
     state.request = {
+        ...restoredRequest,
         method: restoredRequest.method || 'GET',
         url: restoredRequest.url || '',
         params: restoredRequest.params || [],
         headers: restoredRequest.headers || [],
         body: restoredRequest.body || '',
         auth: {
+            ...restoredRequest.auth,
             type: restoredRequest.auth?.type || 'none',
             token: restoredRequest.auth?.token || '',
             apiKey: restoredRequest.auth?.apiKey || '',
@@ -815,7 +817,7 @@ function loadHistoryItem(item) {
     
     if (methodSelect) methodSelect.value = state.request.method.toUpperCase();
     if (urlInput) urlInput.value = state.request.url;
-    if (bodyEditor) bodyEditor.value = state.request.body;
+    if (bodyEditor) loadBodyIntoEditor(state.request.body);
     
     // Update tabs and dynamic lists
     if (typeof renderParams === 'function') renderParams();
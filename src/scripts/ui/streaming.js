@@ -0,0 +1,83 @@
+// Streamed responses: the body is shown as it arrives instead of after it ends
+
+function shouldStream() {
+    if (document.getElementById('streamToggle')?.checked) return true;
+    // Event streams are always shown live
+    return state.request.headers.some(h =>
+        h.enabled && h.key.toLowerCase() === 'accept' && h.value.includes('text/event-stream')
+    );
+}
+
+function initStreaming() {
+    const cancelBtn = document.getElementById('cancelStreamBtn');
+    if (cancelBtn) {
+        cancelBtn.addEventListener('click', async () => {
+            const invoke = window.getInvoke ? window.getInvoke() : null;
+            if (!invoke || !state.streamId) return;
+            try {
+                await invoke('cancel_stream', { streamId: state.streamId });
+            } catch (e) {
+                console.error('Failed to cancel stream:', e);
+            }
+        });
+    }
+
+    const listen = window.__TAURI__?.event?.listen;
+    if (listen) listen('stream-event', (event) => handleStreamEvent(event.payload));
+}
+
+async function handleStreamingSend() {
+    const invoke = window.getInvoke ? window.getInvoke() : null;
+    if (!invoke) {
+        alert('Tauri invoke not found. Are you running in a web browser?');
+        return;
+    }
+    if (state.streamId) {
+        alert('A stream is already running; cancel it first');
+        return;
+    }
+
+    try {
+        const start = await invoke('send_streaming_request', { req: requestForBackend() });
+        state.streamId = start.streamId;
+
+        const statusBadge = document.getElementById('statusBadge');
+        statusBadge.className = `status-badge status-${Math.floor(start.status / 100)}xx`;
+        statusBadge.textContent = `${start.status} ${start.statusText}`;
+        document.getElementById('responseTime').textContent = 'streaming…';
+        document.getElementById('responseSize').textContent = '—';
+        document.getElementById('responseBody').textContent = '';
+        setBodySavable(false);
+        document.getElementById('responseHeaders').innerHTML = formatJSON(start.headers);
+        document.getElementById('cancelStreamBtn').style.display = 'inline-block';
+        document.getElementById('responseViewer').style.display = 'flex';
+    } catch (error) {
+        console.error('Request failed:', error);
+        alert('Request failed: ' + (error.message || error));
+    }
+}
+
+function handleStreamEvent(event) {
+    if (!event || event.streamId !== state.streamId) return;
+    const responseBody = document.getElementById('responseBody');
+
+    if (event.type === 'message') {
+        const entry = event.entry;
+        if (entry.kind === 'event') {
+            const id = entry.id ? ` #${entry.id}` : '';
+            responseBody.textContent += `[${entry.elapsedMs}ms] ${entry.event}${id}\n${entry.data}\n\n`;
+        } else if (entry.kind === 'text') {
+            responseBody.textContent += entry.text;
+        } else {
+            responseBody.textContent += `(binary chunk, ~${Math.floor(entry.base64.length * 3 / 4)} bytes)\n`;
+        }
+    } else if (event.type === 'error') {
+        responseBody.textContent += `\n${event.message}\n`;
+    } else if (event.type === 'finished') {
+        state.streamId = null;
+        document.getElementById('cancelStreamBtn').style.display = 'none';
+        document.getElementById('responseTime').textContent = event.cancelled ? 'cancelled' : 'done';
+        // The full response and transcript are now in history
+        loadHistory().then(() => renderHistory());
+    }
+}
@@ -0,0 +1,109 @@
+// WebSocket sessions: ws:// and wss:// URLs connect instead of sending a request
+
+function isWebSocketUrl(url) {
+    return /^wss?:\/\//i.test((url || '').trim());
+}
+
+function initWebSocketPanel() {
+    const sendTextBtn = document.getElementById('wsSendTextBtn');
+    const sendBinaryBtn = document.getElementById('wsSendBinaryBtn');
+    const disconnectBtn = document.getElementById('wsDisconnectBtn');
+
+    if (sendTextBtn) sendTextBtn.addEventListener('click', () => sendWebSocketFrame('text'));
+    if (sendBinaryBtn) sendBinaryBtn.addEventListener('click', () => sendWebSocketFrame('binary'));
+    if (disconnectBtn) {
+        disconnectBtn.addEventListener('click', async () => {
+            const invoke = window.getInvoke ? window.getInvoke() : null;
+            if (!invoke || !state.wsSessionId) return;
+            try {
+                await invoke('ws_close', { sessionId: state.wsSessionId });
+            } catch (e) {
+                appendWebSocketLine('error', String(e));
+            }
+        });
+    }
+
+    const listen = window.__TAURI__?.event?.listen;
+    if (listen) listen('ws-event', (event) => handleWebSocketEvent(event.payload));
+}
+
+async function handleWebSocketConnect() {
+    const invoke = window.getInvoke ? window.getInvoke() : null;
+    if (!invoke) {
+        alert('Tauri invoke not found. Are you running in a web browser?');
+        return;
+    }
+    if (state.wsSessionId) {
+        alert('A WebSocket session is already open; disconnect it first');
+        return;
+    }
+
+    const panel = document.getElementById('wsPanel');
+    const log = document.getElementById('wsLog');
+    if (log) log.innerHTML = '';
+    if (panel) panel.style.display = 'flex';
+
+    try {
+        const handshake = await invoke('ws_connect', { req: requestForBackend() });
+        state.wsSessionId = handshake.sessionId;
+        setWebSocketStatus(`Connected (${handshake.status})`);
+    } catch (e) {
+        setWebSocketStatus('Not connected');
+        appendWebSocketLine('error', String(e));
+    }
+}
+
+async function sendWebSocketFrame(kind) {
+    const invoke = window.getInvoke ? window.getInvoke() : null;
+    const input = document.getElementById('wsMessageInput');
+    if (!invoke || !input || !state.wsSessionId) return;
+
+    const frame = kind === 'binary'
+        ? { kind: 'binary', base64: input.value.trim() }
+        : { kind: 'text', text: input.value };
+    try {
+        await invoke('ws_send', { sessionId: state.wsSessionId, frame });
+        input.value = '';
+    } catch (e) {
+        appendWebSocketLine('error', String(e));
+    }
+}
+
+function handleWebSocketEvent(event) {
+    if (!event || event.sessionId !== state.wsSessionId) return;
+
+    if (event.type === 'message') {
+        const entry = event.entry;
+        const arrow = entry.direction === 'sent' ? '→' : '←';
+        appendWebSocketLine(entry.direction, `${arrow} [${entry.elapsedMs}ms] ${describeFrame(entry)}`);
+    } else if (event.type === 'error') {
+        appendWebSocketLine('error', event.message);
+    } else if (event.type === 'closed') {
+        const code = event.code ? ` ${event.code}` : '';
+        setWebSocketStatus(`Closed${code}${event.reason ? ': ' + event.reason : ''}`);
+        state.wsSessionId = null;
+        // The session's transcript is now in history
+        loadHistory().then(() => renderHistory());
+    }
+}
+
+function describeFrame(frame) {
+    if (frame.kind === 'text') return frame.text;
+    if (frame.kind === 'binary') return `(binary) ${frame.base64}`;
+    return `(close${frame.code ? ' ' + frame.code : ''}) ${frame.reason || ''}`;
+}
+
+function appendWebSocketLine(kind, text) {
+    const log = document.getElementById('wsLog');
+    if (!log) return;
+    const line = document.createElement('div');
+    line.className = `ws-line ws-${kind}`;
+    line.textContent = text;
+    log.appendChild(line);
+    log.scrollTop = log.scrollHeight;
+}
+
+function setWebSocketStatus(text) {
+    const status = document.getElementById('wsStatus');
+    if (status) status.textContent = text;
+}